@@ -0,0 +1,60 @@
+#!/usr/bin/env perl
+# Genera src/tablas_unicode.rs con los rangos de caracteres de cada categoría general, escritura
+# y propiedad binaria de Unicode usadas por las expresiones regulares.
+#
+# Uso (desde el directorio egrep): perl scripts/generar_tablas_unicode.pl > src/tablas_unicode.rs
+use strict;
+use warnings;
+use Unicode::UCD qw(prop_invlist prop_values prop_value_aliases);
+
+my $maximo = 0x110000;
+
+# Convierte una lista de inversión en rangos inclusivos.
+sub rangos {
+    my @lista = @_;
+    push @lista, $maximo if @lista % 2;
+    my @rangos;
+    for (my $i = 0; $i < @lista; $i += 2) {
+        push @rangos, sprintf("(0x%X, 0x%X)", $lista[$i], $lista[$i + 1] - 1);
+    }
+    return @rangos;
+}
+
+sub tabla {
+    my ($nombre_tabla, $descripcion, @entradas) = @_;
+    print "/// $descripcion\n";
+    print "#[rustfmt::skip]\n";
+    print "pub(crate) const $nombre_tabla: &[(&str, &[(u32, u32)])] = &[\n";
+    for my $entrada (@entradas) {
+        my ($nombre, @rangos) = @$entrada;
+        print "    (\"$nombre\", &[\n";
+        while (my @linea = splice(@rangos, 0, 6)) {
+            print "        ", join(", ", @linea), ",\n";
+        }
+        print "    ]),\n";
+    }
+    print "];\n";
+}
+
+my @categorias;
+for my $categoria (sort map { (prop_value_aliases("gc", $_))[0] } prop_values("gc")) {
+    next if length($categoria) != 2 || $categoria eq "Cn" || $categoria eq "LC";
+    push @categorias, [$categoria, rangos(prop_invlist("gc=$categoria"))];
+}
+
+my @escrituras;
+for my $escritura (sort map { (prop_value_aliases("sc", $_))[1] } prop_values("sc")) {
+    next if $escritura eq "Unknown";
+    push @escrituras, [$escritura, rangos(prop_invlist("sc=$escritura"))];
+}
+
+my @binarias = map { [$_, rangos(prop_invlist($_))] } ("White_Space", "Alphabetic");
+
+print "//! Tablas de propiedades de Unicode ", Unicode::UCD::UnicodeVersion(), ".\n";
+print "//!\n";
+print "//! Generado por scripts/generar_tablas_unicode.pl: no editar a mano.\n\n";
+tabla("CATEGORIAS", "Rangos de caracteres de cada categoría general (salvo Cn, los no asignados).", @categorias);
+print "\n";
+tabla("ESCRITURAS", "Rangos de caracteres de cada escritura.", @escrituras);
+print "\n";
+tabla("PROPIEDADES_BINARIAS", "Rangos de caracteres de cada propiedad binaria.", @binarias);
@@ -0,0 +1,211 @@
+use std::collections::{HashMap, VecDeque};
+
+/// Nodo del trie de patrones: sus transiciones por byte, el nodo al que se pasa si no hay
+/// transición (el del sufijo más largo que también es prefijo de algún patrón) y los largos de
+/// los patrones que terminan en él (incluyendo los que son sufijo de su prefijo).
+#[derive(Debug, Default)]
+struct Nodo {
+    transiciones: HashMap<u8, usize>,
+    falla: usize,
+    largos_patrones: Vec<usize>,
+}
+
+/// Búsqueda simultánea de varias cadenas de bytes con el algoritmo de Aho–Corasick.
+///
+/// Los patrones se guardan en un trie con enlaces de falla, por lo que el valor se recorre una
+/// única vez, sin importar cuántos patrones se busquen.
+#[derive(Debug)]
+pub struct AhoCorasick {
+    nodos: Vec<Nodo>,
+    largo_maximo: usize,
+    ignorar_mayusculas_ascii: bool,
+}
+
+impl AhoCorasick {
+    /// Creación del buscador de varios patrones.
+    pub fn new<'a>(patrones: impl IntoIterator<Item = &'a [u8]>) -> Self {
+        Self::con_mayusculas_ascii(patrones, false)
+    }
+
+    /// Creación del buscador de varios patrones que no distingue mayúsculas de minúsculas en los
+    /// caracteres ASCII (los demás bytes se comparan tal como son).
+    pub fn ignorando_mayusculas_ascii<'a>(patrones: impl IntoIterator<Item = &'a [u8]>) -> Self {
+        Self::con_mayusculas_ascii(patrones, true)
+    }
+
+    fn con_mayusculas_ascii<'a>(
+        patrones: impl IntoIterator<Item = &'a [u8]>,
+        ignorar_mayusculas_ascii: bool,
+    ) -> Self {
+        let mut nodos = vec![Nodo::default()];
+        let mut largo_maximo = 0;
+        for patron in patrones {
+            let mut actual = 0;
+            for byte in patron {
+                let byte = if ignorar_mayusculas_ascii {
+                    byte.to_ascii_lowercase()
+                } else {
+                    *byte
+                };
+                actual = match nodos[actual].transiciones.get(&byte) {
+                    Some(siguiente) => *siguiente,
+                    None => {
+                        nodos.push(Nodo::default());
+                        let siguiente = nodos.len() - 1;
+                        nodos[actual].transiciones.insert(byte, siguiente);
+                        siguiente
+                    }
+                };
+            }
+            nodos[actual].largos_patrones.push(patron.len());
+            largo_maximo = largo_maximo.max(patron.len());
+        }
+        Self::calcular_fallas(&mut nodos);
+        Self {
+            nodos,
+            largo_maximo,
+            ignorar_mayusculas_ascii,
+        }
+    }
+
+    /// Calcula el enlace de falla de cada nodo, recorriendo el trie en anchura, y agrega a cada
+    /// nodo los patrones del nodo al que enlaza.
+    fn calcular_fallas(nodos: &mut [Nodo]) {
+        let mut pendientes: VecDeque<usize> = nodos[0].transiciones.values().copied().collect();
+        while let Some(actual) = pendientes.pop_front() {
+            let transiciones: Vec<(u8, usize)> = nodos[actual]
+                .transiciones
+                .iter()
+                .map(|(byte, hijo)| (*byte, *hijo))
+                .collect();
+            for (byte, hijo) in transiciones {
+                let mut falla = nodos[actual].falla;
+                let falla_hijo = loop {
+                    if let Some(siguiente) = nodos[falla].transiciones.get(&byte) {
+                        break *siguiente;
+                    }
+                    if falla == 0 {
+                        break 0;
+                    }
+                    falla = nodos[falla].falla;
+                };
+                nodos[hijo].falla = falla_hijo;
+                let largos_falla = nodos[falla_hijo].largos_patrones.clone();
+                nodos[hijo].largos_patrones.extend(largos_falla);
+                pendientes.push_back(hijo);
+            }
+        }
+    }
+
+    /// Búsqueda en un valor, desde una posición, de la aparición de alguno de los patrones que
+    /// comienza primero (y, entre las que comienzan allí, la más larga) y que es aceptada por
+    /// una función que recibe sus posiciones de inicio y fin.
+    ///
+    /// Devuelve las posiciones de inicio y fin de la aparición, o None si no hay ninguna.
+    pub fn buscar(
+        &self,
+        valor: &[u8],
+        desde: usize,
+        aceptar: impl Fn(usize, usize) -> bool,
+    ) -> Option<(usize, usize)> {
+        if desde > valor.len() {
+            return None;
+        }
+        let mut mejor: Option<(usize, usize)> = None;
+        considerar(&mut mejor, &self.nodos[0].largos_patrones, desde, &aceptar);
+        let mut actual = 0;
+        for (posicion, byte) in valor.iter().enumerate().skip(desde) {
+            // Ninguna aparición que termine más adelante puede comenzar antes de la encontrada.
+            if mejor.is_some_and(|(inicio_mejor, _)| posicion >= inicio_mejor + self.largo_maximo) {
+                break;
+            }
+            let byte = if self.ignorar_mayusculas_ascii {
+                byte.to_ascii_lowercase()
+            } else {
+                *byte
+            };
+            actual = loop {
+                if let Some(siguiente) = self.nodos[actual].transiciones.get(&byte) {
+                    break *siguiente;
+                }
+                if actual == 0 {
+                    break 0;
+                }
+                actual = self.nodos[actual].falla;
+            };
+            considerar(
+                &mut mejor,
+                &self.nodos[actual].largos_patrones,
+                posicion + 1,
+                &aceptar,
+            );
+        }
+        mejor
+    }
+}
+
+/// Reemplaza la mejor aparición encontrada por alguna de las que terminan en una posición (con
+/// los largos recibidos), si comienza antes (o en la misma posición, pero es más larga) y es
+/// aceptada.
+fn considerar(
+    mejor: &mut Option<(usize, usize)>,
+    largos: &[usize],
+    fin: usize,
+    aceptar: &impl Fn(usize, usize) -> bool,
+) {
+    for largo in largos {
+        let inicio = fin - largo;
+        let es_mejor = mejor.is_none_or(|(inicio_mejor, fin_mejor)| {
+            (inicio < inicio_mejor) | ((inicio == inicio_mejor) & (fin > fin_mejor))
+        });
+        if es_mejor && aceptar(inicio, fin) {
+            *mejor = Some((inicio, fin));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn busca_aparicion_mas_temprana_y_mas_larga() {
+        let patrones: [&[u8]; 4] = [b"he", b"she", b"hers", b"his"];
+        let buscador = AhoCorasick::new(patrones);
+
+        assert_eq!(buscador.buscar(b"ushers", 0, |_, _| true), Some((1, 4)));
+        assert_eq!(buscador.buscar(b"ushers", 2, |_, _| true), Some((2, 6)));
+        assert_eq!(buscador.buscar(b"ahi", 0, |_, _| true), None);
+    }
+
+    #[test]
+    fn busca_ignorando_mayusculas_ascii() {
+        let patrones: [&[u8]; 2] = [b"Hola", "\u{c1}rbol".as_bytes()];
+        let buscador = AhoCorasick::ignorando_mayusculas_ascii(patrones);
+
+        assert_eq!(buscador.buscar(b"xhOLA", 0, |_, _| true), Some((1, 5)));
+        assert_eq!(
+            buscador.buscar("\u{c1}RBOL".as_bytes(), 0, |_, _| true),
+            Some((0, 6))
+        );
+        assert_eq!(
+            buscador.buscar("\u{e1}rbol".as_bytes(), 0, |_, _| true),
+            None
+        );
+    }
+
+    #[test]
+    fn busca_aparicion_aceptada() {
+        let patrones: [&[u8]; 2] = [b"ab", b"abc"];
+        let buscador = AhoCorasick::new(patrones);
+
+        assert_eq!(
+            buscador.buscar(b"abcab", 0, |_, fin| fin != 3),
+            Some((0, 2))
+        );
+        assert_eq!(
+            buscador.buscar(b"abcab", 0, |inicio, _| inicio > 0),
+            Some((3, 5))
+        );
+    }
+}
@@ -0,0 +1,625 @@
+use crate::{
+    codificacion::Codificacion, delimitacion::Delimitacion, error_argumentos::ErrorArgumentos,
+    lectura_archivos::OpcionesLectura, patrones::FuentePatrones, syntax::Syntax,
+};
+
+/// Argumentos de egrep recibidos por línea de comando.
+///
+/// Se reciben el patrón y la ruta de uno o más archivos a evaluar, y opcionalmente:
+///
+/// * `-e PATRON` (o `--regexp PATRON`): patrón a evaluar. Puede recibirse más de una vez.
+///
+/// * `-f ARCHIVO` (o `--file ARCHIVO`): archivo con un patrón por línea ("-" para la entrada
+///   estándar). Puede recibirse más de una vez.
+///
+/// Si se recibe `-e` o `-f`, el primer argumento que no es una opción ya no es el patrón, sino
+/// el primer archivo a evaluar. Se seleccionan las líneas que matchean con alguno de los patrones.
+///
+/// * `-E` (o `--extended-regexp`): los patrones son expresiones regulares extendidas (por defecto).
+///
+/// * `-G` (o `--basic-regexp`): los patrones son expresiones regulares básicas.
+///
+/// * `-P` (o `--perl-regexp`): los patrones son expresiones regulares compatibles con Perl.
+///
+/// * `-F` (o `--fixed-strings`): los patrones son cadenas fijas, sin metacaracteres. Entre `-E`,
+///   `-G`, `-P` y `-F` vale la última recibida.
+///
+/// * `-i` (o `--ignore-case`): no se distinguen mayúsculas de minúsculas.
+///
+/// * `--unicode`: las clases de caracter (ej.: `[[:alpha:]]`, `\w`) incluyen caracteres no ASCII.
+///
+/// * `-w` (o `--word-regexp`): solo se consideran las coincidencias que forman palabras completas.
+///
+/// * `-x` (o `--line-regexp`): solo se consideran las coincidencias con la línea completa. Tiene
+///   prioridad sobre `-w`.
+///
+/// * `-U` (o `--multiline`): los patrones se evalúan sobre el contenido completo de cada archivo,
+///   por lo que una coincidencia puede abarcar varias líneas (con `\n` o bracket expresiones
+///   negadas, ya que el comodín no matchea con '\n'). `^` y `$` matchean al inicio y al final de
+///   cada línea. Se seleccionan todas las líneas que toca alguna coincidencia. No admite `--replace`
+///   ni `--crlf` (los anclajes y el comodín no tratan al '\r' como parte del terminador).
+///
+/// * `-o` (o `--only-matching`): se imprime cada coincidencia de las líneas seleccionadas en vez
+///   de las líneas completas.
+///
+/// * `--replace PLANTILLA` (o `--replace=PLANTILLA`): en las líneas seleccionadas (o en las
+///   coincidencias, con `-o`) se imprime cada coincidencia reemplazada por la plantilla, en la que
+///   `$1`, `${nombre}` o `\1` se expanden con lo que matcheó cada grupo y `$0` con la coincidencia
+///   completa. No se aplica al invertir la selección. Como con `-o` y el color, las coincidencias
+///   vacías no se reemplazan (a diferencia de
+///   [Regex::replace_all](crate::regex::Regex::replace_all)).
+///
+/// * `--in-place`: en vez de imprimir resultados, se reescribe cada archivo con las coincidencias
+///   de todas sus líneas reemplazadas. Requiere `--replace`, y no admite `-v` ni `-m`.
+///
+/// * `--backup SUFFIX` (o `--backup=SUFFIX`): al reescribir un archivo, se guarda una copia del
+///   original con el mismo nombre seguido de SUFFIX. Requiere `--in-place`.
+///
+/// * `--dry-run`: no se reescribe ningún archivo, sino que se imprimen las diferencias que
+///   resultarían de hacerlo, en formato unificado. Requiere `--in-place`.
+///
+/// * `--encoding CODIFICACION` (o `--encoding=CODIFICACION`): codificación de los archivos. Si no se
+///   indica, se detecta a través del BOM de cada archivo.
+///
+/// * `--crlf`: el terminador de línea es "\r\n", por lo que el anclaje final matchea antes del '\r'.
+///   No admite `-U`.
+///
+/// * `-z` (o `--null-data`): las líneas se separan por NUL ('\0') en vez de '\n', tanto en la
+///   entrada como en la salida.
+///
+/// * `-Z` (o `--null`): luego de cada nombre de archivo impreso se escribe NUL en vez de ':'
+///   (o en vez de '\n', al listar archivos).
+///
+/// * `-v` (o `--invert-match`): se seleccionan las líneas que no matchean.
+///
+/// * `-c` (o `--count`): se imprime la cantidad de líneas seleccionadas de cada archivo.
+///
+/// * `-l` (o `--files-with-matches`): se imprime el nombre de cada archivo con alguna línea
+///   seleccionada, dejando de leerlo en la primera.
+///
+/// * `-L` (o `--files-without-match`): se imprime el nombre de cada archivo sin líneas
+///   seleccionadas. Entre `-l` y `-L` vale la última recibida, y ambas tienen prioridad sobre `-c`.
+///
+/// * `-m NUM` (o `--max-count NUM`): se deja de leer cada archivo luego de NUM líneas
+///   seleccionadas (salvo por sus líneas de contexto posterior).
+///
+/// * `-A NUM`, `-B NUM` y `-C NUM` (o `--after-context`, `--before-context` y `--context`): se
+///   imprimen NUM líneas de contexto posteriores, anteriores o ambas a cada línea seleccionada.
+///
+/// Las opciones cortas pueden agruparse (ej.: `-zZ`). Luego de `--`, todos los argumentos se toman
+/// como patrón y archivos.
+#[derive(Debug, PartialEq, Default)]
+pub struct Argumentos {
+    pub patrones: Vec<FuentePatrones>,
+    pub archivos: Vec<String>,
+    pub sintaxis: Syntax,
+    pub cadenas_fijas: bool,
+    pub ignorar_mayusculas: bool,
+    pub unicode: bool,
+    pub delimitacion: Delimitacion,
+    pub multilinea: bool,
+    pub solo_coincidencias: bool,
+    pub reemplazo: Option<String>,
+    pub en_el_lugar: bool,
+    pub sufijo_respaldo: Option<String>,
+    pub simular: bool,
+    pub lectura: OpcionesLectura,
+    pub nombre_nulo: bool,
+    pub invertir: bool,
+    pub contar: bool,
+    pub listar_archivos: Option<ListadoArchivos>,
+    pub maximo_seleccionadas: Option<usize>,
+    pub contexto_anterior: usize,
+    pub contexto_posterior: usize,
+}
+
+/// Archivos a listar en vez de imprimir sus líneas.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ListadoArchivos {
+    ConSeleccion,
+    SinSeleccion,
+}
+
+impl Argumentos {
+    /// Creación de los argumentos a través de los recibidos por línea de comando
+    /// (el primero es el nombre del programa y se ignora).
+    ///
+    /// # Errores
+    ///
+    /// * Si falta el patrón o algún archivo.
+    ///
+    /// * Si se recibe una opción desconocida, o una opción que requiere un valor sin este.
+    ///
+    /// * Si el nombre de la codificación es desconocido.
+    ///
+    /// * Si se recibe una opción sin otra que requiere (ej.: `--in-place` sin `--replace`), o
+    ///   junto con otra que no admite (`-U` con `--replace` o `--crlf`, `--in-place` con `-v` o `-m`).
+    pub fn new(args: &[String]) -> Result<Self, ErrorArgumentos> {
+        let mut argumentos = Self::default();
+        let mut posicionales: Vec<String> = Vec::new();
+        let mut solo_posicionales = false;
+        let mut iter_args = args.iter().skip(1);
+
+        while let Some(arg) = iter_args.next() {
+            if solo_posicionales || !arg.starts_with('-') || arg == "-" {
+                posicionales.push(arg.to_string());
+            } else if arg == "--" {
+                solo_posicionales = true;
+            } else if arg.starts_with("--") {
+                let (opcion, valor_en_linea) = match arg.split_once('=') {
+                    Some((opcion, valor)) => (opcion, Some(valor.to_string())),
+                    None => (arg.as_str(), None),
+                };
+                let valor = Self::valor_opcion(opcion, valor_en_linea, &mut iter_args)?;
+                argumentos.aplicar_opcion(opcion, valor)?;
+            } else {
+                argumentos.aplicar_opciones_cortas(arg, &mut iter_args)?;
+            }
+        }
+
+        let mut posicionales = posicionales.into_iter();
+        if argumentos.patrones.is_empty() {
+            let patron = posicionales
+                .next()
+                .ok_or(ErrorArgumentos::ArgumentosFaltantes)?;
+            argumentos.patrones.push(FuentePatrones::Patron(patron));
+        }
+        argumentos.archivos = posicionales.collect();
+        if argumentos.archivos.is_empty() {
+            return Err(ErrorArgumentos::ArgumentosFaltantes);
+        }
+        argumentos.validar_opciones_requeridas()?;
+        Ok(argumentos)
+    }
+
+    /// Verifica que se hayan recibido las opciones que requieren las de reescritura de archivos,
+    /// que no se haya recibido `--replace` ni `--crlf` en modo multilínea (en el que los anclajes
+    /// y el comodín no tratan al '\r' como parte del terminador) y que no se haya recibido
+    /// `--in-place` junto con `-v` o `-m` (la reescritura reemplaza todas las coincidencias de
+    /// todas las líneas, por lo que no podría respetarlas).
+    fn validar_opciones_requeridas(&self) -> Result<(), ErrorArgumentos> {
+        let incompatibles = [
+            (self.multilinea, "-U", self.reemplazo.is_some(), "--replace"),
+            (self.multilinea, "-U", self.lectura.crlf, "--crlf"),
+            (self.en_el_lugar, "--in-place", self.invertir, "-v"),
+            (
+                self.en_el_lugar,
+                "--in-place",
+                self.maximo_seleccionadas.is_some(),
+                "-m",
+            ),
+        ];
+        if let Some((_, opcion, _, otra)) = incompatibles
+            .iter()
+            .find(|(recibida, _, otra_recibida, _)| *recibida && *otra_recibida)
+        {
+            return Err(ErrorArgumentos::OpcionesIncompatibles(
+                opcion.to_string(),
+                otra.to_string(),
+            ));
+        }
+        let requeridas = [
+            (
+                self.en_el_lugar,
+                "--in-place",
+                self.reemplazo.is_some(),
+                "--replace",
+            ),
+            (
+                self.sufijo_respaldo.is_some(),
+                "--backup",
+                self.en_el_lugar,
+                "--in-place",
+            ),
+            (self.simular, "--dry-run", self.en_el_lugar, "--in-place"),
+        ];
+        match requeridas
+            .iter()
+            .find(|(recibida, _, requerida_recibida, _)| *recibida && !requerida_recibida)
+        {
+            Some((_, opcion, _, requerida)) => Err(ErrorArgumentos::OpcionRequerida(
+                opcion.to_string(),
+                requerida.to_string(),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Aplica un grupo de opciones cortas (ej.: `-zZ`).
+    ///
+    /// Si una de las opciones requiere un valor, este es el resto del grupo o, si no hay resto,
+    /// el siguiente argumento.
+    fn aplicar_opciones_cortas<'a>(
+        &mut self,
+        grupo: &str,
+        iter_args: &mut impl Iterator<Item = &'a String>,
+    ) -> Result<(), ErrorArgumentos> {
+        for (i, c) in grupo.char_indices().skip(1) {
+            let opcion = format!("-{}", c);
+            if Self::requiere_valor(&opcion) {
+                let resto = &grupo[i + c.len_utf8()..];
+                let valor_en_linea = (!resto.is_empty()).then(|| resto.to_string());
+                let valor = Self::valor_opcion(&opcion, valor_en_linea, iter_args)?;
+                return self.aplicar_opcion(&opcion, valor);
+            }
+            self.aplicar_opcion(&opcion, None)?;
+        }
+        Ok(())
+    }
+
+    /// Aplica una opción (con su valor, si lo requiere) a los argumentos.
+    fn aplicar_opcion(
+        &mut self,
+        opcion: &str,
+        valor: Option<String>,
+    ) -> Result<(), ErrorArgumentos> {
+        match (opcion, valor) {
+            ("-e" | "--regexp", Some(patron)) => self.patrones.push(FuentePatrones::Patron(patron)),
+            ("-f" | "--file", Some(archivo)) => {
+                self.patrones.push(FuentePatrones::Archivo(archivo))
+            }
+            ("-E" | "--extended-regexp", None) => {
+                self.sintaxis = Syntax::Ere;
+                self.cadenas_fijas = false;
+            }
+            ("-G" | "--basic-regexp", None) => {
+                self.sintaxis = Syntax::Bre;
+                self.cadenas_fijas = false;
+            }
+            ("-P" | "--perl-regexp", None) => {
+                self.sintaxis = Syntax::Perl;
+                self.cadenas_fijas = false;
+            }
+            ("-F" | "--fixed-strings", None) => self.cadenas_fijas = true,
+            ("-i" | "--ignore-case", None) => self.ignorar_mayusculas = true,
+            ("--unicode", None) => self.unicode = true,
+            ("-w" | "--word-regexp", None) => {
+                if self.delimitacion != Delimitacion::Linea {
+                    self.delimitacion = Delimitacion::Palabra
+                }
+            }
+            ("-x" | "--line-regexp", None) => self.delimitacion = Delimitacion::Linea,
+            ("-U" | "--multiline", None) => self.multilinea = true,
+            ("-o" | "--only-matching", None) => self.solo_coincidencias = true,
+            ("--replace", Some(plantilla)) => self.reemplazo = Some(plantilla),
+            ("--in-place", None) => self.en_el_lugar = true,
+            ("--backup", Some(sufijo)) => self.sufijo_respaldo = Some(sufijo),
+            ("--dry-run", None) => self.simular = true,
+            ("--encoding", Some(nombre)) => {
+                self.lectura.codificacion = Some(Codificacion::new(&nombre)?)
+            }
+            ("--crlf", None) => self.lectura.crlf = true,
+            ("-z" | "--null-data", None) => self.lectura.terminador = b'\0',
+            ("-Z" | "--null", None) => self.nombre_nulo = true,
+            ("-v" | "--invert-match", None) => self.invertir = true,
+            ("-c" | "--count", None) => self.contar = true,
+            ("-l" | "--files-with-matches", None) => {
+                self.listar_archivos = Some(ListadoArchivos::ConSeleccion)
+            }
+            ("-L" | "--files-without-match", None) => {
+                self.listar_archivos = Some(ListadoArchivos::SinSeleccion)
+            }
+            ("-m" | "--max-count", Some(valor)) => {
+                self.maximo_seleccionadas = Some(Self::valor_numerico(opcion, &valor)?)
+            }
+            ("-A" | "--after-context", Some(valor)) => {
+                self.contexto_posterior = Self::valor_numerico(opcion, &valor)?
+            }
+            ("-B" | "--before-context", Some(valor)) => {
+                self.contexto_anterior = Self::valor_numerico(opcion, &valor)?
+            }
+            ("-C" | "--context", Some(valor)) => {
+                self.contexto_posterior = Self::valor_numerico(opcion, &valor)?;
+                self.contexto_anterior = self.contexto_posterior;
+            }
+            _ => return Err(ErrorArgumentos::OpcionDesconocida(opcion.to_string())),
+        }
+        Ok(())
+    }
+
+    fn requiere_valor(opcion: &str) -> bool {
+        matches!(
+            opcion,
+            "-e" | "--regexp"
+                | "-f"
+                | "--file"
+                | "--replace"
+                | "--backup"
+                | "--encoding"
+                | "-m"
+                | "--max-count"
+                | "-A"
+                | "--after-context"
+                | "-B"
+                | "--before-context"
+                | "-C"
+                | "--context"
+        )
+    }
+
+    fn valor_numerico(opcion: &str, valor: &str) -> Result<usize, ErrorArgumentos> {
+        valor
+            .parse()
+            .map_err(|_e| ErrorArgumentos::ValorInvalido(opcion.to_string(), valor.to_string()))
+    }
+
+    /// Devuelve el valor de una opción si esta lo requiere, que puede haberse recibido en el
+    /// mismo argumento (`--opcion=valor`, `-ovalor`) o en el siguiente.
+    fn valor_opcion<'a>(
+        opcion: &str,
+        valor_en_linea: Option<String>,
+        iter_args: &mut impl Iterator<Item = &'a String>,
+    ) -> Result<Option<String>, ErrorArgumentos> {
+        if !Self::requiere_valor(opcion) {
+            return match valor_en_linea {
+                Some(_) => Err(ErrorArgumentos::OpcionDesconocida(opcion.to_string())),
+                None => Ok(None),
+            };
+        }
+        match valor_en_linea {
+            Some(valor) => Ok(Some(valor)),
+            None => iter_args
+                .next()
+                .cloned()
+                .map(Some)
+                .ok_or_else(|| ErrorArgumentos::OpcionSinValor(opcion.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(valores: &[&str]) -> Vec<String> {
+        valores.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn patron_y_archivo() {
+        let argumentos = Argumentos::new(&args(&["egrep", "ab.cd", "archivo.txt"]));
+
+        assert_eq!(
+            argumentos,
+            Ok(Argumentos {
+                patrones: vec![FuentePatrones::Patron("ab.cd".to_string())],
+                archivos: vec!["archivo.txt".to_string()],
+                ..Argumentos::default()
+            })
+        );
+    }
+
+    #[test]
+    fn patron_y_varios_archivos() {
+        let argumentos = Argumentos::new(&args(&["egrep", "a", "b", "c"])).unwrap();
+
+        assert_eq!(argumentos.archivos, vec!["b".to_string(), "c".to_string()]);
+    }
+
+    #[test]
+    fn patrones_con_opciones() {
+        let argumentos = Argumentos::new(&args(&[
+            "egrep",
+            "-e",
+            "uno",
+            "-fpatrones.txt",
+            "-e",
+            "dos",
+            "b",
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            argumentos.patrones,
+            vec![
+                FuentePatrones::Patron("uno".to_string()),
+                FuentePatrones::Archivo("patrones.txt".to_string()),
+                FuentePatrones::Patron("dos".to_string()),
+            ]
+        );
+        assert_eq!(argumentos.archivos, vec!["b".to_string()]);
+    }
+
+    #[test]
+    fn opcion_de_codificacion() {
+        let argumentos1 =
+            Argumentos::new(&args(&["egrep", "--encoding", "latin1", "a", "b"])).unwrap();
+        let argumentos2 = Argumentos::new(&args(&["egrep", "a", "b", "--encoding=utf-16le"]));
+
+        assert_eq!(argumentos1.lectura.codificacion, Some(Codificacion::Latin1));
+        assert_eq!(
+            argumentos2.unwrap().lectura.codificacion,
+            Some(Codificacion::Utf16Le)
+        );
+    }
+
+    #[test]
+    fn opcion_crlf() {
+        let argumentos = Argumentos::new(&args(&["egrep", "--crlf", "a", "b"])).unwrap();
+
+        assert!(argumentos.lectura.crlf);
+    }
+
+    #[test]
+    fn opciones_nulas_agrupadas() {
+        let argumentos = Argumentos::new(&args(&["egrep", "-zZ", "a", "b"])).unwrap();
+
+        assert_eq!(argumentos.lectura.terminador, b'\0');
+        assert!(argumentos.nombre_nulo);
+    }
+
+    #[test]
+    fn opciones_de_listado_vale_la_ultima() {
+        let argumentos1 = Argumentos::new(&args(&["egrep", "-vc", "-l", "a", "b"])).unwrap();
+        let argumentos2 = Argumentos::new(&args(&["egrep", "-l", "-L", "a", "b"])).unwrap();
+
+        assert!(argumentos1.invertir);
+        assert!(argumentos1.contar);
+        assert_eq!(
+            argumentos1.listar_archivos,
+            Some(ListadoArchivos::ConSeleccion)
+        );
+        assert_eq!(
+            argumentos2.listar_archivos,
+            Some(ListadoArchivos::SinSeleccion)
+        );
+    }
+
+    #[test]
+    fn opciones_numericas() {
+        let argumentos = Argumentos::new(&args(&[
+            "egrep",
+            "-m5",
+            "-C",
+            "2",
+            "--after-context=3",
+            "a",
+            "b",
+        ]))
+        .unwrap();
+
+        assert_eq!(argumentos.maximo_seleccionadas, Some(5));
+        assert_eq!(argumentos.contexto_anterior, 2);
+        assert_eq!(argumentos.contexto_posterior, 3);
+        assert_eq!(
+            Argumentos::new(&args(&["egrep", "-m", "x", "a", "b"])),
+            Err(ErrorArgumentos::ValorInvalido(
+                "-m".to_string(),
+                "x".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn opciones_de_coincidencia() {
+        let argumentos = Argumentos::new(&args(&["egrep", "-Fiow", "a.b", "c"])).unwrap();
+
+        assert!(argumentos.cadenas_fijas);
+        assert!(argumentos.ignorar_mayusculas);
+        assert!(argumentos.solo_coincidencias);
+        assert_eq!(argumentos.delimitacion, Delimitacion::Palabra);
+        assert!(!argumentos.unicode);
+
+        let argumentos =
+            Argumentos::new(&args(&["egrep", "-x", "-w", "--unicode", "-U", "a", "b"])).unwrap();
+
+        assert_eq!(argumentos.delimitacion, Delimitacion::Linea);
+        assert!(argumentos.unicode);
+        assert!(argumentos.multilinea);
+
+        let argumentos =
+            Argumentos::new(&args(&["egrep", "a(b)", "--replace", "<$1>", "c"])).unwrap();
+
+        assert_eq!(argumentos.reemplazo, Some("<$1>".to_string()));
+        assert_eq!(argumentos.archivos, vec!["c".to_string()]);
+    }
+
+    #[test]
+    fn ultima_sintaxis_recibida() {
+        let argumentos = Argumentos::new(&args(&["egrep", "-FG", "a", "b"])).unwrap();
+
+        assert!(!argumentos.cadenas_fijas);
+        assert_eq!(argumentos.sintaxis, Syntax::Bre);
+
+        let argumentos =
+            Argumentos::new(&args(&["egrep", "-G", "--extended-regexp", "a", "b"])).unwrap();
+
+        assert_eq!(argumentos.sintaxis, Syntax::Ere);
+
+        let argumentos = Argumentos::new(&args(&["egrep", "-GF", "a", "b"])).unwrap();
+
+        assert!(argumentos.cadenas_fijas);
+
+        let argumentos = Argumentos::new(&args(&["egrep", "-FP", "a", "b"])).unwrap();
+
+        assert!(!argumentos.cadenas_fijas);
+        assert_eq!(argumentos.sintaxis, Syntax::Perl);
+    }
+
+    #[test]
+    fn errores_de_argumentos() {
+        assert_eq!(
+            Argumentos::new(&args(&["egrep", "a"])),
+            Err(ErrorArgumentos::ArgumentosFaltantes)
+        );
+        assert_eq!(
+            Argumentos::new(&args(&["egrep", "--nada", "a", "b"])),
+            Err(ErrorArgumentos::OpcionDesconocida("--nada".to_string()))
+        );
+        assert_eq!(
+            Argumentos::new(&args(&["egrep", "-zq", "a", "b"])),
+            Err(ErrorArgumentos::OpcionDesconocida("-q".to_string()))
+        );
+        assert_eq!(
+            Argumentos::new(&args(&["egrep", "a", "b", "--encoding"])),
+            Err(ErrorArgumentos::OpcionSinValor("--encoding".to_string()))
+        );
+        assert_eq!(
+            Argumentos::new(&args(&["egrep", "--in-place", "a", "b"])),
+            Err(ErrorArgumentos::OpcionRequerida(
+                "--in-place".to_string(),
+                "--replace".to_string()
+            ))
+        );
+        assert_eq!(
+            Argumentos::new(&args(&["egrep", "--replace=x", "--dry-run", "a", "b"])),
+            Err(ErrorArgumentos::OpcionRequerida(
+                "--dry-run".to_string(),
+                "--in-place".to_string()
+            ))
+        );
+        for (opcion, incompatible) in [("--replace=x", "--replace"), ("--crlf", "--crlf")] {
+            assert_eq!(
+                Argumentos::new(&args(&["egrep", "-U", opcion, "a", "b"])),
+                Err(ErrorArgumentos::OpcionesIncompatibles(
+                    "-U".to_string(),
+                    incompatible.to_string()
+                ))
+            );
+        }
+        for (opcion, incompatible) in [("-v", "-v"), ("-m1", "-m")] {
+            assert_eq!(
+                Argumentos::new(&args(&[
+                    "egrep",
+                    opcion,
+                    "--in-place",
+                    "--replace=x",
+                    "a",
+                    "b"
+                ])),
+                Err(ErrorArgumentos::OpcionesIncompatibles(
+                    "--in-place".to_string(),
+                    incompatible.to_string()
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn opciones_de_reescritura() {
+        let argumentos = Argumentos::new(&args(&[
+            "egrep",
+            "--replace=x",
+            "--in-place",
+            "--backup=.orig",
+            "--dry-run",
+            "a",
+            "b",
+        ]))
+        .unwrap();
+
+        assert!(argumentos.en_el_lugar);
+        assert!(argumentos.simular);
+        assert_eq!(argumentos.sufijo_respaldo, Some(".orig".to_string()));
+    }
+
+    #[test]
+    fn luego_de_doble_guion_todo_es_posicional() {
+        let argumentos = Argumentos::new(&args(&["egrep", "--", "--encoding", "b"])).unwrap();
+
+        assert_eq!(
+            argumentos.patrones,
+            vec![FuentePatrones::Patron("--encoding".to_string())]
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use crate::delimitacion::{caracter_de_palabra_anterior, es_caracter_de_palabra};
+
+/// Aserción de una expresión regular: no consume caracteres, sino que se cumple o no según la
+/// posición del valor evaluado en la que se encuentra.
+///
+/// * LimitePalabra (`\b`): entre un caracter de palabra y otro que no lo es (o el inicio o el
+///   final del valor).
+/// * NoLimitePalabra (`\B`): en cualquier posición que no sea un límite de palabra.
+/// * InicioPalabra (`\<`): antes de un caracter de palabra que no sigue a otro.
+/// * FinPalabra (`\>`): luego de un caracter de palabra que no es seguido por otro.
+/// * InicioTexto (`` \` ``) y FinTexto (`\'`): al inicio o al final del valor evaluado.
+/// * InicioLinea y FinLinea (`^` y `$` en modo multilínea): al inicio o al final del valor
+///   evaluado, o luego o antes del terminador de línea que contienen (ej.: '\n').
+///
+/// Forman parte de una palabra las letras, los dígitos y '_'.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Asercion {
+    LimitePalabra,
+    NoLimitePalabra,
+    InicioPalabra,
+    FinPalabra,
+    InicioTexto,
+    FinTexto,
+    InicioLinea(u8),
+    FinLinea(u8),
+}
+
+impl Asercion {
+    /// Devuelve true si la aserción se cumple en una posición de un valor recibido como slice de
+    /// bytes.
+    pub fn se_cumple(&self, valor: &[u8], posicion: usize) -> bool {
+        let anterior = || caracter_de_palabra_anterior(valor, posicion);
+        let siguiente = || es_caracter_de_palabra(&valor[posicion..]);
+        match self {
+            Self::LimitePalabra => anterior() != siguiente(),
+            Self::NoLimitePalabra => anterior() == siguiente(),
+            Self::InicioPalabra => !anterior() && siguiente(),
+            Self::FinPalabra => anterior() && !siguiente(),
+            Self::InicioTexto => posicion == 0,
+            Self::FinTexto => posicion == valor.len(),
+            Self::InicioLinea(terminador) => posicion == 0 || valor[posicion - 1] == *terminador,
+            Self::FinLinea(terminador) => valor.get(posicion).is_none_or(|byte| byte == terminador),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aserciones_de_palabra() {
+        let valor = "ñu, año".as_bytes();
+
+        assert!(Asercion::LimitePalabra.se_cumple(valor, 0));
+        assert!(Asercion::InicioPalabra.se_cumple(valor, 0));
+        assert!(Asercion::FinPalabra.se_cumple(valor, 3));
+        assert!(!Asercion::InicioPalabra.se_cumple(valor, 3));
+        assert!(Asercion::NoLimitePalabra.se_cumple(valor, 4));
+        assert!(!Asercion::LimitePalabra.se_cumple(valor, 2));
+        assert!(Asercion::FinPalabra.se_cumple(valor, valor.len()));
+    }
+
+    #[test]
+    fn aserciones_de_texto() {
+        let valor = b"ab";
+
+        assert!(Asercion::InicioTexto.se_cumple(valor, 0));
+        assert!(!Asercion::InicioTexto.se_cumple(valor, 1));
+        assert!(Asercion::FinTexto.se_cumple(valor, 2));
+        assert!(!Asercion::FinTexto.se_cumple(valor, 1));
+    }
+
+    #[test]
+    fn aserciones_de_linea() {
+        let valor = b"a\nb";
+
+        assert!(Asercion::InicioLinea(b'\n').se_cumple(valor, 0));
+        assert!(Asercion::InicioLinea(b'\n').se_cumple(valor, 2));
+        assert!(!Asercion::InicioLinea(b'\n').se_cumple(valor, 1));
+        assert!(Asercion::FinLinea(b'\n').se_cumple(valor, 1));
+        assert!(Asercion::FinLinea(b'\n').se_cumple(valor, 3));
+        assert!(!Asercion::FinLinea(b'\n').se_cumple(valor, 2));
+        assert!(!Asercion::InicioLinea(0).se_cumple(valor, 2));
+        assert!(Asercion::FinLinea(0).se_cumple(b"a\0b", 1));
+    }
+}
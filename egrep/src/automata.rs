@@ -0,0 +1,863 @@
+use std::sync::Mutex;
+
+use crate::{
+    aho_corasick::AhoCorasick, asercion::Asercion, clase_caracter::ClaseCaracter,
+    decodificacion_utf8::largo_primer_caracter, delimitacion::Delimitacion, expresion::Expresion,
+    regex_token::RegexToken, valor_regex_token::ValorRegexToken,
+};
+
+/// Instrucción de un autómata.
+///
+/// * Caracter: consume un caracter del valor evaluado si matchea con el valor de un token.
+/// * Division: continúa por ambas instrucciones (la primera tiene prioridad).
+/// * Salto: continúa por otra instrucción.
+/// * AnclajeInicio y AnclajeFinal: continúan solo al inicio o al final del valor evaluado.
+/// * Asercion: continúa solo si la aserción se cumple en la posición actual.
+/// * Coincidencia: el patrón con el índice indicado matchea con el valor evaluado.
+#[derive(Debug, PartialEq)]
+enum Instruccion {
+    Caracter(ValorRegexToken),
+    Division(usize, usize),
+    Salto(usize),
+    AnclajeInicio,
+    AnclajeFinal,
+    Asercion(Asercion),
+    Coincidencia(usize),
+}
+
+/// Autómata finito no determinista que reconoce uno o más patrones a la vez.
+///
+/// Se construye a partir de las expresiones de los patrones (cada una con el índice de su patrón)
+/// y se simula recorriendo el valor evaluado una única vez, avanzando en paralelo por todos los
+/// estados posibles. Por esto, el tiempo de evaluación es lineal en el largo del valor, sin
+/// importar cuántos patrones se evalúen.
+///
+/// Los estados en los que comienzan los patrones se calculan una única vez (junto con los literales
+/// con los que comienzan, para saltear las posiciones en las que ninguno puede comenzar), y la
+/// memoria de cada simulación se guarda para reutilizarla en las siguientes evaluaciones.
+#[derive(Debug)]
+pub struct Automata {
+    instrucciones: Vec<Instruccion>,
+    inicios: Inicios,
+    cantidad_patrones: usize,
+    memorias: Mutex<Vec<Memoria>>,
+}
+
+/// Estados en los que puede comenzar a matchear alguno de los patrones del autómata, calculados
+/// al compilarlo.
+///
+/// * por_byte: las instrucciones que consumen un caracter y que solo pueden matchear con uno que
+///   comienza con cierto byte (ej.: un literal), agrupadas según ese byte.
+/// * cualquier_byte: las demás instrucciones que consumen un caracter.
+/// * segun_posicion: los anclajes, las aserciones y las coincidencias (de patrones que matchean
+///   con el valor vacío), que se siguen en cada posición porque dependen de ella.
+/// * prefiltro: si todos los patrones comienzan con un literal (o con una bracket expresión de
+///   literales) o con un anclaje, un buscador de esos literales, sin distinguir mayúsculas ASCII,
+///   con el que se saltean las posiciones en las que ningún patrón puede comenzar a matchear.
+#[derive(Debug)]
+struct Inicios {
+    por_byte: Vec<Vec<usize>>,
+    cualquier_byte: Vec<usize>,
+    segun_posicion: Vec<usize>,
+    prefiltro: Option<AhoCorasick>,
+}
+
+impl Automata {
+    /// Creación de un autómata a partir de expresiones, cada una con el índice del patrón al que
+    /// pertenece, y de la cantidad total de patrones.
+    pub fn new<'a>(
+        expresiones: impl IntoIterator<Item = (usize, &'a Expresion)>,
+        cantidad_patrones: usize,
+    ) -> Self {
+        let mut automata = Self {
+            instrucciones: Vec::new(),
+            inicios: Inicios {
+                por_byte: vec![Vec::new(); 256],
+                cualquier_byte: Vec::new(),
+                segun_posicion: Vec::new(),
+                prefiltro: None,
+            },
+            cantidad_patrones,
+            memorias: Mutex::new(Vec::new()),
+        };
+        let mut inicios = Vec::new();
+        for (indice_patron, expresion) in expresiones {
+            inicios.push(automata.instrucciones.len());
+            automata.compilar_alternativas(std::slice::from_ref(expresion));
+            automata
+                .instrucciones
+                .push(Instruccion::Coincidencia(indice_patron));
+        }
+        automata.calcular_inicios(inicios);
+        automata
+    }
+
+    /// Devuelve la cantidad de instrucciones del autómata.
+    pub fn cantidad_instrucciones(&self) -> usize {
+        self.instrucciones.len()
+    }
+
+    /// Devuelve la cantidad de instrucciones que tendría el autómata de las expresiones recibidas,
+    /// sin compilarlo (como máximo, usize::MAX). Permite rechazar un patrón corto cuyas
+    /// repeticiones anidadas (ej.: a{1000}{1000}{1000}) requerirían demasiada memoria antes de
+    /// expandirlas.
+    pub fn cantidad_instrucciones_de<'a>(
+        expresiones: impl IntoIterator<Item = &'a Expresion>,
+    ) -> usize {
+        expresiones.into_iter().fold(0, |cantidad, expresion| {
+            cantidad
+                .saturating_add(cantidad_alternativas(std::slice::from_ref(expresion)))
+                .saturating_add(1)
+        })
+    }
+
+    /// Agrega las instrucciones de expresiones alternativas: se continúa por cualquiera de ellas
+    /// (con prioridad en el orden recibido) hasta el final de todas.
+    fn compilar_alternativas(&mut self, expresiones: &[Expresion]) {
+        let mut saltos_al_final = Vec::new();
+        for (i, expresion) in expresiones.iter().enumerate() {
+            let division = (i + 1 < expresiones.len()).then(|| {
+                self.instrucciones.push(Instruccion::Division(0, 0));
+                self.instrucciones.len() - 1
+            });
+            for token in expresion.tokens() {
+                self.compilar_token(token);
+            }
+            if let Some(division) = division {
+                saltos_al_final.push(self.instrucciones.len());
+                self.instrucciones.push(Instruccion::Salto(0));
+                self.instrucciones[division] =
+                    Instruccion::Division(division + 1, self.instrucciones.len());
+            }
+        }
+        let fin = self.instrucciones.len();
+        for salto in saltos_al_final {
+            self.instrucciones[salto] = Instruccion::Salto(fin);
+        }
+    }
+
+    /// Agrega las instrucciones de un token: su valor repetido tantas veces como indique su
+    /// rango de repetición.
+    ///
+    /// Las referencias a grupos no pueden representarse en un autómata: se compilan como
+    /// instrucciones que nunca matchean.
+    fn compilar_token(&mut self, token: &RegexToken) {
+        match token.valor {
+            ValorRegexToken::AnclajeInicio => {
+                return self.instrucciones.push(Instruccion::AnclajeInicio)
+            }
+            ValorRegexToken::AnclajeFinal => {
+                return self.instrucciones.push(Instruccion::AnclajeFinal)
+            }
+            ValorRegexToken::Asercion(asercion) => {
+                return self.instrucciones.push(Instruccion::Asercion(asercion))
+            }
+            _ => {}
+        }
+        let min = token.min_repeticiones().unwrap_or(0);
+        for _ in 0..min {
+            self.compilar_valor(&token.valor);
+        }
+        match token.max_repeticiones() {
+            Some(max) => {
+                let mut divisiones = Vec::new();
+                for _ in min..max {
+                    divisiones.push(self.instrucciones.len());
+                    self.instrucciones.push(Instruccion::Division(0, 0));
+                    self.compilar_valor(&token.valor);
+                }
+                let fin = self.instrucciones.len();
+                for division in divisiones {
+                    self.instrucciones[division] = Instruccion::Division(division + 1, fin);
+                }
+            }
+            None => {
+                let division = self.instrucciones.len();
+                self.instrucciones.push(Instruccion::Division(0, 0));
+                self.compilar_valor(&token.valor);
+                self.instrucciones.push(Instruccion::Salto(division));
+                let fin = self.instrucciones.len();
+                self.instrucciones[division] = Instruccion::Division(division + 1, fin);
+            }
+        }
+    }
+
+    /// Agrega las instrucciones de una única repetición del valor de un token.
+    fn compilar_valor(&mut self, valor: &ValorRegexToken) {
+        match valor {
+            ValorRegexToken::Grupo(_, expresiones) => self.compilar_alternativas(expresiones),
+            valor => self
+                .instrucciones
+                .push(Instruccion::Caracter(valor.clone())),
+        }
+    }
+
+    /// Calcula los [estados en los que comienzan][Inicios] los patrones, siguiendo desde la primera
+    /// instrucción de cada uno las divisiones y los saltos, y el prefiltro de sus literales
+    /// iniciales.
+    fn calcular_inicios(&mut self, inicios_patrones: Vec<usize>) {
+        let mut visitadas = vec![false; self.instrucciones.len()];
+        let mut pila: Vec<usize> = inicios_patrones.into_iter().rev().collect();
+        let mut literales = Vec::new();
+        while let Some(indice) = pila.pop() {
+            if std::mem::replace(&mut visitadas[indice], true) {
+                continue;
+            }
+            match &self.instrucciones[indice] {
+                Instruccion::Division(primera, segunda) => {
+                    pila.push(*segunda);
+                    pila.push(*primera);
+                }
+                Instruccion::Salto(siguiente) => pila.push(*siguiente),
+                Instruccion::Caracter(valor) => match primeros_bytes(valor) {
+                    Some(bytes) => {
+                        for byte in bytes {
+                            self.inicios.por_byte[usize::from(byte)].push(indice);
+                        }
+                        literales.extend(self.literales_desde(indice));
+                    }
+                    None => self.inicios.cualquier_byte.push(indice),
+                },
+                _ => self.inicios.segun_posicion.push(indice),
+            }
+        }
+        // Los anclajes solo se cumplen en los extremos del valor, que no se saltean. Un literal
+        // que comienza con un byte de continuación de utf8 podría encontrarse en medio de un
+        // caracter, donde la simulación no comienza.
+        let en_extremos = |indice: &usize| {
+            matches!(
+                self.instrucciones[*indice],
+                Instruccion::AnclajeInicio | Instruccion::AnclajeFinal
+            )
+        };
+        let con_prefiltro = self.inicios.cualquier_byte.is_empty()
+            && self.inicios.segun_posicion.iter().all(en_extremos)
+            && literales.iter().all(|literal| {
+                literal
+                    .first()
+                    .is_some_and(|byte| !(0x80..0xC0).contains(byte))
+            });
+        if con_prefiltro {
+            let literales = literales.iter().map(Vec::as_slice);
+            self.inicios.prefiltro = Some(AhoCorasick::ignorando_mayusculas_ascii(literales));
+        }
+    }
+
+    /// Devuelve los literales con los que puede continuar todo matcheo que pasa por una
+    /// instrucción: los de las instrucciones consecutivas que son literales o bracket expresiones
+    /// de literales (como las de un patrón que ignora mayúsculas), con una variante por cada
+    /// combinación de sus caracteres, hasta un máximo de [MAXIMO_LITERALES_POR_INICIO] de hasta
+    /// [MAXIMO_LARGO_LITERAL] bytes. Los caracteres ASCII se convierten a minúscula.
+    ///
+    /// Si la instrucción no es un literal ni una bracket expresión de literales, se devuelve un
+    /// único literal vacío.
+    fn literales_desde(&self, indice: usize) -> Vec<Vec<u8>> {
+        let mut literales = vec![Vec::new()];
+        for instruccion in &self.instrucciones[indice..] {
+            if literales
+                .iter()
+                .any(|literal| literal.len() >= MAXIMO_LARGO_LITERAL)
+            {
+                break;
+            }
+            let mut variantes = match instruccion {
+                Instruccion::Caracter(ValorRegexToken::Literal(c)) => vec![bytes_en_minuscula(c)],
+                Instruccion::Caracter(ValorRegexToken::Byte(byte)) => vec![vec![*byte]],
+                Instruccion::Caracter(ValorRegexToken::ExpresionBracket(clases)) => {
+                    let variantes = clases
+                        .iter()
+                        .map(|clase| match clase {
+                            ClaseCaracter::Literal(c) => Some(bytes_en_minuscula(c)),
+                            _ => None,
+                        })
+                        .collect::<Option<Vec<Vec<u8>>>>();
+                    match variantes {
+                        Some(variantes) => variantes,
+                        None => break,
+                    }
+                }
+                _ => break,
+            };
+            variantes.sort_unstable();
+            variantes.dedup();
+            if literales.len() * variantes.len() > MAXIMO_LITERALES_POR_INICIO {
+                break;
+            }
+            literales = literales
+                .iter()
+                .flat_map(|literal| {
+                    variantes
+                        .iter()
+                        .map(move |v| [literal.as_slice(), v].concat())
+                })
+                .collect();
+        }
+        literales
+    }
+
+    /// Devuelve los índices (ordenados) de los patrones que matchean con un valor recibido
+    /// como slice de bytes, recorriéndolo una única vez.
+    ///
+    /// Cada patrón puede matchear desde cualquier posición del valor. El recorrido se detiene
+    /// antes del final si ya matchearon todos los patrones.
+    pub fn patrones_que_matchean(&self, valor: &[u8]) -> Vec<usize> {
+        let mut simulacion = Simulacion::new(valor, &self.memorias, self.instrucciones.len());
+        let mut encontrados = vec![false; self.cantidad_patrones];
+        let mut cantidad_encontrados = 0;
+        let mut posicion = 0;
+
+        while let Some(inicio) = self.siguiente_inicio(posicion, &mut simulacion) {
+            posicion = inicio;
+            self.agregar_inicios(posicion, &mut simulacion);
+            for (indice_patron, _) in simulacion.memoria.coincidencias.drain(..) {
+                if !encontrados[indice_patron] {
+                    encontrados[indice_patron] = true;
+                    cantidad_encontrados += 1;
+                }
+            }
+            if (posicion == valor.len()) | (cantidad_encontrados == self.cantidad_patrones) {
+                break;
+            }
+            posicion = self.avanzar(posicion, &mut simulacion);
+        }
+
+        (0..self.cantidad_patrones)
+            .filter(|indice| encontrados[*indice])
+            .collect()
+    }
+
+    /// Búsqueda en un valor recibido como slice de bytes, desde una posición, de la coincidencia
+    /// que comienza primero (y, entre las que comienzan allí, la más larga) con alguno de los
+    /// patrones y que cumple con una delimitación.
+    ///
+    /// Devuelve las posiciones de inicio y fin de la coincidencia, o None si no hay ninguna.
+    pub fn buscar(
+        &self,
+        valor: &[u8],
+        desde: usize,
+        delimitacion: Delimitacion,
+    ) -> Option<(usize, usize)> {
+        self.recorrer(valor, desde, delimitacion, false)
+    }
+
+    /// Devuelve true si alguno de los patrones matchea con un valor recibido como slice de bytes,
+    /// cumpliendo con una delimitación.
+    ///
+    /// A diferencia de [buscar](Self::buscar), el recorrido termina en la primera coincidencia que
+    /// se encuentra, sin buscar la que comienza primero ni la más larga.
+    pub fn matchea(&self, valor: &[u8], delimitacion: Delimitacion) -> bool {
+        self.recorrer(valor, 0, delimitacion, true).is_some()
+    }
+
+    /// Recorre un valor desde una posición buscando la coincidencia que comienza primero y, entre
+    /// las que comienzan allí, la más larga (o, si se indica, terminando en la primera que se
+    /// encuentra), que cumple con una delimitación.
+    fn recorrer(
+        &self,
+        valor: &[u8],
+        desde: usize,
+        delimitacion: Delimitacion,
+        primera: bool,
+    ) -> Option<(usize, usize)> {
+        let mut simulacion = Simulacion::new(valor, &self.memorias, self.instrucciones.len());
+        let mut mejor: Option<(usize, usize)> = None;
+        let mut posicion = desde;
+
+        loop {
+            // Una coincidencia que comience después de la encontrada no puede reemplazarla.
+            if mejor.is_none() {
+                match self.siguiente_inicio(posicion, &mut simulacion) {
+                    Some(inicio) => posicion = inicio,
+                    None => break,
+                }
+                if delimitacion.permite_inicio(valor, posicion) {
+                    self.agregar_inicios(posicion, &mut simulacion);
+                }
+            }
+            if delimitacion.permite_fin(valor, posicion) {
+                for (_, inicio) in simulacion.memoria.coincidencias.iter() {
+                    if mejor.is_none_or(|(inicio_mejor, _)| *inicio <= inicio_mejor) {
+                        mejor = Some((*inicio, posicion));
+                    }
+                }
+            }
+            simulacion.memoria.coincidencias.clear();
+            if primera && mejor.is_some() {
+                break;
+            }
+            if let Some((inicio_mejor, _)) = mejor {
+                simulacion
+                    .memoria
+                    .estados
+                    .retain(|(_, inicio)| *inicio <= inicio_mejor);
+            }
+            if (posicion == valor.len())
+                | (mejor.is_some() && simulacion.memoria.estados.is_empty())
+            {
+                break;
+            }
+            posicion = self.avanzar(posicion, &mut simulacion);
+        }
+        mejor
+    }
+
+    /// Devuelve la posición, a partir de una recibida, desde la que debe continuar una simulación
+    /// en la que todavía no se encontró ninguna coincidencia.
+    ///
+    /// Si la simulación no tiene estados ni coincidencias en la posición actual y el autómata
+    /// tiene un prefiltro, se saltea hasta el siguiente [candidato](Self::siguiente_candidato) o,
+    /// si no hay ninguno, hasta el final del valor (solo si algún patrón comienza con un anclaje;
+    /// caso contrario, se devuelve None). Si algún patrón comienza con un anclaje, el inicio del
+    /// valor no se saltea. En los demás casos, se continúa desde la misma posición.
+    fn siguiente_inicio(&self, posicion: usize, simulacion: &mut Simulacion) -> Option<usize> {
+        let candidato = self.siguiente_candidato(posicion, simulacion);
+        let memoria = &simulacion.memoria;
+        let sin_estados = memoria.estados.is_empty() && memoria.coincidencias.is_empty();
+        if self.inicios.prefiltro.is_none() || !sin_estados {
+            return Some(posicion);
+        }
+        let con_anclajes = !self.inicios.segun_posicion.is_empty();
+        let inicio = match candidato {
+            _ if con_anclajes && posicion == 0 => posicion,
+            Some(candidato) => candidato,
+            None if con_anclajes => simulacion.valor.len(),
+            None => return None,
+        };
+        if inicio > posicion {
+            simulacion.nueva_posicion();
+        }
+        Some(inicio)
+    }
+
+    /// Devuelve la primera posición, a partir de una recibida, en la que comienza alguno de los
+    /// literales del prefiltro (la misma posición, si el autómata no tiene prefiltro), o None si
+    /// no hay ninguna. Solo allí pueden comenzar a matchear los patrones que comienzan con un
+    /// literal. La posición encontrada se guarda en la simulación, para no volver a buscarla.
+    fn siguiente_candidato(&self, posicion: usize, simulacion: &mut Simulacion) -> Option<usize> {
+        let Some(prefiltro) = &self.inicios.prefiltro else {
+            return Some(posicion);
+        };
+        match simulacion.candidato {
+            Some(Some(candidato)) if candidato >= posicion => Some(candidato),
+            Some(None) => None,
+            _ => {
+                let candidato = prefiltro
+                    .buscar(simulacion.valor, posicion, |_, _| true)
+                    .map(|(inicio, _)| inicio);
+                simulacion.candidato = Some(candidato);
+                candidato
+            }
+        }
+    }
+
+    /// Agrega a los estados de una simulación los estados en los que comienzan los patrones en
+    /// una posición del valor evaluado: los que dependen de la posición y, de los que consumen un
+    /// caracter, solo los que pueden matchear con el byte de esa posición (y, si el autómata tiene
+    /// un prefiltro, solo si la posición es un [candidato](Self::siguiente_candidato)).
+    fn agregar_inicios(&self, posicion: usize, simulacion: &mut Simulacion) {
+        for indice in &self.inicios.segun_posicion {
+            self.agregar_estado(*indice, posicion, posicion, simulacion);
+        }
+        if self.siguiente_candidato(posicion, simulacion) != Some(posicion) {
+            return;
+        }
+        if let Some(byte) = simulacion.valor.get(posicion) {
+            let por_byte = &self.inicios.por_byte[usize::from(*byte)];
+            for indice in por_byte.iter().chain(&self.inicios.cualquier_byte) {
+                self.agregar_estado(*indice, posicion, posicion, simulacion);
+            }
+        }
+    }
+
+    /// Avanza todos los estados de una simulación consumiendo el caracter que comienza en una
+    /// posición del valor evaluado. Devuelve la posición siguiente a ese caracter.
+    fn avanzar(&self, posicion: usize, simulacion: &mut Simulacion) -> usize {
+        let valor = simulacion.valor;
+        let siguiente_posicion = posicion + largo_primer_caracter(&valor[posicion..]);
+        let mut actuales = std::mem::take(&mut simulacion.memoria.siguientes);
+        std::mem::swap(&mut actuales, &mut simulacion.memoria.estados);
+        simulacion.nueva_posicion();
+        for (indice, inicio) in &actuales {
+            if let Instruccion::Caracter(valor_token) = &self.instrucciones[*indice] {
+                if valor_token.matchea(&valor[posicion..]) > 0 {
+                    self.agregar_estado(indice + 1, *inicio, siguiente_posicion, simulacion);
+                }
+            }
+        }
+        actuales.clear();
+        simulacion.memoria.siguientes = actuales;
+        siguiente_posicion
+    }
+
+    /// Agrega a los estados de una simulación el estado de una instrucción en una posición del
+    /// valor evaluado, siguiendo todas las instrucciones que no consumen caracteres hasta llegar a
+    /// las que sí lo hacen (o a una coincidencia). Cada estado guarda la posición en la que
+    /// comenzó a matchear.
+    fn agregar_estado(
+        &self,
+        indice: usize,
+        inicio: usize,
+        posicion: usize,
+        simulacion: &mut Simulacion,
+    ) {
+        let memoria = &mut simulacion.memoria;
+        let mut pila = std::mem::take(&mut memoria.pila);
+        pila.push(indice);
+        while let Some(indice) = pila.pop() {
+            if memoria.visitadas[indice] == memoria.marca {
+                continue;
+            }
+            memoria.visitadas[indice] = memoria.marca;
+            match self.instrucciones[indice] {
+                Instruccion::Caracter(_) => memoria.estados.push((indice, inicio)),
+                Instruccion::Division(primera, segunda) => {
+                    pila.push(segunda);
+                    pila.push(primera);
+                }
+                Instruccion::Salto(siguiente) => pila.push(siguiente),
+                Instruccion::AnclajeInicio => {
+                    if posicion == 0 {
+                        pila.push(indice + 1);
+                    }
+                }
+                Instruccion::AnclajeFinal => {
+                    if posicion == simulacion.valor.len() {
+                        pila.push(indice + 1);
+                    }
+                }
+                Instruccion::Asercion(asercion) => {
+                    if asercion.se_cumple(simulacion.valor, posicion) {
+                        pila.push(indice + 1);
+                    }
+                }
+                Instruccion::Coincidencia(indice_patron) => {
+                    memoria.coincidencias.push((indice_patron, inicio))
+                }
+            }
+        }
+        memoria.pila = pila;
+    }
+}
+
+/// Cantidad máxima de variantes de los literales con los que continúa un matcheo desde una
+/// instrucción (ver [Automata::literales_desde]).
+const MAXIMO_LITERALES_POR_INICIO: usize = 16;
+
+/// Largo máximo (en bytes) de los literales con los que continúa un matcheo desde una
+/// instrucción, para no recorrer literales muy largos (ej.: los de a{1000}).
+const MAXIMO_LARGO_LITERAL: usize = 32;
+
+/// Devuelve la codificación utf8 de un caracter, con los caracteres ASCII en minúscula.
+fn bytes_en_minuscula(c: &char) -> Vec<u8> {
+    c.to_ascii_lowercase()
+        .encode_utf8(&mut [0; 4])
+        .as_bytes()
+        .to_vec()
+}
+
+/// Devuelve los bytes con los que puede comenzar un caracter que matchea con un valor de un token
+/// (el primer byte de cada literal, si es un literal o una bracket expresión de literales), o
+/// None si puede comenzar con cualquiera.
+fn primeros_bytes(valor: &ValorRegexToken) -> Option<Vec<u8>> {
+    let primer_byte = |c: &char| c.encode_utf8(&mut [0; 4]).as_bytes()[0];
+    match valor {
+        ValorRegexToken::Literal(c) => Some(vec![primer_byte(c)]),
+        ValorRegexToken::Byte(byte) => Some(vec![*byte]),
+        ValorRegexToken::ExpresionBracket(clases) => {
+            let mut bytes = clases
+                .iter()
+                .map(|clase| match clase {
+                    ClaseCaracter::Literal(c) => Some(primer_byte(c)),
+                    _ => None,
+                })
+                .collect::<Option<Vec<u8>>>()?;
+            bytes.sort_unstable();
+            bytes.dedup();
+            Some(bytes)
+        }
+        _ => None,
+    }
+}
+
+/// Cantidad de instrucciones de expresiones alternativas: las de cada una, más una división y un
+/// salto por cada una salvo la última (ver [Automata::compilar_alternativas]).
+fn cantidad_alternativas(expresiones: &[Expresion]) -> usize {
+    let divisiones_y_saltos = expresiones.len().saturating_sub(1).saturating_mul(2);
+    expresiones
+        .iter()
+        .flat_map(Expresion::tokens)
+        .fold(divisiones_y_saltos, |cantidad, token| {
+            cantidad.saturating_add(cantidad_token(token))
+        })
+}
+
+/// Cantidad de instrucciones de un token (ver [Automata::compilar_token]): las de su valor por
+/// cada repetición obligatoria, más una división por cada repetición opcional (o una división y
+/// un salto, si no hay máximo).
+fn cantidad_token(token: &RegexToken) -> usize {
+    let valor = match &token.valor {
+        ValorRegexToken::AnclajeInicio
+        | ValorRegexToken::AnclajeFinal
+        | ValorRegexToken::Asercion(_) => return 1,
+        ValorRegexToken::Grupo(_, expresiones) => cantidad_alternativas(expresiones),
+        _ => 1,
+    };
+    let min = token.min_repeticiones().unwrap_or(0);
+    let opcionales = match token.max_repeticiones() {
+        Some(max) => max
+            .saturating_sub(min)
+            .saturating_mul(valor.saturating_add(1)),
+        None => valor.saturating_add(2),
+    };
+    valor.saturating_mul(min).saturating_add(opcionales)
+}
+
+/// Estado de la simulación de un autómata sobre un valor: el valor, la [memoria][Memoria] que
+/// usa (que se toma del autómata al comenzar y se le devuelve al terminar) y el siguiente
+/// candidato del prefiltro, si ya se buscó.
+struct Simulacion<'a> {
+    valor: &'a [u8],
+    memoria: Memoria,
+    origen: &'a Mutex<Vec<Memoria>>,
+    candidato: Option<Option<usize>>,
+}
+
+/// Memoria de la simulación de un autómata.
+///
+/// Guarda los estados actuales (instrucción y posición en la que comenzó a matchear), la marca
+/// de la posición en la que se visitó cada instrucción por última vez (para no repetir estados en
+/// una misma posición), las coincidencias encontradas en la posición actual (índice del patrón y
+/// posición de inicio) y los vectores auxiliares con los que se avanza, para no volver a reservar
+/// memoria en cada posición ni en cada simulación.
+///
+/// Los estados se mantienen ordenados por su posición de inicio: si dos estados llegan a la misma
+/// instrucción, se conserva el que comenzó antes.
+#[derive(Debug, Default)]
+struct Memoria {
+    estados: Vec<(usize, usize)>,
+    siguientes: Vec<(usize, usize)>,
+    visitadas: Vec<usize>,
+    marca: usize,
+    coincidencias: Vec<(usize, usize)>,
+    pila: Vec<usize>,
+}
+
+impl<'a> Simulacion<'a> {
+    fn new(
+        valor: &'a [u8],
+        origen: &'a Mutex<Vec<Memoria>>,
+        cantidad_instrucciones: usize,
+    ) -> Self {
+        let mut memoria = origen
+            .lock()
+            .ok()
+            .and_then(|mut memorias| memorias.pop())
+            .unwrap_or_default();
+        memoria.visitadas.resize(cantidad_instrucciones, usize::MAX);
+        let mut simulacion = Self {
+            valor,
+            memoria,
+            origen,
+            candidato: None,
+        };
+        simulacion.nueva_posicion();
+        simulacion
+    }
+
+    /// Cambia la marca con la que se registran las instrucciones visitadas, al pasar a una nueva
+    /// posición del valor.
+    fn nueva_posicion(&mut self) {
+        self.memoria.marca = self.memoria.marca.wrapping_add(1);
+        if self.memoria.marca == usize::MAX {
+            self.memoria.visitadas.fill(usize::MAX);
+            self.memoria.marca = 0;
+        }
+    }
+}
+
+impl Drop for Simulacion<'_> {
+    fn drop(&mut self) {
+        let mut memoria = std::mem::take(&mut self.memoria);
+        memoria.estados.clear();
+        memoria.coincidencias.clear();
+        if let Ok(mut memorias) = self.origen.lock() {
+            memorias.push(memoria);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{regex::Regex, repeticion_regex_token::RepeticionRegexToken};
+
+    fn token(valor: ValorRegexToken, min: Option<usize>, max: Option<usize>) -> RegexToken {
+        RegexToken::new(valor, RepeticionRegexToken::new(min, max))
+    }
+
+    #[test]
+    fn compila_repeticiones() {
+        let expresion =
+            Expresion::new(vec![token(ValorRegexToken::Literal('a'), Some(1), Some(2))]);
+
+        let automata = Automata::new([(0, &expresion)], 1);
+
+        assert_eq!(
+            automata.instrucciones,
+            vec![
+                Instruccion::Caracter(ValorRegexToken::Literal('a')),
+                Instruccion::Division(2, 3),
+                Instruccion::Caracter(ValorRegexToken::Literal('a')),
+                Instruccion::Coincidencia(0),
+            ]
+        );
+    }
+
+    #[test]
+    fn calcula_la_cantidad_de_instrucciones_sin_compilar() {
+        for patron in [
+            "a",
+            "ab|c|^d$",
+            "(a|bc)+x?",
+            "(a{2,4}|b*){3}c{2,}",
+            "\\<a\\b{0}",
+        ] {
+            let regex = Regex::new(patron).unwrap();
+            let automata = Automata::new(regex.expresiones().iter().map(|e| (0, e)), 1);
+
+            assert_eq!(
+                Automata::cantidad_instrucciones_de(regex.expresiones()),
+                automata.cantidad_instrucciones()
+            );
+        }
+        let mut repetido = token(ValorRegexToken::Literal('a'), Some(1000), Some(1000));
+        for _ in 0..6 {
+            let grupo = ValorRegexToken::Grupo(None, vec![Expresion::new(vec![repetido])]);
+            repetido = token(grupo, Some(1000), Some(1000));
+        }
+        assert_eq!(
+            Automata::cantidad_instrucciones_de([&Expresion::new(vec![repetido])]),
+            usize::MAX
+        );
+    }
+
+    #[test]
+    fn compila_grupos_con_alternativas() {
+        let grupo = ValorRegexToken::Grupo(
+            Some(1),
+            vec![
+                Expresion::new(vec![token(ValorRegexToken::Literal('a'), Some(1), Some(1))]),
+                Expresion::new(vec![token(ValorRegexToken::Literal('b'), Some(1), Some(1))]),
+            ],
+        );
+        let expresion = Expresion::new(vec![
+            token(grupo, Some(1), None),
+            token(ValorRegexToken::Literal('c'), Some(1), Some(1)),
+        ]);
+
+        let automata = Automata::new([(0, &expresion)], 1);
+
+        assert_eq!(
+            automata.buscar(b"xbabcab", 0, Delimitacion::Ninguna),
+            Some((1, 5))
+        );
+        assert_eq!(automata.buscar(b"xbab", 0, Delimitacion::Ninguna), None);
+    }
+
+    #[test]
+    fn matchea_varios_patrones_a_la_vez() {
+        let expresion_a = Expresion::new(vec![token(ValorRegexToken::Literal('a'), None, None)]);
+        let expresion_b = Expresion::new(vec![
+            token(ValorRegexToken::Literal('b'), Some(1), Some(1)),
+            token(ValorRegexToken::AnclajeFinal, Some(0), Some(0)),
+        ]);
+        let expresion_c = Expresion::new(vec![
+            token(ValorRegexToken::AnclajeInicio, Some(0), Some(0)),
+            token(ValorRegexToken::Literal('c'), Some(1), Some(1)),
+        ]);
+
+        let automata = Automata::new([(0, &expresion_a), (1, &expresion_b), (2, &expresion_c)], 3);
+
+        assert_eq!(automata.patrones_que_matchean(b"xxb"), vec![0, 1]);
+        assert_eq!(automata.patrones_que_matchean(b"cb"), vec![0, 1, 2]);
+        assert_eq!(automata.patrones_que_matchean(b"bc"), vec![0]);
+    }
+
+    #[test]
+    fn busca_coincidencia_mas_temprana_y_mas_larga() {
+        let expresion_a = Expresion::new(vec![token(ValorRegexToken::Literal('a'), Some(1), None)]);
+        let expresion_b = Expresion::new(vec![
+            token(ValorRegexToken::Literal('b'), Some(1), Some(1)),
+            token(ValorRegexToken::Comodin, None, None),
+        ]);
+
+        let automata = Automata::new([(0, &expresion_a), (0, &expresion_b)], 1);
+
+        assert_eq!(
+            automata.buscar(b"xaab", 0, Delimitacion::Ninguna),
+            Some((1, 3))
+        );
+        assert_eq!(
+            automata.buscar(b"xaab", 3, Delimitacion::Ninguna),
+            Some((3, 4))
+        );
+        assert_eq!(
+            automata.buscar(b"baa", 0, Delimitacion::Ninguna),
+            Some((0, 3))
+        );
+        assert_eq!(automata.buscar(b"xyz", 0, Delimitacion::Ninguna), None);
+    }
+
+    #[test]
+    fn saltea_hasta_los_literales_iniciales() {
+        let regex = Regex::new("^x|hola|[cC]hau|y$").unwrap();
+        let automata = Automata::new(regex.expresiones().iter().map(|e| (0, e)), 1);
+
+        assert!(automata.inicios.prefiltro.is_some());
+        assert_eq!(
+            automata.buscar(b"a hola hola", 0, Delimitacion::Ninguna),
+            Some((2, 6))
+        );
+        assert_eq!(
+            automata.buscar(b"a hola hola", 3, Delimitacion::Ninguna),
+            Some((7, 11))
+        );
+        assert_eq!(automata.buscar(b"un CHAU", 0, Delimitacion::Ninguna), None);
+        assert_eq!(
+            automata.buscar(b"un Chau", 0, Delimitacion::Ninguna),
+            Some((3, 7))
+        );
+        assert!(automata.matchea(b"xa", Delimitacion::Ninguna));
+        assert!(automata.matchea(b"ay", Delimitacion::Ninguna));
+        assert!(!automata.matchea(b"axya", Delimitacion::Ninguna));
+        assert_eq!(automata.patrones_que_matchean(b"hay hola"), vec![0]);
+    }
+
+    #[test]
+    fn sin_prefiltro_si_algun_patron_no_comienza_con_un_literal() {
+        for patron in ["hola|.x", "hola|\\bx", "hola|x*"] {
+            let regex = Regex::new(patron).unwrap();
+            let automata = Automata::new(regex.expresiones().iter().map(|e| (0, e)), 1);
+
+            assert!(automata.inicios.prefiltro.is_none());
+        }
+    }
+
+    #[test]
+    fn busca_coincidencia_delimitada() {
+        let expresion = Expresion::new(vec![token(ValorRegexToken::Literal('a'), Some(1), None)]);
+
+        let automata = Automata::new([(0, &expresion)], 1);
+
+        assert_eq!(
+            automata.buscar(b"baa aa", 0, Delimitacion::Palabra),
+            Some((4, 6))
+        );
+        assert_eq!(automata.buscar(b"aab", 0, Delimitacion::Palabra), None);
+        assert_eq!(automata.buscar(b"baa", 0, Delimitacion::Linea), None);
+        assert_eq!(
+            automata.buscar(b"aaa", 0, Delimitacion::Linea),
+            Some((0, 3))
+        );
+    }
+}
@@ -0,0 +1,480 @@
+use crate::{
+    decodificacion_utf8::{decodificar_primer_caracter, largo_primer_caracter},
+    expresion::Expresion,
+    regex_token::RegexToken,
+    valor_regex_token::ValorRegexToken,
+};
+use std::collections::HashSet;
+
+/// Función a la que se continúa luego de matchear una parte de una expresión, recibiendo la
+/// posición en la que terminó. Devuelve true si el resto del matcheo fue exitoso.
+type Continuacion<'c, 'a> = dyn FnMut(&mut Backtracking<'a>, usize) -> bool + 'c;
+
+/// Estado en el que se intenta una repetición más de un grupo o referencia: la posición en el
+/// valor, la cantidad de repeticiones ya matcheadas y las capturas de los grupos.
+type EstadoRepeticion = (usize, usize, Vec<Option<(usize, usize)>>);
+
+/// Motor de backtracking para matchear expresiones con un valor.
+///
+/// Prueba las alternativas en orden y las repeticiones de mayor a menor (o de menor a mayor, si son
+/// perezosas), quedándose con el primer matcheo que encuentra (o explorándolas todas y quedándose
+/// con el más largo, si así se indica), volviendo atrás cuando el
+/// resto de la expresión no matchea. A diferencia de un autómata, guarda lo que matcheó cada grupo,
+/// por lo que puede evaluar referencias a grupos.
+///
+/// Para que las repeticiones de grupos anidadas (ej.: `(a*)*\1c`) no lleven a un tiempo de
+/// evaluación exponencial, en cada repetición de un grupo o referencia se recuerdan los estados
+/// (posición, repeticiones y capturas) desde los que ya se intentó seguir, y no se vuelven a
+/// intentar: con el mismo estado, el resto del matcheo daría el mismo resultado. Las capturas solo
+/// forman parte del estado si las expresiones tienen referencias a grupos.
+pub struct Backtracking<'a> {
+    valor: &'a [u8],
+    capturas: Vec<Option<(usize, usize)>>,
+    con_referencias: bool,
+    mas_largo: bool,
+}
+
+impl<'a> Backtracking<'a> {
+    /// Creación del motor para matchear con un valor recibido como slice de bytes.
+    pub fn new(valor: &'a [u8]) -> Self {
+        Self {
+            valor,
+            capturas: Vec::new(),
+            con_referencias: false,
+            mas_largo: false,
+        }
+    }
+
+    /// Indica si, entre los matcheos que comienzan en una misma posición, se busca el más largo
+    /// (como en POSIX) en lugar del primero que se encuentra (como en Perl).
+    pub fn mas_largo(mut self, mas_largo: bool) -> Self {
+        self.mas_largo = mas_largo;
+        self
+    }
+
+    /// Búsqueda en el valor, desde una posición, de la primera posición en la que alguna de las
+    /// expresiones matchea con un fin aceptado por una función que recibe las posiciones de inicio
+    /// y fin.
+    ///
+    /// Devuelve las posiciones de inicio y fin de la coincidencia, o None si no hay ninguna. Entre
+    /// las coincidencias que comienzan en una misma posición, se devuelve la primera que se
+    /// encuentra, respetando el orden de las alternativas y prefiriendo más repeticiones (o menos,
+    /// en las repeticiones perezosas), o la más larga (ver [mas_largo](Self::mas_largo)).
+    pub fn buscar(
+        &mut self,
+        expresiones: &[Expresion],
+        desde: usize,
+        aceptar: impl Fn(usize, usize) -> bool,
+    ) -> Option<(usize, usize)> {
+        let mut inicio = desde;
+        while inicio <= self.valor.len() {
+            if let Some(fin) = self.matchear_desde(expresiones, inicio, |fin| aceptar(inicio, fin))
+            {
+                return Some((inicio, fin));
+            }
+            inicio += largo_primer_caracter(&self.valor[inicio..]).max(1);
+        }
+        None
+    }
+
+    /// Matcheo de alguna de las expresiones desde una posición del valor, con un fin aceptado por
+    /// una función. Devuelve la posición en la que termina el matcheo (el primero que se encuentra
+    /// o el más largo, ver [mas_largo](Self::mas_largo)), o None si no matchea.
+    pub fn matchear_desde(
+        &mut self,
+        expresiones: &[Expresion],
+        inicio: usize,
+        aceptar: impl Fn(usize) -> bool,
+    ) -> Option<usize> {
+        self.capturas.clear();
+        self.con_referencias = expresiones.iter().any(Expresion::tiene_referencias);
+        let mut fin_matcheo = None;
+        let mut capturas_matcheo = Vec::new();
+        let (mas_largo, largo_valor) = (self.mas_largo, self.valor.len());
+        self.alternativas(expresiones, inicio, &mut |backtracking, fin| {
+            if !aceptar(fin) || fin_matcheo.is_some_and(|fin_matcheo| fin_matcheo >= fin) {
+                return false;
+            }
+            fin_matcheo = Some(fin);
+            capturas_matcheo.clone_from(&backtracking.capturas);
+            // Ningún matcheo puede ser más largo que uno que llega al final del valor.
+            !mas_largo || fin == largo_valor
+        });
+        self.capturas = capturas_matcheo;
+        fin_matcheo
+    }
+
+    /// Devuelve las posiciones de lo que matcheó cada grupo (según su número) en el último
+    /// matcheo exitoso. Los grupos que no participaron del matcheo no tienen captura.
+    pub fn capturas(&self) -> &[Option<(usize, usize)>] {
+        &self.capturas
+    }
+
+    fn alternativas(
+        &mut self,
+        expresiones: &[Expresion],
+        posicion: usize,
+        continuacion: &mut Continuacion<'_, 'a>,
+    ) -> bool {
+        expresiones
+            .iter()
+            .any(|expresion| self.secuencia(expresion.tokens(), posicion, continuacion))
+    }
+
+    fn secuencia(
+        &mut self,
+        tokens: &[RegexToken],
+        posicion: usize,
+        continuacion: &mut Continuacion<'_, 'a>,
+    ) -> bool {
+        match tokens.split_first() {
+            None => continuacion(self, posicion),
+            Some((token, resto)) => self.token(token, resto, posicion, continuacion),
+        }
+    }
+
+    /// Matcheo de un token seguido por el resto de los tokens de una expresión.
+    ///
+    /// Los tokens de un único caracter se repiten todo lo posible de una vez y luego se prueban
+    /// de mayor a menor cantidad de repeticiones (de menor a mayor, si son perezosos), sin
+    /// recursión por cada repetición.
+    fn token(
+        &mut self,
+        token: &RegexToken,
+        resto: &[RegexToken],
+        posicion: usize,
+        continuacion: &mut Continuacion<'_, 'a>,
+    ) -> bool {
+        match &token.valor {
+            ValorRegexToken::AnclajeInicio => {
+                posicion == 0 && self.secuencia(resto, posicion, continuacion)
+            }
+            ValorRegexToken::AnclajeFinal => {
+                posicion == self.valor.len() && self.secuencia(resto, posicion, continuacion)
+            }
+            ValorRegexToken::Asercion(asercion) => {
+                asercion.se_cumple(self.valor, posicion)
+                    && self.secuencia(resto, posicion, continuacion)
+            }
+            valor @ (ValorRegexToken::Anticipacion(_)
+            | ValorRegexToken::AnticipacionNegada(_)
+            | ValorRegexToken::Retrospeccion(_)
+            | ValorRegexToken::RetrospeccionNegada(_)) => {
+                let capturas = self.capturas.clone();
+                if self.se_cumple_alrededor(valor, posicion)
+                    && self.secuencia(resto, posicion, continuacion)
+                {
+                    return true;
+                }
+                self.capturas = capturas;
+                false
+            }
+            ValorRegexToken::Grupo(..)
+            | ValorRegexToken::GrupoAtomico(_)
+            | ValorRegexToken::Referencia(..) => {
+                self.repetir(token, resto, posicion, 0, &mut HashSet::new(), continuacion)
+            }
+            valor => {
+                let min = token.min_repeticiones().unwrap_or(0);
+                let max = token.max_repeticiones();
+                let mut posiciones = vec![posicion];
+                let mut actual = posicion;
+                while max.is_none_or(|max| posiciones.len() <= max) {
+                    let largo = valor.matchea(&self.valor[actual..]);
+                    if largo == 0 {
+                        break;
+                    }
+                    actual += largo;
+                    posiciones.push(actual);
+                }
+                let mut candidatas = posiciones.into_iter().enumerate().skip(min);
+                if token.es_perezoso() {
+                    candidatas.any(|(_, fin)| self.secuencia(resto, fin, continuacion))
+                } else {
+                    candidatas
+                        .rev()
+                        .any(|(_, fin)| self.secuencia(resto, fin, continuacion))
+                }
+            }
+        }
+    }
+
+    /// Matcheo de un grupo o referencia repetido, luego de haberlo matcheado una cantidad de veces,
+    /// seguido por el resto de los tokens de una expresión.
+    ///
+    /// Se prueba primero una repetición más y, si no se logra matchear, el resto de los tokens (o
+    /// al revés, si la repetición es perezosa). Una repetición que no avanza en el valor, si ya se
+    /// llegó al mínimo, solo se acepta como la última: guarda lo que matchearon sus grupos (ej.: en
+    /// '(a*)?\1', el grupo matchea vacío) y sigue con el resto de los tokens, sin volver a repetir.
+    ///
+    /// Si ya se intentó seguir desde el mismo estado (con la misma continuación), no se vuelve a
+    /// intentar. Sin máximo de repeticiones, todas las cantidades desde el mínimo son equivalentes.
+    fn repetir(
+        &mut self,
+        token: &RegexToken,
+        resto: &[RegexToken],
+        posicion: usize,
+        repeticiones: usize,
+        visitados: &mut HashSet<EstadoRepeticion>,
+        continuacion: &mut Continuacion<'_, 'a>,
+    ) -> bool {
+        let min = token.min_repeticiones().unwrap_or(0);
+        let repeticiones_estado = match token.max_repeticiones() {
+            None => repeticiones.min(min),
+            Some(_) => repeticiones,
+        };
+        let capturas = if self.con_referencias {
+            self.capturas.clone()
+        } else {
+            Vec::new()
+        };
+        if !visitados.insert((posicion, repeticiones_estado, capturas)) {
+            return false;
+        }
+        if token.es_perezoso()
+            && repeticiones >= min
+            && self.secuencia(resto, posicion, continuacion)
+        {
+            return true;
+        }
+        if token
+            .max_repeticiones()
+            .is_none_or(|max| repeticiones < max)
+        {
+            let otra_repeticion = self.atomo(&token.valor, posicion, &mut |backtracking, fin| {
+                if (fin == posicion) & (repeticiones >= min) {
+                    return backtracking.secuencia(resto, fin, continuacion);
+                }
+                backtracking.repetir(token, resto, fin, repeticiones + 1, visitados, continuacion)
+            });
+            if otra_repeticion {
+                return true;
+            }
+        }
+        !token.es_perezoso() && repeticiones >= min && self.secuencia(resto, posicion, continuacion)
+    }
+
+    /// Matcheo de una única repetición de un grupo (guardando lo que matcheó, si captura) o de una referencia
+    /// (que matchea con lo mismo que matcheó su grupo, si este ya matcheó, o con sus variantes de
+    /// mayúsculas y minúsculas, si la referencia las ignora).
+    ///
+    /// De un grupo atómico se toma el primer matcheo que se encuentra, sin volver atrás en él si
+    /// luego no matchea el resto de la expresión.
+    fn atomo(
+        &mut self,
+        valor: &ValorRegexToken,
+        posicion: usize,
+        continuacion: &mut Continuacion<'_, 'a>,
+    ) -> bool {
+        match valor {
+            ValorRegexToken::Grupo(None, expresiones) => {
+                self.alternativas(expresiones, posicion, continuacion)
+            }
+            ValorRegexToken::Grupo(Some(numero), expresiones) => {
+                self.alternativas(expresiones, posicion, &mut |backtracking, fin| {
+                    let anterior = backtracking.captura(*numero);
+                    backtracking.guardar_captura(*numero, Some((posicion, fin)));
+                    if continuacion(backtracking, fin) {
+                        true
+                    } else {
+                        backtracking.guardar_captura(*numero, anterior);
+                        false
+                    }
+                })
+            }
+            ValorRegexToken::GrupoAtomico(expresiones) => {
+                let capturas = self.capturas.clone();
+                let mut fin_atomico = None;
+                self.alternativas(expresiones, posicion, &mut |_, fin| {
+                    fin_atomico = Some(fin);
+                    true
+                });
+                if fin_atomico.is_some_and(|fin| continuacion(self, fin)) {
+                    return true;
+                }
+                self.capturas = capturas;
+                false
+            }
+            ValorRegexToken::Referencia(numero, ignorar_mayusculas) => {
+                match self.captura(*numero) {
+                    Some((inicio, fin)) => {
+                        let (valor, capturado) =
+                            (&self.valor[posicion..], &self.valor[inicio..fin]);
+                        let largo = if *ignorar_mayusculas {
+                            largo_prefijo_sin_mayusculas(valor, capturado)
+                        } else {
+                            valor.starts_with(capturado).then_some(capturado.len())
+                        };
+                        largo.is_some_and(|largo| continuacion(self, posicion + largo))
+                    }
+                    None => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Devuelve true si una anticipación o retrospección se cumple en una posición del valor.
+    ///
+    /// Una anticipación se cumple si alguna de sus expresiones matchea desde la posición, y una
+    /// retrospección si alguna matchea hasta ella, comenzando a lo sumo tantos bytes antes como su
+    /// largo máximo. Las negadas se cumplen si ninguna matchea. Se toma el primer matcheo que se
+    /// encuentra, sin volver atrás en él si luego no matchea el resto de la expresión.
+    fn se_cumple_alrededor(&mut self, valor: &ValorRegexToken, posicion: usize) -> bool {
+        match valor {
+            ValorRegexToken::Anticipacion(expresiones) => {
+                self.alternativas(expresiones, posicion, &mut |_, _| true)
+            }
+            ValorRegexToken::AnticipacionNegada(expresiones) => {
+                !self.alternativas(expresiones, posicion, &mut |_, _| true)
+            }
+            ValorRegexToken::Retrospeccion(expresiones) => {
+                self.matchea_hasta(expresiones, posicion)
+            }
+            ValorRegexToken::RetrospeccionNegada(expresiones) => {
+                !self.matchea_hasta(expresiones, posicion)
+            }
+            _ => false,
+        }
+    }
+
+    fn matchea_hasta(&mut self, expresiones: &[Expresion], posicion: usize) -> bool {
+        let largo_maximo = expresiones
+            .iter()
+            .filter_map(Expresion::largo_maximo)
+            .max()
+            .unwrap_or(0);
+        (posicion.saturating_sub(largo_maximo)..=posicion)
+            .filter(|inicio| {
+                // No se comienza en medio de un caracter utf8.
+                self.valor
+                    .get(*inicio)
+                    .is_none_or(|byte| byte & 0b1100_0000 != 0b1000_0000)
+            })
+            .any(|inicio| self.alternativas(expresiones, inicio, &mut |_, fin| fin == posicion))
+    }
+
+    fn captura(&self, numero: usize) -> Option<(usize, usize)> {
+        self.capturas.get(numero).copied().flatten()
+    }
+
+    fn guardar_captura(&mut self, numero: usize, captura: Option<(usize, usize)>) {
+        if self.capturas.len() <= numero {
+            self.capturas.resize(numero + 1, None);
+        }
+        self.capturas[numero] = captura;
+    }
+}
+
+/// Devuelve el largo del prefijo de un valor que es igual a otro, ignorando mayúsculas (caracter
+/// a caracter, por lo que pueden tener distinto largo en bytes), o None si el valor no comienza
+/// con él. Los bytes que no forman parte de un caracter utf8 válido solo son iguales a sí mismos.
+fn largo_prefijo_sin_mayusculas(valor: &[u8], prefijo: &[u8]) -> Option<usize> {
+    let (mut largo, mut largo_prefijo) = (0, 0);
+    while largo_prefijo < prefijo.len() {
+        let iguales = match (
+            decodificar_primer_caracter(&valor[largo..])?,
+            decodificar_primer_caracter(&prefijo[largo_prefijo..])?,
+        ) {
+            (Ok(c), Ok(c_prefijo)) => c.to_lowercase().eq(c_prefijo.to_lowercase()),
+            (c, c_prefijo) => c == c_prefijo,
+        };
+        if !iguales {
+            return None;
+        }
+        largo += largo_primer_caracter(&valor[largo..]);
+        largo_prefijo += largo_primer_caracter(&prefijo[largo_prefijo..]);
+    }
+    Some(largo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{regex_builder::RegexBuilder, syntax::Syntax};
+
+    fn expresiones(patron: &str) -> Vec<Expresion> {
+        let regex = RegexBuilder::new(patron)
+            .syntax(Syntax::Bre)
+            .build()
+            .unwrap();
+        regex.expresiones().to_vec()
+    }
+
+    #[test]
+    fn matchea_referencias_a_grupos() {
+        let expresiones = expresiones(r"\(a*\)b\1");
+
+        assert_eq!(
+            Backtracking::new(b"xaabaa").buscar(&expresiones, 0, |_, _| true),
+            Some((1, 6))
+        );
+        assert_eq!(
+            Backtracking::new(b"aaba").buscar(&expresiones, 0, |_, _| true),
+            Some((1, 4))
+        );
+    }
+
+    #[test]
+    fn vuelve_atras_en_repeticiones_de_grupos() {
+        let expresiones = expresiones(r"\(ab\|a\)*\1c");
+
+        assert_eq!(
+            Backtracking::new(b"abaac").buscar(&expresiones, 0, |_, _| true),
+            Some((0, 5))
+        );
+        assert_eq!(
+            Backtracking::new(b"abab").buscar(&expresiones, 0, |_, _| true),
+            None
+        );
+    }
+
+    #[test]
+    fn matchea_con_fin_aceptado() {
+        let expresiones = expresiones(r"\(a\)\1*");
+
+        assert_eq!(
+            Backtracking::new(b"aaa").matchear_desde(&expresiones, 0, |fin| fin < 3),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn prefiere_menos_repeticiones_si_son_perezosas() {
+        let expresiones = RegexBuilder::new("<.+?>(ab)*?b")
+            .syntax(Syntax::Perl)
+            .build()
+            .unwrap()
+            .expresiones()
+            .to_vec();
+
+        assert_eq!(
+            Backtracking::new(b"<a>b<b>b").buscar(&expresiones, 0, |_, _| true),
+            Some((0, 4))
+        );
+        assert_eq!(
+            Backtracking::new(b"<a>x<b>ababb").buscar(&expresiones, 0, |_, _| true),
+            Some((0, 12))
+        );
+    }
+
+    #[test]
+    fn repeticiones_anidadas_de_grupos_sin_tiempo_exponencial() {
+        let expresiones = RegexBuilder::new(r"(a*)*\1c")
+            .syntax(Syntax::Ere)
+            .build()
+            .unwrap()
+            .expresiones()
+            .to_vec();
+        let valor = [b'a'; 40];
+
+        assert_eq!(
+            Backtracking::new(&valor).buscar(&expresiones, 0, |_, _| true),
+            None
+        );
+        assert_eq!(
+            Backtracking::new(b"aaaac").buscar(&expresiones, 0, |_, _| true),
+            Some((0, 5))
+        );
+    }
+}
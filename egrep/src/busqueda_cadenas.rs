@@ -0,0 +1,198 @@
+use crate::{
+    aho_corasick::AhoCorasick, decodificacion_utf8::decodificar_primer_caracter,
+    delimitacion::Delimitacion, horspool::Horspool,
+};
+
+/// Algoritmo de búsqueda según la cantidad de cadenas: Boyer–Moore–Horspool para una única
+/// cadena y Aho–Corasick para varias.
+#[derive(Debug)]
+enum Algoritmo {
+    Horspool(Horspool),
+    AhoCorasick(AhoCorasick),
+}
+
+/// Buscador de cadenas fijas: los patrones se buscan tal como son, sin interpretar
+/// metacaracteres, por lo que no se crea ninguna expresión regular.
+#[derive(Debug)]
+pub struct BuscadorCadenas {
+    algoritmo: Algoritmo,
+    ignorar_mayusculas: bool,
+}
+
+impl BuscadorCadenas {
+    /// Creación de un buscador de cadenas fijas, indicando si no se deben distinguir mayúsculas
+    /// de minúsculas.
+    ///
+    /// Una cadena vacía aparece en cualquier posición. Si no se recibe ninguna cadena, no se
+    /// encuentra ninguna aparición.
+    pub fn new<I, S>(cadenas: I, ignorar_mayusculas: bool) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<[u8]>,
+    {
+        let mut cadenas: Vec<Vec<u8>> = cadenas
+            .into_iter()
+            .map(|cadena| {
+                if ignorar_mayusculas {
+                    plegar_mayusculas(cadena.as_ref()).0
+                } else {
+                    cadena.as_ref().to_vec()
+                }
+            })
+            .collect();
+        let algoritmo = if cadenas.len() == 1 {
+            Algoritmo::Horspool(Horspool::new(&cadenas.remove(0)))
+        } else {
+            Algoritmo::AhoCorasick(AhoCorasick::new(cadenas.iter().map(|c| c.as_slice())))
+        };
+        Self {
+            algoritmo,
+            ignorar_mayusculas,
+        }
+    }
+
+    /// Devuelve true si alguna de las cadenas aparece en un valor recibido como slice de bytes.
+    pub fn matchea(&self, valor: &[u8]) -> bool {
+        self.buscar(valor, 0, Delimitacion::Ninguna).is_some()
+    }
+
+    /// Búsqueda en un valor recibido como slice de bytes, desde una posición, de la aparición de
+    /// alguna de las cadenas que comienza primero (y, entre las que comienzan allí, la más larga)
+    /// y que cumple con una delimitación.
+    ///
+    /// Devuelve las posiciones de inicio y fin de la aparición, o None si no hay ninguna.
+    pub fn buscar(
+        &self,
+        valor: &[u8],
+        desde: usize,
+        delimitacion: Delimitacion,
+    ) -> Option<(usize, usize)> {
+        let aceptar = |inicio: usize, fin: usize| {
+            delimitacion.permite_inicio(valor, inicio) && delimitacion.permite_fin(valor, fin)
+        };
+        if !self.ignorar_mayusculas {
+            return self.buscar_aceptada(valor, desde, aceptar);
+        }
+        // Se busca en el valor en minúsculas y se traducen las posiciones a las del valor original.
+        let (plegado, posiciones) = plegar_mayusculas(valor);
+        let desde_plegado = posiciones.partition_point(|posicion| *posicion < desde);
+        self.buscar_aceptada(&plegado, desde_plegado, |inicio, fin| {
+            aceptar(posiciones[inicio], posiciones[fin])
+        })
+        .map(|(inicio, fin)| (posiciones[inicio], posiciones[fin]))
+    }
+
+    fn buscar_aceptada(
+        &self,
+        valor: &[u8],
+        desde: usize,
+        aceptar: impl Fn(usize, usize) -> bool,
+    ) -> Option<(usize, usize)> {
+        match &self.algoritmo {
+            Algoritmo::Horspool(horspool) => {
+                let mut posicion = desde;
+                while let Some(inicio) = horspool.buscar(valor, posicion) {
+                    let fin = inicio + horspool.largo();
+                    if aceptar(inicio, fin) {
+                        return Some((inicio, fin));
+                    }
+                    posicion = inicio + 1;
+                }
+                None
+            }
+            Algoritmo::AhoCorasick(aho_corasick) => aho_corasick.buscar(valor, desde, aceptar),
+        }
+    }
+}
+
+/// Devuelve un valor con cada caracter convertido a minúscula (si su minúscula es un único
+/// caracter), junto con la posición en el valor original del caracter al que corresponde cada
+/// byte del valor convertido (y, al final, el largo del valor original).
+///
+/// Los bytes que no forman parte de un caracter utf8 válido se mantienen.
+fn plegar_mayusculas(valor: &[u8]) -> (Vec<u8>, Vec<usize>) {
+    let mut plegado = Vec::with_capacity(valor.len());
+    let mut posiciones = Vec::with_capacity(valor.len() + 1);
+    let mut posicion = 0;
+    while let Some(caracter) = decodificar_primer_caracter(&valor[posicion..]) {
+        let largo = match caracter {
+            Ok(c) => {
+                let mut minusculas = c.to_lowercase();
+                let minuscula = match (minusculas.next(), minusculas.next()) {
+                    (Some(m), None) => m,
+                    _ => c,
+                };
+                let mut buffer = [0; 4];
+                plegado.extend_from_slice(minuscula.encode_utf8(&mut buffer).as_bytes());
+                posiciones.resize(plegado.len(), posicion);
+                c.len_utf8()
+            }
+            Err(byte) => {
+                plegado.push(byte);
+                posiciones.push(posicion);
+                1
+            }
+        };
+        posicion += largo;
+    }
+    posiciones.push(valor.len());
+    (plegado, posiciones)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn busca_cadena_con_metacaracteres() {
+        let buscador = BuscadorCadenas::new(["a.b[c]$"], false);
+
+        assert!(buscador.matchea(b"x a.b[c]$ y"));
+        assert!(!buscador.matchea(b"axb[c]"));
+    }
+
+    #[test]
+    fn busca_varias_cadenas_con_delimitacion() {
+        let buscador = BuscadorCadenas::new(["foo", "foo.bar"], false);
+
+        assert_eq!(
+            buscador.buscar(b"xfoo foo.bar", 0, Delimitacion::Ninguna),
+            Some((1, 4))
+        );
+        assert_eq!(
+            buscador.buscar(b"xfoo foo.bar", 0, Delimitacion::Palabra),
+            Some((5, 12))
+        );
+        assert_eq!(
+            buscador.buscar(b"foo.barx", 0, Delimitacion::Palabra),
+            Some((0, 3))
+        );
+        assert_eq!(
+            buscador.buscar(b"foo.bar", 0, Delimitacion::Linea),
+            Some((0, 7))
+        );
+    }
+
+    #[test]
+    fn busca_ignorando_mayusculas() {
+        let buscador = BuscadorCadenas::new(["ñandú"], true);
+
+        assert_eq!(
+            buscador.buscar("el ÑANDÚ".as_bytes(), 0, Delimitacion::Ninguna),
+            Some((3, 10))
+        );
+        assert_eq!(
+            buscador.buscar("\u{212a} ÑanDú".as_bytes(), 0, Delimitacion::Palabra),
+            Some((4, 11))
+        );
+        assert!(!buscador.matchea(b"nandu"));
+    }
+
+    #[test]
+    fn plegado_de_mayusculas_con_posiciones() {
+        let (plegado, posiciones) = plegar_mayusculas("A\u{212a}\u{ff}Ñ".as_bytes());
+
+        assert_eq!(plegado, "ak\u{ff}ñ".as_bytes());
+        assert_eq!(posiciones, vec![0, 1, 4, 4, 6, 6, 8]);
+    }
+}
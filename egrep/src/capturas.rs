@@ -0,0 +1,156 @@
+/// Capturas de una coincidencia con una expresión regular: las posiciones de la coincidencia
+/// completa (grupo 0) y de lo que matcheó cada uno de sus grupos en el valor evaluado.
+///
+/// Los grupos que no participaron de la coincidencia no tienen captura.
+#[derive(Debug, PartialEq)]
+pub struct Capturas<'a> {
+    valor: &'a [u8],
+    posiciones: Vec<Option<(usize, usize)>>,
+    nombres: &'a [(String, usize)],
+}
+
+impl<'a> Capturas<'a> {
+    /// Creación de las capturas en un valor a partir de las posiciones de cada grupo (la primera
+    /// es la de la coincidencia completa) y de los nombres de los grupos con nombre.
+    pub(crate) fn new(
+        valor: &'a [u8],
+        posiciones: Vec<Option<(usize, usize)>>,
+        nombres: &'a [(String, usize)],
+    ) -> Self {
+        Self {
+            valor,
+            posiciones,
+            nombres,
+        }
+    }
+
+    /// Devuelve las posiciones de inicio y fin de lo que matcheó un grupo (0 para la coincidencia
+    /// completa), o None si el grupo no existe o no participó de la coincidencia.
+    pub fn posiciones(&self, numero: usize) -> Option<(usize, usize)> {
+        self.posiciones.get(numero).copied().flatten()
+    }
+
+    /// Devuelve lo que matcheó un grupo (0 para la coincidencia completa), o None si el grupo no
+    /// existe o no participó de la coincidencia.
+    pub fn get(&self, numero: usize) -> Option<&'a [u8]> {
+        self.posiciones(numero)
+            .map(|(inicio, fin)| &self.valor[inicio..fin])
+    }
+
+    /// Devuelve lo que matcheó el grupo con un nombre (ej.: `(?<anio>\d+)`, en sintaxis compatible
+    /// con Perl), o None si no hay un grupo con ese nombre o no participó de la coincidencia.
+    pub fn nombre(&self, nombre: &str) -> Option<&'a [u8]> {
+        self.nombres
+            .iter()
+            .filter(|(n, _)| n == nombre)
+            .find_map(|(_, numero)| self.get(*numero))
+    }
+
+    /// Agrega a un destino una plantilla de reemplazo, expandiendo en ella las referencias a las
+    /// capturas:
+    ///
+    /// * `$n` o `${n}`: lo que matcheó el grupo número n (`$0` es la coincidencia completa).
+    /// * `${nombre}`: lo que matcheó el grupo con ese nombre.
+    /// * `\n`, con n entre 0 y 9: lo mismo que `$n`.
+    /// * `$$` y `\\`: un `$` y una `\` literales.
+    ///
+    /// Las referencias a grupos inexistentes o que no participaron de la coincidencia se expanden
+    /// como vacías. Un `$` o una `\` que no forman una referencia se copian literalmente.
+    pub fn expandir(&self, plantilla: &str, destino: &mut Vec<u8>) {
+        let mut resto = plantilla;
+        while let Some(posicion) = resto.find(['$', '\\']) {
+            destino.extend_from_slice(&resto.as_bytes()[..posicion]);
+            let (referencia, siguiente) = Self::referencia(&resto[posicion..]);
+            match referencia {
+                Referencia::Literal(literal) => destino.extend_from_slice(literal.as_bytes()),
+                Referencia::Numero(numero) => {
+                    destino.extend_from_slice(self.get(numero).unwrap_or_default())
+                }
+                Referencia::Nombre(nombre) => {
+                    destino.extend_from_slice(self.nombre(nombre).unwrap_or_default())
+                }
+            }
+            resto = siguiente;
+        }
+        destino.extend_from_slice(resto.as_bytes());
+    }
+
+    /// Obtiene la referencia al comienzo de una plantilla (que comienza con `$` o `\`), y
+    /// devuelve también el resto de la plantilla.
+    fn referencia(plantilla: &str) -> (Referencia<'_>, &str) {
+        let (simbolo, resto) = plantilla.split_at(1);
+        let digitos = |largo_maximo: usize| {
+            resto
+                .bytes()
+                .take(largo_maximo)
+                .take_while(u8::is_ascii_digit)
+                .count()
+        };
+        match (simbolo, resto.chars().next()) {
+            ("$", Some('$')) | ("\\", Some('\\')) => (Referencia::Literal(simbolo), &resto[1..]),
+            ("$", Some('{')) => match resto.find('}') {
+                Some(cierre) => {
+                    let nombre = &resto[1..cierre];
+                    let referencia = match nombre.parse() {
+                        Ok(numero) => Referencia::Numero(numero),
+                        Err(_) => Referencia::Nombre(nombre),
+                    };
+                    (referencia, &resto[cierre + 1..])
+                }
+                None => (Referencia::Literal(simbolo), resto),
+            },
+            ("$", _) if digitos(usize::MAX) > 0 => {
+                let largo = digitos(usize::MAX);
+                (Self::numero(&resto[..largo]), &resto[largo..])
+            }
+            ("\\", _) if digitos(1) > 0 => (Self::numero(&resto[..1]), &resto[1..]),
+            _ => (Referencia::Literal(simbolo), resto),
+        }
+    }
+
+    fn numero(digitos: &str) -> Referencia<'_> {
+        // Un número demasiado grande no corresponde a ningún grupo.
+        Referencia::Numero(digitos.parse().unwrap_or(usize::MAX))
+    }
+}
+
+/// Referencia a una captura (o texto literal) en una plantilla de reemplazo.
+enum Referencia<'p> {
+    Literal(&'p str),
+    Numero(usize),
+    Nombre(&'p str),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expandir(capturas: &Capturas, plantilla: &str) -> String {
+        let mut destino = Vec::new();
+        capturas.expandir(plantilla, &mut destino);
+        String::from_utf8(destino).unwrap()
+    }
+
+    #[test]
+    fn expansion_de_referencias_a_grupos() {
+        let nombres = vec![("dia".to_string(), 2)];
+        let capturas = Capturas::new(
+            b"mes 10 dia 25",
+            vec![Some((4, 13)), Some((4, 6)), Some((11, 13)), None],
+            &nombres,
+        );
+
+        assert_eq!(capturas.get(1), Some(&b"10"[..]));
+        assert_eq!(capturas.nombre("dia"), Some(&b"25"[..]));
+        assert_eq!(capturas.get(3), None);
+        assert_eq!(expandir(&capturas, "$2/$1"), "25/10");
+        assert_eq!(expandir(&capturas, "${dia}-${1}0"), "25-100");
+        assert_eq!(expandir(&capturas, r"\2\1\0"), "251010 dia 25");
+        assert_eq!(expandir(&capturas, "[$3$9${mes}]"), "[]");
+        assert_eq!(
+            expandir(&capturas, r"$$1 \\1 $x \q ${1"),
+            r"$1 \1 $x \q ${1"
+        );
+        assert_eq!(expandir(&capturas, "ñ$1ñ"), "ñ10ñ");
+    }
+}
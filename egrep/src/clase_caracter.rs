@@ -1,16 +1,33 @@
-use crate::error_expresion_bracket::ErrorExpresionBracket;
+use crate::{
+    decodificacion_utf8::decodificar_primer_caracter,
+    error_expresion_bracket::ErrorExpresionBracket, propiedad_unicode::PropiedadUnicode,
+};
 
 /// Representación de una clase de caractes de una expresión bracket.
-#[derive(Debug, PartialEq)]
+///
+/// Una clase de equivalencia (\[=a=]) guarda la letra base de sus caracteres: matchea con todos
+/// los que tienen esa misma letra base (ej.: a, á, à, ä).
+///
+/// Las clases POSIX solo contienen caracteres ASCII; en modo Unicode se convierten en
+/// [propiedades de Unicode][PropiedadUnicode] (ver [ClaseCaracter::unicode]).
+#[derive(Debug, PartialEq, Clone)]
 pub enum ClaseCaracter {
     Literal(char),
+    Equivalencia(char),
+    Propiedad(PropiedadUnicode),
     Alfanumerico,
     Alfabetico,
     Digito,
+    DigitoHexadecimal,
     Minuscula,
     Mayuscula,
     Espacio,
+    Blanco,
+    Control,
+    Grafico,
+    Imprimible,
     Puntuacion,
+    Palabra,
 }
 
 impl ClaseCaracter {
@@ -25,19 +42,55 @@ impl ClaseCaracter {
             "alnum" => Self::Alfanumerico,
             "alpha" => Self::Alfabetico,
             "digit" => Self::Digito,
+            "xdigit" => Self::DigitoHexadecimal,
             "lower" => Self::Minuscula,
             "upper" => Self::Mayuscula,
             "space" => Self::Espacio,
+            "blank" => Self::Blanco,
+            "cntrl" => Self::Control,
+            "graph" => Self::Grafico,
+            "print" => Self::Imprimible,
             "punct" => Self::Puntuacion,
+            "word" => Self::Palabra,
             _ => return Err(ErrorExpresionBracket::NombreClaseInvalido),
         };
         Ok(clase)
     }
 
-    /// Matcheo de una clase de caracter con un valor, recibido como string slice.
+    /// Devuelve las clases equivalentes en modo Unicode: cada clase POSIX se convierte en las
+    /// categorías generales de Unicode que le corresponden (ej.: alpha en L, digit en Nd, punct
+    /// en P y S), salvo xdigit, que sigue siendo ASCII. Las demás clases no cambian.
+    pub fn unicode(self) -> Vec<Self> {
+        let categorias = |categorias: &[&str]| {
+            vec![Self::Propiedad(PropiedadUnicode::de_categorias(categorias))]
+        };
+        match self {
+            Self::Alfanumerico => categorias(&["L", "Nd"]),
+            Self::Alfabetico => categorias(&["L"]),
+            Self::Digito => categorias(&["Nd"]),
+            Self::Minuscula => categorias(&["Ll"]),
+            Self::Mayuscula => categorias(&["Lu"]),
+            Self::Espacio => PropiedadUnicode::new("White_Space")
+                .map(Self::Propiedad)
+                .into_iter()
+                .collect(),
+            Self::Blanco => vec![
+                Self::Propiedad(PropiedadUnicode::de_categorias(&["Zs"])),
+                Self::Literal('\t'),
+            ],
+            Self::Control => categorias(&["Cc"]),
+            Self::Grafico => categorias(&["L", "M", "N", "P", "S"]),
+            Self::Imprimible => categorias(&["L", "M", "N", "P", "S", "Zs"]),
+            Self::Puntuacion => categorias(&["P", "S"]),
+            Self::Palabra => categorias(&["L", "M", "Nd", "Pc"]),
+            clase => vec![clase],
+        }
+    }
+
+    /// Matcheo de una clase de caracter con un valor, recibido como slice de bytes.
     ///
     /// En todos los casos, se matchea con el primer caracter del valor evaluado.
-    /// Si el valor evaluado está vacío, se devuelve 0.
+    /// Si el valor evaluado está vacío o no comienza con un caracter utf8 válido, se devuelve 0.
     ///
     /// Si se matchea con el caracter evaluado, se devuelve su largo en utf8.
     /// Caso contrario, se devuelve 0.
@@ -46,19 +99,31 @@ impl ClaseCaracter {
     ///
     /// * Un literal matchea con un caracter si son iguales.
     ///
+    /// * Una clase de equivalencia matchea con un caracter si tiene su misma letra base.
+    ///
+    /// * Una propiedad de Unicode matchea con un caracter si este la cumple.
+    ///
     /// * Las demás clases matchean con un caracter según si
     ///   este pertenece a la clase (según indica el nombre de la misma).
-    pub fn matchea(&self, valor: &str) -> usize {
-        if let Some(c) = valor.chars().next() {
+    pub fn matchea(&self, valor: &[u8]) -> usize {
+        if let Some(Ok(c)) = decodificar_primer_caracter(valor) {
             match self {
                 Self::Alfanumerico => matchear_con_alfanumerico(c),
                 Self::Alfabetico => matchear_con_alfabetico(c),
                 Self::Digito => matchear_con_digito(c),
+                Self::DigitoHexadecimal => matchear_si(c.is_ascii_hexdigit(), c),
                 Self::Minuscula => matchear_con_minuscula(c),
                 Self::Mayuscula => matchear_con_mayuscula(c),
                 Self::Espacio => matchear_con_espacio(c),
+                Self::Blanco => matchear_si((c == ' ') | (c == '\t'), c),
+                Self::Control => matchear_si(c.is_ascii_control(), c),
+                Self::Grafico => matchear_si(c.is_ascii_graphic(), c),
+                Self::Imprimible => matchear_si(c.is_ascii_graphic() | (c == ' '), c),
                 Self::Puntuacion => matchear_con_puntuacion(c),
+                Self::Palabra => matchear_si(c.is_ascii_alphanumeric() | (c == '_'), c),
                 Self::Literal(l) => matchear_con_literal(*l, c),
+                Self::Equivalencia(base) => matchear_si(letra_base(c) == *base, c),
+                Self::Propiedad(propiedad) => matchear_si(propiedad.contiene(c), c),
             }
         } else {
             0
@@ -122,6 +187,38 @@ fn matchear_con_puntuacion(c: char) -> usize {
     }
 }
 
+fn matchear_si(pertenece: bool, c: char) -> usize {
+    if pertenece {
+        c.len_utf8()
+    } else {
+        0
+    }
+}
+
+/// Devuelve la letra base de un caracter latino, sin acentos ni otros diacríticos (ej.: á → a,
+/// Ñ → N). Los demás caracteres se devuelven sin cambios.
+pub(crate) fn letra_base(c: char) -> char {
+    match c {
+        'à'..='å' => 'a',
+        'À'..='Å' => 'A',
+        'ç' => 'c',
+        'Ç' => 'C',
+        'è'..='ë' => 'e',
+        'È'..='Ë' => 'E',
+        'ì'..='ï' => 'i',
+        'Ì'..='Ï' => 'I',
+        'ñ' => 'n',
+        'Ñ' => 'N',
+        'ò'..='ö' | 'ø' => 'o',
+        'Ò'..='Ö' | 'Ø' => 'O',
+        'ù'..='ü' => 'u',
+        'Ù'..='Ü' => 'U',
+        'ý' | 'ÿ' => 'y',
+        'Ý' => 'Y',
+        c => c,
+    }
+}
+
 fn matchear_con_literal(l: char, c: char) -> usize {
     if c == l {
         c.len_utf8()
@@ -152,6 +249,11 @@ mod tests {
         assert_eq!(space, Ok(ClaseCaracter::Espacio));
         assert_eq!(lower, Ok(ClaseCaracter::Minuscula));
         assert_eq!(upper, Ok(ClaseCaracter::Mayuscula));
+        assert_eq!(
+            ClaseCaracter::new("xdigit"),
+            Ok(ClaseCaracter::DigitoHexadecimal)
+        );
+        assert_eq!(ClaseCaracter::new("word"), Ok(ClaseCaracter::Palabra));
         assert_eq!(
             clase_inexistente,
             Err(ErrorExpresionBracket::NombreClaseInvalido)
@@ -170,4 +272,46 @@ mod tests {
         assert_eq!(0, matchear_con_mayuscula(valor));
         assert_eq!(0, matchear_con_puntuacion(valor));
     }
+
+    #[test]
+    fn matcheos_con_clases_posix_completas() {
+        assert_eq!(ClaseCaracter::DigitoHexadecimal.matchea(b"F"), 1);
+        assert_eq!(ClaseCaracter::DigitoHexadecimal.matchea(b"g"), 0);
+        assert_eq!(ClaseCaracter::Blanco.matchea(b"\t"), 1);
+        assert_eq!(ClaseCaracter::Blanco.matchea(b"\n"), 0);
+        assert_eq!(ClaseCaracter::Control.matchea(b"\x07"), 1);
+        assert_eq!(ClaseCaracter::Grafico.matchea(b" "), 0);
+        assert_eq!(ClaseCaracter::Imprimible.matchea(b" "), 1);
+        assert_eq!(ClaseCaracter::Palabra.matchea(b"_"), 1);
+        assert_eq!(ClaseCaracter::Palabra.matchea(b"-"), 0);
+    }
+
+    #[test]
+    fn matcheos_con_clase_de_equivalencia() {
+        let clase = ClaseCaracter::Equivalencia('e');
+
+        assert_eq!(clase.matchea("é".as_bytes()), 2);
+        assert_eq!(clase.matchea(b"e"), 1);
+        assert_eq!(clase.matchea("É".as_bytes()), 0);
+        assert_eq!(letra_base('Ñ'), 'N');
+    }
+
+    #[test]
+    fn clases_en_modo_unicode() {
+        let matchea = |clase: ClaseCaracter, valor: &str| {
+            clase
+                .unicode()
+                .iter()
+                .any(|clase| clase.matchea(valor.as_bytes()) > 0)
+        };
+
+        assert!(matchea(ClaseCaracter::Alfabetico, "ñ"));
+        assert!(matchea(ClaseCaracter::Mayuscula, "É"));
+        assert!(!matchea(ClaseCaracter::Minuscula, "É"));
+        assert!(matchea(ClaseCaracter::Puntuacion, "¿"));
+        assert!(matchea(ClaseCaracter::Espacio, "\u{2003}"));
+        assert!(matchea(ClaseCaracter::Blanco, "\t"));
+        assert!(!matchea(ClaseCaracter::DigitoHexadecimal, "٣"));
+        assert!(!matchea(ClaseCaracter::Palabra, "-"));
+    }
 }
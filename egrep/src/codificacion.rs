@@ -0,0 +1,456 @@
+use std::io::{self, Read};
+
+use crate::{decodificacion_utf8::decodificar_primer_caracter, error_argumentos::ErrorArgumentos};
+
+const BOM_UTF8: &[u8] = &[0xEF, 0xBB, 0xBF];
+const BOM_UTF16LE: &[u8] = &[0xFF, 0xFE];
+const BOM_UTF16BE: &[u8] = &[0xFE, 0xFF];
+const TAMAÑO_LECTURA: usize = 8 * 1024;
+
+/// Caracteres de windows-1252 para los bytes 0x80 a 0x9F. Los bytes sin asignar se
+/// decodifican como el caracter de control homónimo, igual que en latin1.
+const WINDOWS_1252: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž', '\u{8F}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}', 'ž', 'Ÿ',
+];
+
+/// Codificaciones de texto que pueden leerse y transcodificarse a utf8.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Codificacion {
+    Utf8,
+    Latin1,
+    Windows1252,
+    Utf16Le,
+    Utf16Be,
+    Utf16,
+}
+
+impl Codificacion {
+    /// Creación de una codificación a través de su nombre (sin distinguir mayúsculas de minúsculas).
+    ///
+    /// Nombres admitidos: utf-8 (utf8), latin1 (iso-8859-1), windows-1252 (cp1252),
+    /// utf-16le, utf-16be y utf-16 (cuyo orden de bytes se toma del BOM del archivo, o es el de
+    /// utf-16le si no tiene BOM).
+    ///
+    /// # Errores
+    ///
+    /// Si el nombre no corresponde a ninguna codificación conocida, se devuelve el error correspondiente.
+    pub fn new(nombre: &str) -> Result<Self, ErrorArgumentos> {
+        let codificacion = match nombre.to_ascii_lowercase().as_str() {
+            "utf-8" | "utf8" => Self::Utf8,
+            "latin1" | "latin-1" | "iso-8859-1" | "iso8859-1" => Self::Latin1,
+            "windows-1252" | "cp1252" => Self::Windows1252,
+            "utf-16le" | "utf16le" => Self::Utf16Le,
+            "utf-16" | "utf16" => Self::Utf16,
+            "utf-16be" | "utf16be" => Self::Utf16Be,
+            _ => return Err(ErrorArgumentos::CodificacionDesconocida(nombre.to_string())),
+        };
+        Ok(codificacion)
+    }
+
+    /// Detecta la codificación de un texto según su BOM (byte order mark), si lo tiene.
+    ///
+    /// Devuelve la codificación y el largo del BOM, para que pueda descartarse.
+    pub fn detectar(inicio: &[u8]) -> Option<(Self, usize)> {
+        [Self::Utf8, Self::Utf16Le, Self::Utf16Be]
+            .into_iter()
+            .find(|codificacion| inicio.starts_with(codificacion.bom()))
+            .map(|codificacion| (codificacion, codificacion.bom().len()))
+    }
+
+    /// Devuelve la codificación con la que se decodifica un texto, según la codificación indicada
+    /// (si se indicó) y su inicio, junto con el largo de su BOM (0 si no tiene). Si no se indicó
+    /// una codificación y el texto no tiene BOM, devuelve None: el texto se lee sin
+    /// modificaciones.
+    pub fn del_texto(indicada: Option<Self>, inicio: &[u8]) -> Option<(Self, usize)> {
+        match indicada {
+            Some(codificacion) => {
+                let codificacion = codificacion.resolver(inicio);
+                let bom = codificacion.bom();
+                let largo_bom = if !bom.is_empty() && inicio.starts_with(bom) {
+                    bom.len()
+                } else {
+                    0
+                };
+                Some((codificacion, largo_bom))
+            }
+            None => Self::detectar(inicio),
+        }
+    }
+
+    /// BOM de la codificación. Las codificaciones de un byte por caracter no tienen BOM, y utf-16
+    /// sin orden de bytes puede tener el de cualquiera de los dos órdenes (ver
+    /// [Codificacion::resolver]).
+    fn bom(&self) -> &'static [u8] {
+        match self {
+            Self::Utf8 => BOM_UTF8,
+            Self::Utf16Le => BOM_UTF16LE,
+            Self::Utf16Be => BOM_UTF16BE,
+            Self::Latin1 | Self::Windows1252 | Self::Utf16 => &[],
+        }
+    }
+
+    /// Devuelve la codificación concreta con la que se decodifica un texto en esta codificación,
+    /// según su inicio: utf-16 se decodifica como utf-16be si el texto tiene su BOM, o como
+    /// utf-16le si no. Las demás codificaciones no cambian.
+    fn resolver(self, inicio: &[u8]) -> Self {
+        match self {
+            Self::Utf16 if inicio.starts_with(BOM_UTF16BE) => Self::Utf16Be,
+            Self::Utf16 => Self::Utf16Le,
+            _ => self,
+        }
+    }
+
+    /// Decodifica todas las unidades completas de un texto en esta codificación, agregándolas
+    /// como utf8 a un vector de salida. Devuelve la cantidad de bytes consumidos.
+    ///
+    /// Si el texto termina con una unidad incompleta (por ejemplo, un byte suelto en utf-16),
+    /// esta no se consume, salvo que sea el final de la entrada: en ese caso se decodifica
+    /// como el caracter de reemplazo.
+    ///
+    /// Los bytes utf8 se copian sin validar, para que puedan matchearse byte a byte.
+    fn decodificar(&self, texto: &[u8], salida: &mut Vec<u8>, fin_entrada: bool) -> usize {
+        match self {
+            Self::Utf8 => {
+                salida.extend_from_slice(texto);
+                texto.len()
+            }
+            Self::Latin1 => {
+                for byte in texto {
+                    agregar_caracter(char::from(*byte), salida);
+                }
+                texto.len()
+            }
+            Self::Windows1252 => {
+                for byte in texto {
+                    let caracter = match byte {
+                        0x80..=0x9F => WINDOWS_1252[(byte - 0x80) as usize],
+                        _ => char::from(*byte),
+                    };
+                    agregar_caracter(caracter, salida);
+                }
+                texto.len()
+            }
+            Self::Utf16Le | Self::Utf16 => {
+                decodificar_utf16(texto, salida, fin_entrada, u16::from_le_bytes)
+            }
+            Self::Utf16Be => decodificar_utf16(texto, salida, fin_entrada, u16::from_be_bytes),
+        }
+    }
+
+    /// Codifica un texto utf8 en esta codificación, agregándolo a un vector de salida (la
+    /// inversa de la decodificación). En utf8, los bytes se copian sin validar.
+    ///
+    /// # Errores
+    ///
+    /// Si el texto tiene un caracter que no puede representarse en esta codificación (ej.: '€' en
+    /// latin1), se devuelve ese caracter. Los bytes que no forman parte de un caracter utf8 válido
+    /// se consideran el caracter de reemplazo.
+    pub fn codificar(&self, texto: &[u8], salida: &mut Vec<u8>) -> Result<(), char> {
+        if *self == Self::Utf8 {
+            salida.extend_from_slice(texto);
+            return Ok(());
+        }
+        let mut resto = texto;
+        while let Some(decodificado) = decodificar_primer_caracter(resto) {
+            let caracter = decodificado.unwrap_or(char::REPLACEMENT_CHARACTER);
+            resto = &resto[decodificado.map_or(1, char::len_utf8)..];
+            let mut unidades = [0; 2];
+            match self {
+                Self::Utf8 => {}
+                Self::Latin1 => salida.push(u8::try_from(caracter).map_err(|_| caracter)?),
+                Self::Windows1252 => {
+                    let byte = match WINDOWS_1252.iter().position(|c| *c == caracter) {
+                        Some(indice) => 0x80 + indice as u8,
+                        None => match u8::try_from(caracter) {
+                            Ok(byte) if !(0x80..=0x9F).contains(&byte) => byte,
+                            _ => return Err(caracter),
+                        },
+                    };
+                    salida.push(byte);
+                }
+                Self::Utf16Le | Self::Utf16 => {
+                    for unidad in caracter.encode_utf16(&mut unidades) {
+                        salida.extend_from_slice(&unidad.to_le_bytes());
+                    }
+                }
+                Self::Utf16Be => {
+                    for unidad in caracter.encode_utf16(&mut unidades) {
+                        salida.extend_from_slice(&unidad.to_be_bytes());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn agregar_caracter(caracter: char, salida: &mut Vec<u8>) {
+    let mut buffer = [0; 4];
+    salida.extend_from_slice(caracter.encode_utf8(&mut buffer).as_bytes());
+}
+
+/// Decodificación de utf-16 con el orden de bytes que indica la función recibida.
+///
+/// Los surrogates sin par se decodifican como el caracter de reemplazo.
+fn decodificar_utf16(
+    texto: &[u8],
+    salida: &mut Vec<u8>,
+    fin_entrada: bool,
+    desde_bytes: fn([u8; 2]) -> u16,
+) -> usize {
+    let mut consumidos = 0;
+    while consumidos + 2 <= texto.len() {
+        let unidad = desde_bytes([texto[consumidos], texto[consumidos + 1]]);
+        let mut largo = 2;
+        let caracter = if (0xD800..0xDC00).contains(&unidad) {
+            if consumidos + 4 > texto.len() {
+                if !fin_entrada {
+                    break;
+                }
+                char::REPLACEMENT_CHARACTER
+            } else {
+                let siguiente = desde_bytes([texto[consumidos + 2], texto[consumidos + 3]]);
+                if (0xDC00..0xE000).contains(&siguiente) {
+                    largo = 4;
+                    let codigo = 0x10000
+                        + ((u32::from(unidad) - 0xD800) << 10)
+                        + (u32::from(siguiente) - 0xDC00);
+                    char::from_u32(codigo).unwrap_or(char::REPLACEMENT_CHARACTER)
+                } else {
+                    char::REPLACEMENT_CHARACTER
+                }
+            }
+        } else {
+            char::from_u32(u32::from(unidad)).unwrap_or(char::REPLACEMENT_CHARACTER)
+        };
+        agregar_caracter(caracter, salida);
+        consumidos += largo;
+    }
+    if fin_entrada && consumidos < texto.len() {
+        agregar_caracter(char::REPLACEMENT_CHARACTER, salida);
+        consumidos = texto.len();
+    }
+    consumidos
+}
+
+/// Lector que transcodifica a utf8 el contenido de otro lector.
+///
+/// Si no se indica una codificación, se detecta a través del BOM del contenido. Si el contenido
+/// no tiene BOM, se lee sin modificaciones. En todos los casos, el BOM se descarta, para que el
+/// inicio de la primera línea sea el inicio real del texto.
+pub struct LectorDecodificado<R: Read> {
+    interno: R,
+    codificacion: Option<Codificacion>,
+    inicio_procesado: bool,
+    pendientes: Vec<u8>,
+    decodificados: Vec<u8>,
+    posicion: usize,
+    fin_entrada: bool,
+}
+
+impl<R: Read> LectorDecodificado<R> {
+    /// Creación del lector, dado el lector interno y la codificación de su contenido (si se conoce).
+    pub fn new(interno: R, codificacion: Option<Codificacion>) -> Self {
+        Self {
+            interno,
+            codificacion,
+            inicio_procesado: false,
+            pendientes: Vec::new(),
+            decodificados: Vec::new(),
+            posicion: 0,
+            fin_entrada: false,
+        }
+    }
+
+    /// Lee del lector interno hasta tener al menos una cantidad de bytes pendientes, o hasta
+    /// el final de la entrada.
+    fn leer_pendientes(&mut self, cantidad: usize) -> io::Result<()> {
+        let mut buffer = [0; TAMAÑO_LECTURA];
+        while !self.fin_entrada && self.pendientes.len() < cantidad {
+            let leidos = self.interno.read(&mut buffer)?;
+            if leidos == 0 {
+                self.fin_entrada = true;
+            }
+            self.pendientes.extend_from_slice(&buffer[..leidos]);
+        }
+        Ok(())
+    }
+
+    /// Detecta la codificación del contenido (si no se indicó) y descarta el BOM.
+    fn procesar_inicio(&mut self) -> io::Result<()> {
+        self.leer_pendientes(BOM_UTF8.len())?;
+        if let Some((codificacion, largo_bom)) =
+            Codificacion::del_texto(self.codificacion, &self.pendientes)
+        {
+            self.codificacion = Some(codificacion);
+            self.pendientes.drain(..largo_bom);
+        }
+        self.inicio_procesado = true;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for LectorDecodificado<R> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        if !self.inicio_procesado {
+            self.procesar_inicio()?;
+        }
+        while self.posicion == self.decodificados.len() {
+            if self.pendientes.is_empty() && self.fin_entrada {
+                return Ok(0);
+            }
+            self.leer_pendientes(1)?;
+            self.decodificados.clear();
+            self.posicion = 0;
+            let codificacion = self.codificacion.unwrap_or(Codificacion::Utf8);
+            let consumidos = codificacion.decodificar(
+                &self.pendientes,
+                &mut self.decodificados,
+                self.fin_entrada,
+            );
+            self.pendientes.drain(..consumidos);
+            if consumidos == 0 && !self.fin_entrada {
+                // Unidad incompleta: se necesita leer más del lector interno.
+                let faltantes = self.pendientes.len() + 1;
+                self.leer_pendientes(faltantes)?;
+            }
+        }
+        let cantidad = buffer.len().min(self.decodificados.len() - self.posicion);
+        buffer[..cantidad]
+            .copy_from_slice(&self.decodificados[self.posicion..self.posicion + cantidad]);
+        self.posicion += cantidad;
+        Ok(cantidad)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leer_todo(contenido: &[u8], codificacion: Option<Codificacion>) -> Vec<u8> {
+        let mut lector = LectorDecodificado::new(contenido, codificacion);
+        let mut salida = Vec::new();
+        lector.read_to_end(&mut salida).unwrap();
+        salida
+    }
+
+    #[test]
+    fn creacion_de_codificaciones() {
+        assert_eq!(Codificacion::new("UTF-16LE"), Ok(Codificacion::Utf16Le));
+        assert_eq!(Codificacion::new("latin1"), Ok(Codificacion::Latin1));
+        assert_eq!(
+            Codificacion::new("ebcdic"),
+            Err(ErrorArgumentos::CodificacionDesconocida(
+                "ebcdic".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn detecta_bom_y_lo_descarta() {
+        let utf8 = [0xEF, 0xBB, 0xBF, b'h', b'o', b'l', b'a'];
+        let utf16le = [0xFF, 0xFE, b'h', 0, b'o', 0, b'l', 0, b'a', 0];
+        let utf16be = [0xFE, 0xFF, 0, b'h', 0, b'o', 0, b'l', 0, b'a'];
+
+        assert_eq!(b"hola".to_vec(), leer_todo(&utf8, None));
+        assert_eq!(b"hola".to_vec(), leer_todo(&utf16le, None));
+        assert_eq!(b"hola".to_vec(), leer_todo(&utf16be, None));
+    }
+
+    #[test]
+    fn sin_bom_ni_codificacion_no_modifica_contenido() {
+        let contenido = [b's', b'e', 0xF1, b'a', b'l'];
+
+        assert_eq!(contenido.to_vec(), leer_todo(&contenido, None));
+    }
+
+    #[test]
+    fn decodifica_codificaciones_explicitas() {
+        let latin1 = [b's', b'e', 0xF1, b'a', b'l'];
+        let windows1252 = [0x80, b'5'];
+        // "𝄞" (fuera del plano básico) en utf-16le, con surrogates.
+        let utf16le = [0x34, 0xD8, 0x1E, 0xDD];
+
+        assert_eq!(
+            "señal".as_bytes().to_vec(),
+            leer_todo(&latin1, Some(Codificacion::Latin1))
+        );
+        assert_eq!(
+            "€5".as_bytes().to_vec(),
+            leer_todo(&windows1252, Some(Codificacion::Windows1252))
+        );
+        assert_eq!(
+            "𝄞".as_bytes().to_vec(),
+            leer_todo(&utf16le, Some(Codificacion::Utf16Le))
+        );
+    }
+
+    #[test]
+    fn utf16_sin_orden_de_bytes_lo_toma_del_bom() {
+        let utf16le = [0xFF, 0xFE, b'h', 0, b'i', 0];
+        let utf16be = [0xFE, 0xFF, 0, b'h', 0, b'i'];
+        let sin_bom = [b'h', 0, b'i', 0];
+
+        assert_eq!(Codificacion::new("UTF-16"), Ok(Codificacion::Utf16));
+        assert_eq!(
+            b"hi".to_vec(),
+            leer_todo(&utf16le, Some(Codificacion::Utf16))
+        );
+        assert_eq!(
+            b"hi".to_vec(),
+            leer_todo(&utf16be, Some(Codificacion::Utf16))
+        );
+        assert_eq!(
+            b"hi".to_vec(),
+            leer_todo(&sin_bom, Some(Codificacion::Utf16))
+        );
+    }
+
+    #[test]
+    fn unidad_incompleta_al_final_se_reemplaza() {
+        let utf16le = [b'a', 0, b'b'];
+
+        assert_eq!(
+            "a\u{FFFD}".as_bytes().to_vec(),
+            leer_todo(&utf16le, Some(Codificacion::Utf16Le))
+        );
+    }
+
+    #[test]
+    fn codifica_como_se_decodifico() {
+        let utf16le = [0xFF, 0xFE, b'h', 0, 0x3D, 0xD8, 0x00, 0xDE];
+        let (codificacion, largo_bom) = Codificacion::del_texto(None, &utf16le).unwrap();
+        let mut salida = utf16le[..largo_bom].to_vec();
+
+        assert_eq!(
+            Ok(()),
+            codificacion.codificar(&leer_todo(&utf16le, None), &mut salida)
+        );
+        assert_eq!(utf16le.to_vec(), salida);
+        assert_eq!(None, Codificacion::del_texto(None, b"hola"));
+        assert_eq!(
+            Some((Codificacion::Utf16Be, 2)),
+            Codificacion::del_texto(Some(Codificacion::Utf16), &[0xFE, 0xFF, 0, b'h'])
+        );
+    }
+
+    #[test]
+    fn codificar_caracteres_no_representables_es_error() {
+        let mut salida = Vec::new();
+
+        assert_eq!(
+            Ok(()),
+            Codificacion::Windows1252.codificar("€ñ".as_bytes(), &mut salida)
+        );
+        assert_eq!(vec![0x80, 0xF1], salida);
+        assert_eq!(
+            Err('€'),
+            Codificacion::Latin1.codificar("€".as_bytes(), &mut Vec::new())
+        );
+        assert_eq!(
+            Err('ā'),
+            Codificacion::Windows1252.codificar("ā".as_bytes(), &mut Vec::new())
+        );
+    }
+}
@@ -0,0 +1,61 @@
+/// Decodificación del primer caracter de una secuencia de bytes.
+///
+/// Si la secuencia está vacía, devuelve None.
+///
+/// Si los primeros bytes forman un caracter utf8 válido, devuelve Ok con el caracter.
+/// Si no, devuelve Err con el primer byte, que debe tratarse como una unidad de un único byte.
+pub fn decodificar_primer_caracter(valor: &[u8]) -> Option<Result<char, u8>> {
+    let primer_byte = *valor.first()?;
+    let largo = largo_secuencia_utf8(primer_byte);
+    if (largo == 0) | (valor.len() < largo) {
+        return Some(Err(primer_byte));
+    }
+    match std::str::from_utf8(&valor[..largo]) {
+        Ok(caracter) => caracter.chars().next().map(Ok),
+        Err(_) => Some(Err(primer_byte)),
+    }
+}
+
+/// Devuelve el largo en bytes de la primera unidad de una secuencia de bytes: el largo en utf8
+/// del primer caracter si es válido, 1 si es un byte inválido o 0 si la secuencia está vacía.
+pub fn largo_primer_caracter(valor: &[u8]) -> usize {
+    match decodificar_primer_caracter(valor) {
+        Some(Ok(c)) => c.len_utf8(),
+        Some(Err(_)) => 1,
+        None => 0,
+    }
+}
+
+/// Largo de la secuencia utf8 que comienza con un byte, según sus bits más significativos.
+/// Devuelve 0 si el byte no puede iniciar una secuencia.
+fn largo_secuencia_utf8(byte: u8) -> usize {
+    match byte {
+        0x00..=0x7F => 1,
+        0xC2..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF4 => 4,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodifica_caracteres_validos() {
+        assert_eq!(Some(Ok('a')), decodificar_primer_caracter(b"abc"));
+        assert_eq!(Some(Ok('ñ')), decodificar_primer_caracter("ñu".as_bytes()));
+        assert_eq!(None, decodificar_primer_caracter(b""));
+    }
+
+    #[test]
+    fn bytes_invalidos_se_toman_de_a_uno() {
+        // 'ñ' en latin1
+        let valor = [0xF1, b'u'];
+
+        assert_eq!(Some(Err(0xF1)), decodificar_primer_caracter(&valor));
+        assert_eq!(1, largo_primer_caracter(&valor));
+        assert_eq!(1, largo_primer_caracter(&[0x80]));
+    }
+}
@@ -0,0 +1,117 @@
+use crate::decodificacion_utf8::decodificar_primer_caracter;
+
+/// Delimitación que debe cumplir una coincidencia con un patrón para ser válida.
+///
+/// * Ninguna: cualquier coincidencia es válida.
+/// * Palabra: la coincidencia debe estar al inicio del valor o precedida por un caracter que no
+///   forme parte de una palabra, y al final del valor o seguida por un caracter que no forme parte
+///   de una palabra. Forman parte de una palabra las letras, los dígitos y '_'.
+/// * Linea: la coincidencia debe abarcar el valor completo.
+/// * Lineas: el valor tiene varias líneas separadas por un terminador, y la coincidencia debe
+///   abarcar una o más líneas completas: comenzar al inicio del valor o luego de un terminador, y
+///   terminar al final del valor o antes de uno.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Delimitacion {
+    #[default]
+    Ninguna,
+    Palabra,
+    Linea,
+    Lineas(u8),
+}
+
+impl Delimitacion {
+    /// Devuelve la delimitación equivalente para un valor con varias líneas separadas por un
+    /// terminador: las palabras ya terminan en él, pero una línea completa pasa a ser cualquiera
+    /// de ellas.
+    pub fn multilinea(self, terminador: u8) -> Self {
+        match self {
+            Delimitacion::Linea => Delimitacion::Lineas(terminador),
+            delimitacion => delimitacion,
+        }
+    }
+
+    /// Devuelve true si una coincidencia puede comenzar en una posición del valor evaluado.
+    pub fn permite_inicio(&self, valor: &[u8], posicion: usize) -> bool {
+        match self {
+            Delimitacion::Ninguna => true,
+            Delimitacion::Palabra => !caracter_de_palabra_anterior(valor, posicion),
+            Delimitacion::Linea => posicion == 0,
+            Delimitacion::Lineas(terminador) => posicion == 0 || valor[posicion - 1] == *terminador,
+        }
+    }
+
+    /// Devuelve true si una coincidencia puede terminar en una posición del valor evaluado.
+    pub fn permite_fin(&self, valor: &[u8], posicion: usize) -> bool {
+        match self {
+            Delimitacion::Ninguna => true,
+            Delimitacion::Palabra => !es_caracter_de_palabra(&valor[posicion..]),
+            Delimitacion::Linea => posicion == valor.len(),
+            Delimitacion::Lineas(terminador) => valor.get(posicion).is_none_or(|b| b == terminador),
+        }
+    }
+}
+
+/// Devuelve true si el valor comienza con un caracter que forma parte de una palabra.
+pub(crate) fn es_caracter_de_palabra(valor: &[u8]) -> bool {
+    matches!(
+        decodificar_primer_caracter(valor),
+        Some(Ok(c)) if c.is_alphanumeric() || c == '_'
+    )
+}
+
+/// Devuelve true si el caracter que termina en una posición del valor forma parte de una palabra.
+pub(crate) fn caracter_de_palabra_anterior(valor: &[u8], posicion: usize) -> bool {
+    // Un caracter utf8 ocupa a lo sumo 4 bytes: se busca dónde comienza el anterior.
+    (posicion.saturating_sub(4)..posicion)
+        .find(|inicio| {
+            matches!(
+                decodificar_primer_caracter(&valor[*inicio..posicion]),
+                Some(Ok(c)) if inicio + c.len_utf8() == posicion
+            )
+        })
+        .is_some_and(|inicio| es_caracter_de_palabra(&valor[inicio..posicion]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delimitacion_de_palabra() {
+        let valor = "año_1 ñu-x".as_bytes();
+
+        assert!(Delimitacion::Palabra.permite_inicio(valor, 0));
+        assert!(!Delimitacion::Palabra.permite_inicio(valor, 3));
+        assert!(Delimitacion::Palabra.permite_inicio(valor, 7));
+        assert!(!Delimitacion::Palabra.permite_fin(valor, 5));
+        assert!(Delimitacion::Palabra.permite_fin(valor, 10));
+        assert!(Delimitacion::Palabra.permite_fin(valor, valor.len()));
+    }
+
+    #[test]
+    fn delimitacion_de_linea() {
+        let valor = b"hola";
+
+        assert!(Delimitacion::Linea.permite_inicio(valor, 0));
+        assert!(!Delimitacion::Linea.permite_inicio(valor, 1));
+        assert!(Delimitacion::Linea.permite_fin(valor, 4));
+        assert!(!Delimitacion::Linea.permite_fin(valor, 3));
+    }
+
+    #[test]
+    fn delimitacion_de_lineas() {
+        let valor = b"ab\ncd";
+        let lineas = Delimitacion::Linea.multilinea(b'\n');
+
+        assert_eq!(lineas, Delimitacion::Lineas(b'\n'));
+        assert_eq!(
+            Delimitacion::Palabra.multilinea(b'\n'),
+            Delimitacion::Palabra
+        );
+        assert!(lineas.permite_inicio(valor, 3));
+        assert!(!lineas.permite_inicio(valor, 1));
+        assert!(lineas.permite_fin(valor, 2));
+        assert!(lineas.permite_fin(valor, valor.len()));
+        assert!(!lineas.permite_fin(valor, 4));
+    }
+}
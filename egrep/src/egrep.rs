@@ -1,9 +1,204 @@
-use crate::lectura_archivos::leer_lineas_archivo;
+use crate::argumentos::{Argumentos, ListadoArchivos};
+use crate::busqueda_cadenas::BuscadorCadenas;
+use crate::capturas::Capturas;
+use crate::decodificacion_utf8::largo_primer_caracter;
+use crate::delimitacion::Delimitacion;
+use crate::error_busqueda::ErrorBusqueda;
+use crate::error_lectura_archivos::ErrorArchivo;
+use crate::error_patron::ErrorPatron;
+use crate::impresora::Impresora;
+use crate::lectura_archivos::{leer_lineas_archivo, Linea};
+use crate::patrones::{compilar_patrones, obtener_patrones, Patron};
 use crate::regex::Regex;
-use std::error;
+use crate::regex_builder::OpcionesRegex;
+use crate::regex_set::RegexSet;
+use crate::searcher::{EstadisticasBusqueda, OpcionesBusqueda, Searcher, Sink};
+use std::{collections::VecDeque, error, io, iter, mem};
 
 type Result<T> = std::result::Result<T, Box<dyn error::Error>>;
 
+/// Resultado de egrep sobre uno de los archivos recibidos: las líneas a imprimir o el error
+/// que se produjo al buscar en él.
+///
+/// Si se deben listar archivos, solo se guarda la primera línea seleccionada.
+#[derive(Debug)]
+pub struct ResultadoArchivo {
+    pub archivo: String,
+    pub lineas: std::result::Result<Vec<LineaResultado>, ErrorBusqueda>,
+}
+
+/// Línea resultado de egrep: una línea seleccionada o una línea de contexto de alguna de estas,
+/// junto con su número de línea en el archivo y la posición de su inicio en el contenido de este
+/// (ya transcodificado a utf8, si corresponde).
+///
+/// Si se deben imprimir solo las coincidencias, se guardan también sus posiciones de inicio y fin
+/// en el contenido de la línea. Si se deben reemplazar las coincidencias, el contenido de una
+/// línea seleccionada es el que resulta de reemplazarlas, y las posiciones son las de los
+/// reemplazos.
+#[derive(Debug, PartialEq)]
+pub struct LineaResultado {
+    pub linea: Linea,
+    pub numero: usize,
+    pub desplazamiento: usize,
+    pub seleccionada: bool,
+    pub coincidencias: Vec<(usize, usize)>,
+}
+
+/// Buscador de coincidencias de los patrones en cada línea: una expresión regular o, si los
+/// patrones son cadenas fijas, un buscador de cadenas. Puede crearse a partir de cualquiera de
+/// los dos o de un [conjunto de expresiones regulares](RegexSet), que matchea si alguna de ellas
+/// matchea (ver [Searcher::new]).
+pub enum Buscador {
+    Regex(Regex),
+    Cadenas(BuscadorCadenas),
+}
+
+impl From<Regex> for Buscador {
+    fn from(regex: Regex) -> Self {
+        Buscador::Regex(regex)
+    }
+}
+
+impl From<BuscadorCadenas> for Buscador {
+    fn from(buscador: BuscadorCadenas) -> Self {
+        Buscador::Cadenas(buscador)
+    }
+}
+
+impl From<RegexSet> for Buscador {
+    fn from(conjunto: RegexSet) -> Self {
+        Buscador::Regex(conjunto.into())
+    }
+}
+
+impl Buscador {
+    /// Creación del buscador de los patrones según los argumentos de egrep: obtiene los patrones
+    /// de sus fuentes y los compila con las opciones que corresponden (ver
+    /// [con_patrones](Self::con_patrones)).
+    pub(crate) fn desde_argumentos(argumentos: &Argumentos) -> Result<Self> {
+        let patrones = obtener_patrones(&argumentos.patrones)?;
+        // En modo multilínea, los anclajes matchean en cada línea y el comodín no cruza de una
+        // línea a otra (separadas por el terminador de la lectura).
+        let opciones = OpcionesRegex {
+            sintaxis: argumentos.sintaxis,
+            ignorar_mayusculas: argumentos.ignorar_mayusculas,
+            unicode: argumentos.unicode,
+            multilinea: argumentos.multilinea,
+            comodin_nueva_linea: !argumentos.multilinea,
+            terminador_linea: argumentos.lectura.terminador,
+            ..OpcionesRegex::default()
+        };
+        Ok(Self::con_patrones(
+            &patrones,
+            argumentos.cadenas_fijas,
+            &opciones,
+        )?)
+    }
+
+    /// Creación del buscador de varios patrones: un [buscador de cadenas](BuscadorCadenas) si
+    /// son cadenas fijas (que solo considera si se ignoran mayúsculas), o una única expresión
+    /// regular compilada con las opciones recibidas (ver [compilar_patrones]).
+    ///
+    /// # Errores
+    ///
+    /// Si alguno de los patrones es inválido, se devuelve su error junto con su origen.
+    pub fn con_patrones(
+        patrones: &[Patron],
+        cadenas_fijas: bool,
+        opciones: &OpcionesRegex,
+    ) -> std::result::Result<Self, ErrorPatron> {
+        if cadenas_fijas {
+            let cadenas = patrones.iter().map(|patron| &patron.texto);
+            return Ok(Buscador::Cadenas(BuscadorCadenas::new(
+                cadenas,
+                opciones.ignorar_mayusculas,
+            )));
+        }
+        Ok(Buscador::Regex(compilar_patrones(patrones, opciones)?))
+    }
+
+    fn buscar(
+        &self,
+        valor: &[u8],
+        desde: usize,
+        delimitacion: Delimitacion,
+    ) -> Option<(usize, usize)> {
+        match self {
+            Buscador::Regex(regex) => regex.buscar(valor, desde, delimitacion),
+            Buscador::Cadenas(buscador) => buscador.buscar(valor, desde, delimitacion),
+        }
+    }
+
+    /// Devuelve true si hay alguna coincidencia en un valor que cumple con una delimitación.
+    pub(crate) fn matchea(&self, valor: &[u8], delimitacion: Delimitacion) -> bool {
+        match self {
+            Buscador::Regex(regex) => regex.hay_coincidencia(valor, delimitacion),
+            Buscador::Cadenas(buscador) => buscador.buscar(valor, 0, delimitacion).is_some(),
+        }
+    }
+
+    /// Devuelve las posiciones de inicio y fin de cada coincidencia no vacía en un valor que
+    /// cumple con una delimitación, sin superponerse.
+    fn coincidencias(&self, valor: &[u8], delimitacion: Delimitacion) -> Vec<(usize, usize)> {
+        let mut coincidencias = self.todas_las_coincidencias(valor, delimitacion);
+        coincidencias.retain(|(inicio, fin)| fin > inicio);
+        coincidencias
+    }
+
+    /// Devuelve las posiciones de inicio y fin de cada coincidencia (incluidas las vacías) en un
+    /// valor que cumple con una delimitación, sin superponerse. Luego de una coincidencia vacía,
+    /// se sigue buscando desde el siguiente caracter.
+    fn todas_las_coincidencias(
+        &self,
+        valor: &[u8],
+        delimitacion: Delimitacion,
+    ) -> Vec<(usize, usize)> {
+        let mut coincidencias = Vec::new();
+        let mut desde = 0;
+        while let Some((inicio, fin)) = self.buscar(valor, desde, delimitacion) {
+            coincidencias.push((inicio, fin));
+            if fin > inicio {
+                desde = fin;
+            } else if inicio < valor.len() {
+                desde = inicio + largo_primer_caracter(&valor[inicio..]);
+            } else {
+                break;
+            }
+        }
+        coincidencias
+    }
+
+    /// Devuelve un valor con cada coincidencia no vacía que cumple con una delimitación reemplazada
+    /// por una plantilla (ver [Capturas::expandir]), junto con las posiciones de inicio y fin de
+    /// cada reemplazo en él.
+    ///
+    /// Las cadenas fijas no tienen grupos, por lo que solo pueden referenciar la coincidencia
+    /// completa (`$0`).
+    pub(crate) fn reemplazar(
+        &self,
+        valor: &[u8],
+        delimitacion: Delimitacion,
+        plantilla: &str,
+    ) -> (Vec<u8>, Vec<(usize, usize)>) {
+        let mut reemplazado = Vec::with_capacity(valor.len());
+        let mut reemplazos = Vec::new();
+        let mut copiado = 0;
+        for (inicio, fin) in self.coincidencias(valor, delimitacion) {
+            reemplazado.extend_from_slice(&valor[copiado..inicio]);
+            let inicio_reemplazo = reemplazado.len();
+            let capturas = match self {
+                Buscador::Regex(regex) => regex.capturas_de(valor, (inicio, fin)),
+                Buscador::Cadenas(_) => Capturas::new(valor, vec![Some((inicio, fin))], &[]),
+            };
+            capturas.expandir(plantilla, &mut reemplazado);
+            reemplazos.push((inicio_reemplazo, reemplazado.len()));
+            copiado = fin;
+        }
+        reemplazado.extend_from_slice(&valor[copiado..]);
+        (reemplazado, reemplazos)
+    }
+}
+
 /// Impresión por pantalla de los resultados de egrep
 pub fn imprimir(resultado: Result<Vec<String>>) {
     match resultado {
@@ -16,6 +211,65 @@ pub fn imprimir(resultado: Result<Vec<String>>) {
     }
 }
 
+/// Código de salida si se seleccionó alguna línea (o, con `-L`, si se listó algún archivo).
+pub const SALIDA_CON_SELECCION: i32 = 0;
+/// Código de salida si no se seleccionó ninguna línea (o, con `-L`, si no se listó ningún archivo).
+pub const SALIDA_SIN_SELECCION: i32 = 1;
+/// Código de salida si ocurrió algún error, aunque se hayan seleccionado líneas.
+pub const SALIDA_ERROR: i32 = 2;
+
+/// Búsqueda de los patrones en cada archivo recibido e impresión por pantalla de sus resultados a
+/// medida que se encuentran, con un [Searcher] y la [impresora](Impresora) de egrep (ver
+/// [egrep_bytes]). Devuelve el código de salida que corresponde a los resultados.
+///
+/// Los errores de lectura de cada archivo se imprimen sin interrumpir la búsqueda en los demás.
+pub fn imprimir_busqueda(argumentos: &Argumentos) -> i32 {
+    let searcher = match Searcher::desde_argumentos(argumentos) {
+        Ok(searcher) => searcher,
+        Err(e) => {
+            eprintln!("grep: {}", e);
+            return SALIDA_ERROR;
+        }
+    };
+    let mut impresora = Impresora::new(io::stdout().lock(), argumentos);
+    let mut hubo_error = false;
+    let mut hubo_seleccion = false;
+    for archivo in &argumentos.archivos {
+        match searcher.search_path(archivo, &mut impresora.archivo(archivo)) {
+            Ok(estadisticas) => hubo_seleccion |= hay_seleccion(&estadisticas, argumentos),
+            Err(ErrorBusqueda::Archivo(e)) => {
+                eprintln!("grep: {}", e);
+                hubo_error = true;
+            }
+            Err(e @ ErrorBusqueda::Receptor(_)) => {
+                eprintln!("grep: {}", e);
+                return SALIDA_ERROR;
+            }
+        }
+    }
+    codigo_salida(hubo_error, hubo_seleccion)
+}
+
+/// Devuelve true si las estadísticas de la búsqueda en un archivo cuentan como selección para el
+/// código de salida: si se seleccionó alguna línea o, con `-L`, si el archivo se listó.
+fn hay_seleccion(estadisticas: &EstadisticasBusqueda, argumentos: &Argumentos) -> bool {
+    match argumentos.listar_archivos {
+        Some(ListadoArchivos::SinSeleccion) => estadisticas.lineas_seleccionadas == 0,
+        _ => estadisticas.lineas_seleccionadas > 0,
+    }
+}
+
+/// Devuelve el código de salida según si ocurrió algún error y si hubo selección.
+fn codigo_salida(hubo_error: bool, hubo_seleccion: bool) -> i32 {
+    if hubo_error {
+        SALIDA_ERROR
+    } else if hubo_seleccion {
+        SALIDA_CON_SELECCION
+    } else {
+        SALIDA_SIN_SELECCION
+    }
+}
+
 /// Dado un patrón y un path a un archivo, devuelve los resultados de egrep como un vector de strings.
 pub fn egrep(patron: &str, path_archivo: &String) -> Result<Vec<String>> {
     let lineas_archivo = leer_lineas_archivo(path_archivo)?;
@@ -28,3 +282,354 @@ pub fn egrep(patron: &str, path_archivo: &String) -> Result<Vec<String>> {
     }
     Ok(lineas_que_matchean)
 }
+
+/// Dados los argumentos de egrep, devuelve sus resultados sobre cada archivo recibido, con
+/// las líneas seleccionadas en bytes: las que matchean con el patrón o, si se debe invertir
+/// la selección, las que no matchean.
+///
+/// Los archivos pueden tener cualquier contenido: no se requiere que sean utf8 válido. Si se
+/// indicó una codificación, el contenido se transcodifica a utf8 antes de evaluarse.
+///
+/// El terminador de cada línea no forma parte del valor evaluado (en modo crlf, tampoco el '\r'
+/// que precede a '\n'), por lo que el anclaje final matchea justo antes de este.
+///
+/// Los patrones recibidos se compilan en una única expresión regular, que matchea con una línea
+/// si alguno de ellos matchea. Si se indicó que son cadenas fijas, no se crea ninguna expresión
+/// regular: las cadenas se buscan en cada línea tal como son.
+///
+/// Solo se consideran las coincidencias que cumplen con la delimitación indicada (palabras o
+/// líneas completas), y no se distinguen mayúsculas de minúsculas si así se indicó.
+///
+/// Si se indicó una plantilla de reemplazo, el contenido de cada línea seleccionada es el que
+/// resulta de reemplazar sus coincidencias por la plantilla.
+///
+/// Cada archivo se recorre con un [Searcher], con las [opciones](OpcionesBusqueda::desde_argumentos) que
+/// corresponden a los argumentos, guardando las líneas que entrega.
+///
+/// # Errores
+///
+/// Si algún archivo de patrones no se puede leer o algún patrón es inválido, se devuelve su error.
+/// Los errores de lectura de cada archivo se devuelven en su resultado, sin interrumpir la
+/// evaluación de los demás.
+pub fn egrep_bytes(argumentos: &Argumentos) -> Result<Vec<ResultadoArchivo>> {
+    let searcher = Searcher::desde_argumentos(argumentos)?;
+    let resultados_archivos = argumentos
+        .archivos
+        .iter()
+        .map(|archivo| {
+            let mut lineas = LineasResultado::default();
+            ResultadoArchivo {
+                archivo: archivo.to_string(),
+                lineas: searcher.search_path(archivo, &mut lineas).map(|_| lineas.0),
+            }
+        })
+        .collect();
+    Ok(resultados_archivos)
+}
+
+/// Receptor que guarda cada línea que recibe (seleccionada o de contexto) como línea resultado.
+#[derive(Default)]
+struct LineasResultado(Vec<LineaResultado>);
+
+impl Sink for LineasResultado {
+    fn matched(
+        &mut self,
+        numero_linea: usize,
+        desplazamiento: usize,
+        linea: &Linea,
+        coincidencias: &[(usize, usize)],
+    ) -> io::Result<bool> {
+        self.0.push(LineaResultado {
+            linea: linea.clone(),
+            numero: numero_linea,
+            desplazamiento,
+            seleccionada: true,
+            coincidencias: coincidencias.to_vec(),
+        });
+        Ok(true)
+    }
+
+    fn context(
+        &mut self,
+        numero_linea: usize,
+        desplazamiento: usize,
+        linea: &Linea,
+    ) -> io::Result<bool> {
+        self.0.push(LineaResultado {
+            linea: linea.clone(),
+            numero: numero_linea,
+            desplazamiento,
+            seleccionada: false,
+            coincidencias: Vec::new(),
+        });
+        Ok(true)
+    }
+}
+
+/// Entrega en orden a una función las líneas seleccionadas de un archivo y sus líneas de
+/// contexto, recorriéndolas de a una (o todas a la vez, en modo multilínea). La función devuelve
+/// si se deben seguir recorriendo las líneas.
+///
+/// Si se piden las coincidencias, se guardan las posiciones de las coincidencias no vacías de
+/// cada línea seleccionada (salvo si se debe invertir la selección). Si se deben reemplazar las
+/// coincidencias, siempre se guardan las posiciones de los reemplazos.
+pub(crate) fn egrep_lineas<E: From<ErrorArchivo>>(
+    buscador: &Buscador,
+    lineas: impl Iterator<Item = std::result::Result<Linea, ErrorArchivo>>,
+    opciones: &OpcionesBusqueda,
+    entregar: impl FnMut(LineaResultado) -> std::result::Result<bool, E>,
+) -> std::result::Result<(), E> {
+    if opciones.maximo_seleccionadas == Some(0) {
+        return Ok(());
+    }
+    if opciones.multilinea {
+        return egrep_multilinea(
+            buscador,
+            lineas.collect::<std::result::Result<_, _>>()?,
+            opciones,
+            entregar,
+        );
+    }
+    let seleccionar = |linea_resultado: &mut LineaResultado| {
+        let contenido = &linea_resultado.linea.contenido;
+        if buscador.matchea(contenido, opciones.delimitacion) == opciones.invertir {
+            return false;
+        }
+        match &opciones.reemplazo {
+            Some(plantilla) if !opciones.invertir => {
+                let (reemplazado, reemplazos) =
+                    buscador.reemplazar(contenido, opciones.delimitacion, plantilla);
+                linea_resultado.linea.contenido = reemplazado;
+                linea_resultado.coincidencias = reemplazos;
+            }
+            _ if opciones.con_coincidencias && !opciones.invertir => {
+                linea_resultado.coincidencias =
+                    buscador.coincidencias(contenido, opciones.delimitacion);
+            }
+            _ => {}
+        }
+        true
+    };
+    seleccionar_lineas(lineas, opciones, seleccionar, entregar)
+}
+
+/// Entrega en orden a una función las líneas seleccionadas por otra (que además puede completar
+/// su resultado, como sus coincidencias) y sus líneas de contexto, recorriéndolas de a una.
+///
+/// Se deja de recorrer las líneas si la función que las recibe lo indica, o al alcanzar la
+/// cantidad máxima de líneas seleccionadas, salvo por las líneas de contexto posterior que falten.
+fn seleccionar_lineas<E: From<ErrorArchivo>>(
+    lineas: impl Iterator<Item = std::result::Result<Linea, ErrorArchivo>>,
+    opciones: &OpcionesBusqueda,
+    mut seleccionar: impl FnMut(&mut LineaResultado) -> bool,
+    mut entregar: impl FnMut(LineaResultado) -> std::result::Result<bool, E>,
+) -> std::result::Result<(), E> {
+    let maximo = opciones.maximo_seleccionadas;
+    let (contexto_anterior, contexto_posterior) =
+        (opciones.contexto_anterior, opciones.contexto_posterior);
+    let mut anteriores: VecDeque<LineaResultado> = VecDeque::new();
+    let mut cantidad_seleccionadas = 0;
+    let mut contexto_posterior_restante = 0;
+    let mut desplazamiento = 0;
+
+    for (i, linea) in lineas.enumerate() {
+        let linea = linea?;
+        let largo = linea.contenido.len() + linea.terminador.len();
+        let mut linea_resultado = LineaResultado {
+            linea,
+            numero: i + 1,
+            desplazamiento,
+            seleccionada: false,
+            coincidencias: Vec::new(),
+        };
+        desplazamiento += largo;
+        let continuar = if maximo.is_some_and(|m| cantidad_seleccionadas >= m) {
+            if contexto_posterior_restante == 0 {
+                break;
+            }
+            contexto_posterior_restante -= 1;
+            entregar(linea_resultado)?
+        } else if seleccionar(&mut linea_resultado) {
+            linea_resultado.seleccionada = true;
+            cantidad_seleccionadas += 1;
+            contexto_posterior_restante = contexto_posterior;
+            let mut continuar = true;
+            for linea_resultado in anteriores.drain(..).chain(iter::once(linea_resultado)) {
+                if continuar {
+                    continuar = entregar(linea_resultado)?;
+                }
+            }
+            continuar
+        } else if contexto_posterior_restante > 0 {
+            contexto_posterior_restante -= 1;
+            entregar(linea_resultado)?
+        } else {
+            if contexto_anterior > 0 {
+                if anteriores.len() == contexto_anterior {
+                    anteriores.pop_front();
+                }
+                anteriores.push_back(linea_resultado);
+            }
+            true
+        };
+        if !continuar {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Contenido completo de un archivo en modo multilínea: sus líneas concatenadas (cada una con su
+/// terminador) y la posición en la que comienza cada una.
+struct TextoArchivo {
+    texto: Vec<u8>,
+    inicios: Vec<usize>,
+}
+
+impl TextoArchivo {
+    fn new(lineas: &[Linea]) -> Self {
+        let mut texto = Vec::new();
+        let mut inicios = Vec::with_capacity(lineas.len());
+        for linea in lineas {
+            inicios.push(texto.len());
+            texto.extend_from_slice(&linea.contenido);
+            texto.extend_from_slice(&linea.terminador);
+        }
+        Self { texto, inicios }
+    }
+
+    /// Devuelve los índices de la primera y la última línea que toca una coincidencia, o None si
+    /// no toca ninguna (si es vacía y está al final del texto).
+    fn lineas_tocadas(&self, (inicio, fin): (usize, usize)) -> Option<(usize, usize)> {
+        let linea_de = |posicion| self.inicios.partition_point(|i| *i <= posicion) - 1;
+        (inicio < self.texto.len()).then(|| (linea_de(inicio), linea_de(fin.max(inicio + 1) - 1)))
+    }
+
+    /// Devuelve la posición en la que termina una línea, incluido su terminador.
+    fn fin_de_linea(&self, indice: usize) -> usize {
+        self.inicios
+            .get(indice + 1)
+            .copied()
+            .unwrap_or(self.texto.len())
+    }
+}
+
+/// Entrega en orden a una función las líneas seleccionadas de un archivo y sus líneas de contexto
+/// en modo multilínea: los patrones se evalúan sobre el contenido completo del archivo (con sus
+/// terminadores), y se seleccionan las líneas que toca alguna coincidencia (o las que no toca
+/// ninguna, si se debe invertir la selección). Las palabras y líneas completas se delimitan por los
+/// límites de las líneas que toca cada coincidencia, no por los del archivo (ver
+/// [Delimitacion::multilinea]).
+///
+/// Las coincidencias de cada línea seleccionada, si se piden, son las partes de su contenido que
+/// cubren las coincidencias no vacías que la tocan. Si se deben imprimir solo las coincidencias,
+/// se entregan [bloques](bloques_de_coincidencias) de líneas en vez de las líneas seleccionadas.
+fn egrep_multilinea<E: From<ErrorArchivo>>(
+    buscador: &Buscador,
+    lineas: Vec<Linea>,
+    opciones: &OpcionesBusqueda,
+    mut entregar: impl FnMut(LineaResultado) -> std::result::Result<bool, E>,
+) -> std::result::Result<(), E> {
+    let texto = TextoArchivo::new(&lineas);
+    let delimitacion = opciones
+        .delimitacion
+        .multilinea(opciones.lectura.terminador);
+    let coincidencias = buscador.todas_las_coincidencias(&texto.texto, delimitacion);
+    if opciones.solo_coincidencias && !opciones.invertir {
+        let maximo = opciones.maximo_seleccionadas.unwrap_or(usize::MAX);
+        for bloque in bloques_de_coincidencias(&texto, coincidencias)
+            .into_iter()
+            .take(maximo)
+        {
+            if !entregar(bloque)? {
+                break;
+            }
+        }
+        return Ok(());
+    }
+    let mut tocada = vec![false; lineas.len()];
+    let mut coincidencias_lineas = vec![Vec::new(); lineas.len()];
+    for (inicio, fin) in coincidencias {
+        let Some((primera, ultima)) = texto.lineas_tocadas((inicio, fin)) else {
+            continue;
+        };
+        tocada[primera..=ultima].fill(true);
+        if !opciones.con_coincidencias {
+            continue;
+        }
+        for indice in primera..=ultima {
+            let inicio_linea = texto.inicios[indice];
+            let fin_contenido = inicio_linea + lineas[indice].contenido.len();
+            let (inicio_parte, fin_parte) = (inicio.max(inicio_linea), fin.min(fin_contenido));
+            if inicio_parte < fin_parte {
+                coincidencias_lineas[indice]
+                    .push((inicio_parte - inicio_linea, fin_parte - inicio_linea));
+            }
+        }
+    }
+    let seleccionar = |linea_resultado: &mut LineaResultado| {
+        let indice = linea_resultado.numero - 1;
+        if tocada[indice] == opciones.invertir {
+            return false;
+        }
+        if !opciones.invertir {
+            linea_resultado.coincidencias = mem::take(&mut coincidencias_lineas[indice]);
+        }
+        true
+    };
+    seleccionar_lineas(lineas.into_iter().map(Ok), opciones, seleccionar, entregar)
+}
+
+/// Agrupa las coincidencias no vacías en bloques de líneas consecutivas tocadas por ellas. Cada
+/// bloque es una línea resultado, numerada como su primera línea, cuyo contenido son las líneas
+/// del bloque (con sus terminadores) y con las posiciones de sus coincidencias en él.
+fn bloques_de_coincidencias(
+    texto: &TextoArchivo,
+    coincidencias: Vec<(usize, usize)>,
+) -> Vec<LineaResultado> {
+    let mut bloques: Vec<LineaResultado> = Vec::new();
+    for coincidencia in coincidencias
+        .into_iter()
+        .filter(|(inicio, fin)| fin > inicio)
+    {
+        let Some((primera, _)) = texto.lineas_tocadas(coincidencia) else {
+            continue;
+        };
+        // Las coincidencias están ordenadas, por lo que la última de un bloque es la que termina
+        // en su última línea.
+        let ultima_del_bloque = bloques
+            .last()
+            .and_then(|bloque| bloque.coincidencias.last())
+            .and_then(|ultima| texto.lineas_tocadas(*ultima))
+            .map(|(_, ultima_linea)| ultima_linea);
+        match bloques.last_mut() {
+            Some(bloque) if ultima_del_bloque.is_some_and(|ultima| primera <= ultima) => {
+                bloque.coincidencias.push(coincidencia)
+            }
+            _ => bloques.push(LineaResultado {
+                linea: Linea {
+                    contenido: Vec::new(),
+                    terminador: Vec::new(),
+                },
+                numero: primera + 1,
+                desplazamiento: texto.inicios[primera],
+                seleccionada: true,
+                coincidencias: vec![coincidencia],
+            }),
+        }
+    }
+    for bloque in &mut bloques {
+        let inicio = texto.inicios[bloque.numero - 1];
+        let ultima_linea = bloque
+            .coincidencias
+            .last()
+            .and_then(|ultima| texto.lineas_tocadas(*ultima))
+            .map_or(bloque.numero - 1, |(_, ultima_linea)| ultima_linea);
+        bloque.linea.contenido = texto.texto[inicio..texto.fin_de_linea(ultima_linea)].to_vec();
+        for (inicio_coincidencia, fin_coincidencia) in &mut bloque.coincidencias {
+            *inicio_coincidencia -= inicio;
+            *fin_coincidencia -= inicio;
+        }
+    }
+    bloques
+}
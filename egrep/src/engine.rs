@@ -0,0 +1,20 @@
+/// Motor con el que se evalúa una expresión regular.
+///
+/// * Auto: un autómata, salvo que la expresión tenga referencias a grupos, repeticiones
+///   perezosas, anticipaciones o retrospecciones, que solo pueden evaluarse con backtracking. En
+///   sintaxis básica y extendida, el backtracking también devuelve la coincidencia más larga.
+///
+/// * Automata: un autómata finito no determinista, cuyo tiempo de evaluación es lineal en el
+///   largo del valor. No admite las expresiones que requieren backtracking.
+///
+/// * Backtracking: un motor de backtracking, que admite cualquier expresión, pero cuyo tiempo de
+///   evaluación puede crecer mucho más que linealmente con el largo del valor. Entre las
+///   coincidencias que comienzan en una misma posición, devuelve la primera que encuentra en vez
+///   de la más larga.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Engine {
+    #[default]
+    Auto,
+    Automata,
+    Backtracking,
+}
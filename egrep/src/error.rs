@@ -0,0 +1,115 @@
+use std::{error, fmt};
+
+use crate::{error_expresion_bracket::ErrorExpresionBracket, error_regex::ErrorRegex};
+
+/// Tipo de un error en la creación de una expresión regular: un error del patrón en general o
+/// de una de sus expresiones bracket.
+#[derive(Debug, PartialEq)]
+pub enum TipoError {
+    Regex(ErrorRegex),
+    ExpresionBracket(ErrorExpresionBracket),
+}
+
+impl From<ErrorRegex> for TipoError {
+    fn from(error: ErrorRegex) -> Self {
+        TipoError::Regex(error)
+    }
+}
+
+impl From<ErrorExpresionBracket> for TipoError {
+    fn from(error: ErrorExpresionBracket) -> Self {
+        TipoError::ExpresionBracket(error)
+    }
+}
+
+impl fmt::Display for TipoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TipoError::Regex(error) => write!(f, "{}", error),
+            TipoError::ExpresionBracket(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+/// Error en la creación de una expresión regular a partir de un patrón: su tipo, el patrón y las
+/// posiciones (en bytes) de inicio y fin de la parte del patrón en la que se encontró.
+///
+/// Se muestra con el mensaje de su tipo, seguido por el patrón y una marca debajo de dicha parte:
+///
+/// ```text
+/// Secuencia de escape desconocida «\q»
+///   a\qb
+///    ^~
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct Error {
+    tipo: TipoError,
+    patron: String,
+    inicio: usize,
+    fin: usize,
+}
+
+impl Error {
+    /// Creación de un error de un tipo, en la parte de un patrón entre dos posiciones en bytes.
+    pub(crate) fn new(tipo: TipoError, patron: &str, inicio: usize, fin: usize) -> Self {
+        Self {
+            tipo,
+            patron: patron.to_string(),
+            inicio,
+            fin,
+        }
+    }
+
+    /// Devuelve el tipo del error.
+    pub fn tipo(&self) -> &TipoError {
+        &self.tipo
+    }
+
+    /// Devuelve el patrón en el que se encontró el error.
+    pub fn patron(&self) -> &str {
+        &self.patron
+    }
+
+    /// Devuelve las posiciones en bytes de inicio y fin (sin incluir) de la parte del patrón en la
+    /// que se encontró el error.
+    pub fn posiciones(&self) -> (usize, usize) {
+        (self.inicio, self.fin)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let columna = self.patron[..self.inicio].chars().count();
+        let largo = self.patron[self.inicio..self.fin].chars().count();
+        writeln!(f, "{}", self.tipo)?;
+        writeln!(f, "  {}", self.patron)?;
+        write!(
+            f,
+            "  {}^{}",
+            " ".repeat(columna),
+            "~".repeat(largo.saturating_sub(1))
+        )
+    }
+}
+
+impl error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marca_la_parte_del_patron_con_el_error() {
+        let error = Error::new(
+            TipoError::Regex(ErrorRegex::EscapeDesconocido('q')),
+            "ñ\\qb",
+            2,
+            4,
+        );
+
+        assert_eq!(
+            error.to_string(),
+            "Secuencia de escape desconocida «\\q»\n  ñ\\qb\n   ^~"
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use std::{error, fmt};
+
+/// Errores posibles en los argumentos recibidos por línea de comando.
+#[derive(Debug, PartialEq)]
+pub enum ErrorArgumentos {
+    ArgumentosFaltantes,
+    OpcionDesconocida(String),
+    OpcionSinValor(String),
+    ValorInvalido(String, String),
+    CodificacionDesconocida(String),
+    OpcionRequerida(String, String),
+    OpcionesIncompatibles(String, String),
+}
+
+impl fmt::Display for ErrorArgumentos {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ErrorArgumentos::ArgumentosFaltantes => {
+                write!(f, "No se ingresaron los argumentos necesarios")
+            }
+            ErrorArgumentos::OpcionDesconocida(ref opcion) => {
+                write!(f, "Opción desconocida «{}»", opcion)
+            }
+            ErrorArgumentos::OpcionSinValor(ref opcion) => {
+                write!(f, "La opción «{}» requiere un argumento", opcion)
+            }
+            ErrorArgumentos::ValorInvalido(ref opcion, ref valor) => {
+                write!(f, "Valor inválido «{}» para la opción «{}»", valor, opcion)
+            }
+            ErrorArgumentos::CodificacionDesconocida(ref nombre) => {
+                write!(f, "Codificación desconocida «{}»", nombre)
+            }
+            ErrorArgumentos::OpcionRequerida(ref opcion, ref requerida) => {
+                write!(
+                    f,
+                    "La opción «{}» requiere la opción «{}»",
+                    opcion, requerida
+                )
+            }
+            ErrorArgumentos::OpcionesIncompatibles(ref opcion, ref otra) => {
+                write!(f, "La opción «{}» no admite la opción «{}»", opcion, otra)
+            }
+        }
+    }
+}
+
+impl error::Error for ErrorArgumentos {}
@@ -0,0 +1,30 @@
+use std::{error, fmt, io};
+
+use crate::error_lectura_archivos::ErrorArchivo;
+
+/// Errores posibles en una búsqueda: al leer el archivo en el que se busca, o al entregar sus
+/// resultados al receptor de estos.
+#[derive(Debug)]
+pub enum ErrorBusqueda {
+    Archivo(ErrorArchivo),
+    Receptor(io::Error),
+}
+
+impl fmt::Display for ErrorBusqueda {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ErrorBusqueda::Archivo(ref error) => write!(f, "{}", error),
+            ErrorBusqueda::Receptor(ref error) => {
+                write!(f, "No se pueden escribir los resultados: {}", error)
+            }
+        }
+    }
+}
+
+impl From<ErrorArchivo> for ErrorBusqueda {
+    fn from(error: ErrorArchivo) -> Self {
+        ErrorBusqueda::Archivo(error)
+    }
+}
+
+impl error::Error for ErrorBusqueda {}
@@ -6,6 +6,7 @@ pub enum ErrorExpresionBracket {
     BracketNoMatchea,
     NombreClaseInvalido,
     SintaxisClaseInvalida,
+    ElementoIntercaladoInvalido,
 }
 
 impl fmt::Display for ErrorExpresionBracket {
@@ -19,6 +20,12 @@ impl fmt::Display for ErrorExpresionBracket {
                 f,
                 "La sintaxis de la clase de caracteres es [[:space:]], no [:space:]"
             ),
+            ErrorExpresionBracket::ElementoIntercaladoInvalido => {
+                write!(
+                    f,
+                    "Símbolo de intercalación o clase de equivalencia inválido"
+                )
+            }
         }
     }
 }
@@ -5,6 +5,8 @@ use std::{error, fmt, path::PathBuf};
 pub enum ErrorArchivo {
     Path(PathBuf),
     Lectura(PathBuf, usize),
+    Escritura(PathBuf),
+    Codificacion(PathBuf, char),
 }
 
 impl fmt::Display for ErrorArchivo {
@@ -21,6 +23,17 @@ impl fmt::Display for ErrorArchivo {
                 nombre_archivo.display(),
                 numero_linea
             ),
+            ErrorArchivo::Escritura(ref nombre_archivo) => write!(
+                f,
+                "No se puede escribir el archivo '{}'",
+                nombre_archivo.display()
+            ),
+            ErrorArchivo::Codificacion(ref nombre_archivo, caracter) => write!(
+                f,
+                "No se puede escribir «{}» en la codificación del archivo '{}'",
+                caracter,
+                nombre_archivo.display()
+            ),
         }
     }
 }
@@ -0,0 +1,24 @@
+use std::{error, fmt};
+
+use crate::{error::Error, patrones::OrigenPatron};
+
+/// Error en la creación de la expresión regular de uno de los patrones recibidos, junto con el
+/// origen de dicho patrón.
+#[derive(Debug)]
+pub struct ErrorPatron {
+    pub origen: OrigenPatron,
+    pub error: Error,
+}
+
+impl fmt::Display for ErrorPatron {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.origen {
+            OrigenPatron::Argumento => write!(f, "{}", self.error),
+            OrigenPatron::Archivo(ref nombre_archivo, ref numero_linea) => {
+                write!(f, "{}:{}: {}", nombre_archivo, numero_linea, self.error)
+            }
+        }
+    }
+}
+
+impl error::Error for ErrorPatron {}
@@ -6,7 +6,16 @@ pub enum ErrorRegex {
     RepeticionInvalida,
     ContenidoInvalidoRepeticion,
     BarraInvertidaAlFinal,
-    ExpresionInvalidaAnclajeInicio,
+    ParentesisDesbalanceado,
+    ReferenciaInvalida,
+    EscapeDesconocido(char),
+    PropiedadDesconocida(String),
+    RetrospeccionNoAcotada,
+    NombreDeGrupoInvalido,
+    GrupoDesconocido,
+    MotorIncompatible,
+    LimiteDeTamanoExcedido(usize),
+    RepeticionDemasiadoGrande,
 }
 
 impl fmt::Display for ErrorRegex {
@@ -18,9 +27,39 @@ impl fmt::Display for ErrorRegex {
             ),
             ErrorRegex::ContenidoInvalidoRepeticion => write!(f, "Contenido de \\{{\\}} inválido"),
             ErrorRegex::BarraInvertidaAlFinal => write!(f, "Barra invertida extra al final «\\»"),
-            ErrorRegex::ExpresionInvalidaAnclajeInicio => write!(
+            ErrorRegex::ParentesisDesbalanceado => write!(f, "Paréntesis ( o ) desbalanceado"),
+            ErrorRegex::ReferenciaInvalida => write!(
                 f,
-                "Metacaracter de anclaje de inicio ^ en medio de una expresión"
+                "Referencia a un grupo inexistente o que todavía no se cerró"
+            ),
+            ErrorRegex::PropiedadDesconocida(ref nombre) => {
+                write!(f, "Propiedad de Unicode desconocida «{}»", nombre)
+            }
+            ErrorRegex::EscapeDesconocido(c) => {
+                write!(f, "Secuencia de escape desconocida «\\{}»", c)
+            }
+            ErrorRegex::MotorIncompatible => write!(
+                f,
+                "El autómata no admite referencias, repeticiones perezosas, anticipaciones ni retrospecciones"
+            ),
+            ErrorRegex::LimiteDeTamanoExcedido(limite) => write!(
+                f,
+                "La expresión regular compilada supera el límite de {} instrucciones",
+                limite
+            ),
+            ErrorRegex::RepeticionDemasiadoGrande => {
+                write!(f, "Expresión regular demasiado grande")
+            }
+            ErrorRegex::RetrospeccionNoAcotada => write!(
+                f,
+                "Retrospección (?<=...) o (?<!...) sin un largo máximo acotado"
+            ),
+            ErrorRegex::GrupoDesconocido => {
+                write!(f, "Grupo (?...) desconocido o no admitido")
+            }
+            ErrorRegex::NombreDeGrupoInvalido => write!(
+                f,
+                "Nombre de grupo (?<nombre>...) vacío, sin cerrar o con caracteres inválidos"
             ),
         }
     }
@@ -1,10 +1,9 @@
 use crate::{
-    iterador_vec::IteradorVecHaciaAtras, regex_token::RegexToken,
-    valor_regex_token::ValorRegexToken,
+    backtracking::Backtracking, regex_token::RegexToken, valor_regex_token::ValorRegexToken,
 };
 
 /// Representación de una expresión particular (sólo contiene tokens).
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Expresion {
     tokens: Vec<RegexToken>,
 }
@@ -15,192 +14,93 @@ impl Expresion {
         Self { tokens }
     }
 
-    /// Matcheo con de un valor recibido como string slice con una expresión.
-    /// Devuelve true si matchea, false en caso contrario.
-    ///
-    /// Matchea desde el inicio del valor si el primer token es de anclaje,
-    /// sino desde cada caracter del valor.
-    ///
-    /// Si self es una expresión vacía, devuelve true.
-    pub fn matchea(&self, valor: &str) -> bool {
-        if let Some(token) = self.tokens.first() {
-            if token.valor == ValorRegexToken::AnclajeInicio {
-                self.matchea_desde_inicio(valor)
-            } else {
-                self.matchea_desde_cualquier_lugar(valor)
-            }
-        } else {
-            true
-        }
+    /// Devuelve una expresión equivalente que no distingue mayúsculas de minúsculas.
+    pub fn ignorar_mayusculas(self) -> Self {
+        self.transformar_valores(&ValorRegexToken::ignorar_mayusculas)
     }
 
-    /// Matcheo desde cualquier caracter de un valor.
-    fn matchea_desde_cualquier_lugar(&self, valor: &str) -> bool {
-        let indices = valor.char_indices();
-        for i in indices {
-            if self.matchea_desde_inicio(&valor[i.0..]) {
-                return true;
-            }
-        }
-        false
+    /// Devuelve una expresión equivalente en modo Unicode, en la que las clases de caracter
+    /// incluyen caracteres no ASCII.
+    pub fn unicode(self) -> Self {
+        self.transformar_valores(&ValorRegexToken::unicode)
     }
 
-    /// Matcheo desde el inicio de un valor.
-    fn matchea_desde_inicio(&self, valor: &str) -> bool {
-        let mut indice = 0;
-        let mut iter_tokens = IteradorVecHaciaAtras::new(&self.tokens);
-        // Pila con tuplas que indican el tamaño de match en utf8 de un token con un valor y
-        // si se puede hacer backtrack sobre ese token.
-        let mut pila: Vec<(usize, bool)> = Vec::new();
+    /// Devuelve una expresión equivalente en modo multilínea, en la que los anclajes matchean al
+    /// inicio y al final de cada línea del valor evaluado (separadas por un terminador).
+    pub fn multilinea(self, terminador: u8) -> Self {
+        self.transformar_valores(&|valor: ValorRegexToken| valor.multilinea(terminador))
+    }
 
-        // recorre los tokens de la expresión con un iterador.
-        'tokens: while let Some(token) = iter_tokens.next() {
-            // Si el token es de anclaje final, devuelve 1 o 0 según si se llegó al final del valor que se evalúa.
-            if token.valor == ValorRegexToken::AnclajeFinal {
-                return valor.len() == indice;
-            }
-            // Si el token tiene un límite inferior de repeticiones, se chequea si matchea n veces con el valor.
-            // Si matchea n veces, se sigue con el siguiente token. Si no, devuelve 0.
-            if let Some(n) = token.min_repeticiones() {
-                let (tamaño_match, continuar) = Self::matchear_n_veces(
-                    n,
-                    token,
-                    valor,
-                    &mut indice,
-                    &mut iter_tokens,
-                    &mut pila,
-                );
-                if continuar {
-                    continue 'tokens;
-                }
-                if (tamaño_match == 0) & (n != 0) {
-                    return false;
-                }
-            }
-            // Si el token tiene un límite superior de repeticiones, se avanza sobre el valor
-            // hasta m veces, según si se macthea con el token. Si no, se puede avanzar sobre
-            //  el valor hasta el final.
-            if let Some(m) = token.max_repeticiones() {
-                Self::matchear_entre_n_y_m_veces(m, token, valor, &mut indice, &mut pila);
-            } else {
-                Self::matchear_hasta_el_final(token, valor, &mut indice, &mut pila);
-            }
-        }
-        true
+    /// Devuelve una expresión equivalente en la que los comodines no matchean con un terminador
+    /// de línea.
+    pub fn comodin_sin_terminador(self, terminador: u8) -> Self {
+        self.transformar_valores(&|valor: ValorRegexToken| valor.comodin_sin_terminador(terminador))
     }
 
-    /// Función auxiliar para el matcheo de una expresion con un valor desde su inicio.
-    ///
-    /// Verifica si matchea n veces un token con un valor desde determinado índice.
-    /// Devuelve una tupla con el tamaño del matcheo en utf8 (o 0 si no hay match) en
-    /// la primera posición y un booleano que indica si se debe continuar en el loop desde el que
-    /// la función fue llamada.
-    fn matchear_n_veces(
-        n: usize,
-        token: &RegexToken,
-        valor: &str,
-        indice: &mut usize,
-        tokens: &mut IteradorVecHaciaAtras<RegexToken>,
-        pila: &mut Vec<(usize, bool)>,
-    ) -> (usize, bool) {
-        let mut tamaño_match = 0;
-        let mut continuar = false;
-        for _ in 0..n {
-            let s = token.valor.matchea(&valor[*indice..]);
-            if s == 0 {
-                // Si no matchea, backtrackeo lo que me indica backtrack mas
-                // hasta donde matchee en el for. Si se backtrackeo, indico que
-                // se debe continuar en el loop donde se llamo a la función y salgo del for.
-                if let Some(size) = Self::backtrack(pila, tokens) {
-                    *indice -= size + tamaño_match;
-                    continuar = true;
-                }
-                tamaño_match = 0;
-                break;
-            } else {
-                tamaño_match += s;
-                *indice += s;
-            }
-        }
-        if tamaño_match != 0 {
-            // Guardo en la pila de backtrackeables el tamaño por el que se matcheo
-            // e indico que no se puede backtrackear (matcheo n veces, no se vuelve atrás).
-            let backtrackeable = false;
-            pila.push((tamaño_match, backtrackeable));
+    fn transformar_valores(mut self, f: &dyn Fn(ValorRegexToken) -> ValorRegexToken) -> Self {
+        for token in self.tokens.iter_mut() {
+            let valor = std::mem::replace(&mut token.valor, ValorRegexToken::Comodin);
+            token.valor = f(valor);
         }
-        (tamaño_match, continuar)
+        self
     }
 
-    /// Función para hacer backtrack sobre un iterador de tokens.
+    /// Devuelve los tokens de la expresión.
+    pub fn tokens(&self) -> &[RegexToken] {
+        &self.tokens
+    }
+
+    /// Matcheo con de un valor recibido como slice de bytes con una expresión.
+    /// Devuelve true si matchea, false en caso contrario.
     ///
-    /// Dada una pila que guarda tamaños de matcheos y si es posible backtrackear
-    /// sobre un token que matcheó, backtrackea sobre el iterador de tokens todo lo que se pueda,
-    /// devolviendo el tamaño en usize por el que se backtrackeo (o None en caso de no haberlo hecho).
-    fn backtrack(
-        evaluated: &mut Vec<(usize, bool)>,
-        tokens: &mut IteradorVecHaciaAtras<RegexToken>,
-    ) -> Option<usize> {
-        let mut back_size = 0;
-        tokens.prev();
+    /// Se intenta matchear desde cada posición del valor (incluso desde su final), por lo que
+    /// los anclajes solo matchean al inicio o al final de este. Los bytes que no forman parte de
+    /// un caracter utf8 válido se recorren de a uno.
+    ///
+    /// Si self es una expresión vacía, devuelve true.
+    pub fn matchea(&self, valor: &[u8]) -> bool {
+        Backtracking::new(valor)
+            .buscar(std::slice::from_ref(self), 0, |_, _| true)
+            .is_some()
+    }
 
-        while let Some(e) = evaluated.pop() {
-            let tamaño_match = e.0;
-            let backtrackeable = e.1;
-            back_size += tamaño_match;
-            if backtrackeable {
-                return Some(back_size);
-            } else {
-                tokens.prev();
-            }
-        }
-        None
+    /// Devuelve true si la expresión contiene alguna referencia a un grupo.
+    pub fn tiene_referencias(&self) -> bool {
+        self.tokens.iter().any(|token| match &token.valor {
+            ValorRegexToken::Referencia(..) => true,
+            valor => valor.expresiones().iter().any(Expresion::tiene_referencias),
+        })
     }
 
-    /// Función auxiliar para el matcheo de una expresion con un valor desde su inicio.
-    ///
-    /// Verifica si matchea entre n y m veces un token con un valor desde determinado índice.
-    fn matchear_entre_n_y_m_veces(
-        m: usize,
-        token: &RegexToken,
-        valor: &str,
-        indice: &mut usize,
-        pila: &mut Vec<(usize, bool)>,
-    ) {
-        let mut tamaño_match = token.valor.matchea(&valor[*indice..]);
-        let mut valores_evaluados = 0;
-        // Si el token tenía un mínimo de repeticiones y se llegó al llamado de esta función,
-        // significa que el token ya matcheó n veces con el valor.
-        if let Some(n) = token.min_repeticiones() {
-            valores_evaluados = n;
-        }
-        while (tamaño_match != 0) & (valores_evaluados < m) {
-            *indice += tamaño_match;
-            // Guardo en la pila de backtrackeables el tamaño por el que se matcheo
-            // e indico que se puede backtrackear (ya matcheo n veces, se puede volver atrás).
-            let backtrackeable = true;
-            pila.push((tamaño_match, backtrackeable));
-            tamaño_match = token.valor.matchea(&valor[*indice..]);
-            valores_evaluados += 1;
-        }
+    /// Devuelve true si la expresión solo puede evaluarse con backtracking: si contiene alguna
+    /// referencia a un grupo, repetición perezosa, grupo atómico, anticipación o retrospección.
+    pub fn requiere_backtracking(&self) -> bool {
+        self.tokens.iter().any(|token| {
+            token.es_perezoso()
+                || match &token.valor {
+                    ValorRegexToken::Grupo(_, expresiones) => {
+                        expresiones.iter().any(Expresion::requiere_backtracking)
+                    }
+                    ValorRegexToken::Referencia(..)
+                    | ValorRegexToken::GrupoAtomico(_)
+                    | ValorRegexToken::Anticipacion(_)
+                    | ValorRegexToken::AnticipacionNegada(_)
+                    | ValorRegexToken::Retrospeccion(_)
+                    | ValorRegexToken::RetrospeccionNegada(_) => true,
+                    _ => false,
+                }
+        })
     }
 
-    /// Función auxiliar para el matcheo de una expresion con un valor desde su inicio.
-    ///
-    /// Verifica si matchea entre un token con un valor desde determinado índice, hasta el final de este.
-    fn matchear_hasta_el_final(
-        token: &RegexToken,
-        valor: &str,
-        indice: &mut usize,
-        pila: &mut Vec<(usize, bool)>,
-    ) {
-        let mut tamaño_match = token.valor.matchea(&valor[*indice..]);
-        while tamaño_match != 0 {
-            *indice += tamaño_match;
-            // Guardo en la pila de backtrackeables el tamaño por el que se matcheo
-            // e indico que se puede backtrackear (ya matcheo n veces o no hay n, se puede volver atrás).
-            let backtrackeable = true;
-            pila.push((tamaño_match, backtrackeable));
-            tamaño_match = token.valor.matchea(&valor[*indice..]);
-        }
+    /// Devuelve el largo máximo en bytes de un valor con el que puede matchear la expresión, o
+    /// None si no está acotado.
+    pub fn largo_maximo(&self) -> Option<usize> {
+        self.tokens.iter().try_fold(0, |total, token| {
+            let largo = token.valor.largo_maximo()?;
+            if largo == 0 {
+                return Some(total);
+            }
+            Some(total + largo * token.max_repeticiones()?)
+        })
     }
 }
@@ -1,7 +1,10 @@
 use crate::{
-    clase_caracter::ClaseCaracter, error_expresion_bracket::ErrorExpresionBracket,
-    iterador_vec::IteradorVecHaciaAtras, regex_token::RegexToken,
-    repeticion_regex_token::RepeticionRegexToken, valor_regex_token::ValorRegexToken,
+    clase_caracter::{letra_base, ClaseCaracter},
+    error_expresion_bracket::ErrorExpresionBracket,
+    iterador_vec::IteradorVecHaciaAtras,
+    regex_token::RegexToken,
+    repeticion_regex_token::RepeticionRegexToken,
+    valor_regex_token::ValorRegexToken,
 };
 
 /// Creación de un token de expresión bracket dado un iterador de los caracteres de un patrón
@@ -19,6 +22,9 @@ use crate::{
 ///
 /// * Si el nombre de la clase de caracter es inválido (ej.: \[\[:spac:]]).
 ///
+/// * Si un símbolo de intercalación o una clase de equivalencia no son un único caracter o el
+///   nombre de uno (ej.: \[\[.guion.]], \[\[=ab=]]).
+///
 /// Los ejemplos son dados como strings, pero debe interpretarse que los caracteres siguientes en el
 /// iterador recibido por parámetro son los que le siguen al primer bracket de apertura ([) en cada caso.
 pub fn nuevo_token_expresion_bracket(
@@ -64,7 +70,7 @@ fn obtener_contenido_de_bracket(
 ) -> Result<String, ErrorExpresionBracket> {
     let mut contenido = String::new();
     let mut contenido_valido = false;
-    let mut clase_caracter_abierta = false;
+    let mut clase_caracter_abierta = None;
     while let Some(c) = iter_chars.next() {
         if *c == ']' {
             if cerrar_contenido(clase_caracter_abierta, &contenido) {
@@ -72,12 +78,12 @@ fn obtener_contenido_de_bracket(
                 break;
             } else {
                 contenido.push(*c);
-                clase_caracter_abierta = false;
+                clase_caracter_abierta = None;
                 continue;
             }
         } else if *c == '[' {
-            abrir_o_no_clase_caracter(&mut clase_caracter_abierta, iter_chars);
             contenido.push(*c);
+            abrir_o_no_clase_caracter(&mut clase_caracter_abierta, &mut contenido, iter_chars);
         } else {
             contenido.push(*c);
         }
@@ -91,19 +97,20 @@ fn obtener_contenido_de_bracket(
 
 /// Función auxiliar para la obtención del contenido de un bracket
 ///
-/// Dado un booleano y un string slice que representa el contenido parcial
-/// de un bracket, devuelve true o false según si se puede cerrar el contenido o no.
+/// Dado el delimitador de la clase de caracter abierta (si hay una) y un string slice que
+/// representa el contenido parcial de un bracket, devuelve true o false según si se puede cerrar
+/// el contenido o no.
 ///
 /// Esta función se llama cuando se lee un ']' dentro del bracket.
 ///
 /// Si no hay una clase de caracter abierta, se cierra el contenido.
 ///
 /// Si hay una clase de caracter abierta, se cierra el contenido solo si el caracter anterior a
-/// ']' no es ':'.
-fn cerrar_contenido(clase_caracter_abierta: bool, contenido: &str) -> bool {
-    if clase_caracter_abierta {
+/// ']' no es su delimitador (':', '.' o '=').
+fn cerrar_contenido(clase_caracter_abierta: Option<char>, contenido: &str) -> bool {
+    if let Some(delimitador) = clase_caracter_abierta {
         if let Some(anterior_c) = contenido.chars().last() {
-            return anterior_c != ':';
+            return anterior_c != delimitador;
         }
     }
     true
@@ -111,24 +118,40 @@ fn cerrar_contenido(clase_caracter_abierta: bool, contenido: &str) -> bool {
 
 /// Función auxiliar para la obtención del contenido de un bracket
 ///
-/// Recibe un booleano que indica si hay una clase de caracter abierta en el contenido
-/// (si se encontró "[:") y un iterador de caracteres que es el patrón para crear la expresión regular original.
+/// Recibe el delimitador de la clase de caracter abierta en el contenido (si se encontró "[:",
+/// "[." o "[="), el contenido parcial y un iterador de caracteres que es el patrón para crear la
+/// expresión regular original.
 ///
 /// Esta función se llama cuando se lee un '[' dentro del bracket.
 ///
-/// Si luego se encuentra un ':', se abre la clase de caracter (se modifica el booleano a true).
+/// Si luego se encuentra un ':', '.' o '=', se abre la clase de caracter con ese delimitador.
 /// //No importa si ya se encontraba abierta, eso será un error más adelante
 ///
-/// Si no se encuentra un ':', no se hace nada.
+/// En los símbolos de intercalación y las clases de equivalencia, el primer caracter es parte del
+/// contenido aunque sea ']' (ej.: \[\[.].]]), por lo que se agrega junto con el delimitador.
+///
+/// Si no se encuentra un delimitador, no se hace nada.
 fn abrir_o_no_clase_caracter(
-    clase_caracter_abierta: &mut bool,
+    clase_caracter_abierta: &mut Option<char>,
+    contenido: &mut String,
     iter_chars: &mut IteradorVecHaciaAtras<char>,
 ) {
-    if let Some(siguiente_c) = iter_chars.next() {
-        if *siguiente_c == ':' {
-            *clase_caracter_abierta = true;
+    match iter_chars.next() {
+        Some(':') => {
+            *clase_caracter_abierta = Some(':');
+            iter_chars.prev();
+        }
+        Some(delimitador @ ('.' | '=')) => {
+            *clase_caracter_abierta = Some(*delimitador);
+            contenido.push(*delimitador);
+            if let Some(primer_c) = iter_chars.next() {
+                contenido.push(*primer_c);
+            }
+        }
+        Some(_) => {
+            iter_chars.prev();
         }
-        iter_chars.prev();
+        None => {}
     }
 }
 
@@ -222,6 +245,9 @@ fn manejar_el_resto_de_contenido(
 /// Si hay un error en el contenido de la clase de caracter, se devuelve. Si no, se añade al vector
 /// esta clase
 ///
+/// Si el siguiente es '.', se abrió un símbolo de intercalación (ej.: \[.-.] o \[.hyphen.]), que
+/// se agrega como literal. Si es '=', se abrió una clase de equivalencia (ej.: \[=a=]).
+///
 /// Si el siguiente no es ':', simplemente se agrega como clase caracter literal a '['
 /// y a este caracter que le seguía.
 fn manejar_posible_clase_caracter(
@@ -231,10 +257,20 @@ fn manejar_posible_clase_caracter(
     if let Some(siguiente_c) = iter_contenido_bracket.next() {
         match siguiente_c {
             ':' => {
-                let contenido_clase = obtener_contenido_de_clase_caracter(iter_contenido_bracket)?;
+                let contenido_clase =
+                    obtener_contenido_de_clase_caracter(iter_contenido_bracket, ':')?;
                 let clase_caracter = ClaseCaracter::new(&contenido_clase)?;
                 caracteres.push(clase_caracter);
             }
+            '.' => {
+                let nombre = obtener_contenido_de_clase_caracter(iter_contenido_bracket, '.')?;
+                caracteres.push(ClaseCaracter::Literal(caracter_intercalado(&nombre)?));
+            }
+            '=' => {
+                let nombre = obtener_contenido_de_clase_caracter(iter_contenido_bracket, '=')?;
+                let base = letra_base(caracter_intercalado(&nombre)?);
+                caracteres.push(ClaseCaracter::Equivalencia(base));
+            }
             _ => {
                 caracteres.push(ClaseCaracter::Literal('['));
                 caracteres.push(ClaseCaracter::Literal(*siguiente_c));
@@ -252,21 +288,28 @@ fn manejar_posible_clase_caracter(
 ///
 /// Es una función auxiliar para la creación de un token con valor bracket expresión,
 /// por como se hacen las llamadas el caracter siguiente en el iterador del contenido
-/// es uno que le sigue a "[:".
+/// es uno que le sigue a "[:" (o "[." o "[=", según el delimitador recibido).
 ///
 /// Si no se encuentra un cierre a la clase de caracter (":]"), se devuelve error.
 ///
 /// Si se encuentra el cierre, se devuelve los caracteres desde el primer caracter que devuelve el iterador
-/// hasta encontrar ":]" como un String.
+/// hasta encontrar ":]" como un String. En los símbolos de intercalación y las clases de
+/// equivalencia, el primer caracter siempre forma parte del contenido.
 fn obtener_contenido_de_clase_caracter(
     iter_contenido_bracket: &mut IteradorVecHaciaAtras<char>,
+    delimitador: char,
 ) -> Result<String, ErrorExpresionBracket> {
     let mut contenido = String::new();
     let mut contenido_valido = false;
+    if delimitador != ':' {
+        if let Some(primer_c) = iter_contenido_bracket.next() {
+            contenido.push(*primer_c);
+        }
+    }
     for c in iter_contenido_bracket.by_ref() {
         if *c == ']' {
             if let Some(ultimo_c) = contenido.chars().last() {
-                if ultimo_c == ':' {
+                if ultimo_c == delimitador {
                     contenido_valido = true;
                 }
             }
@@ -285,6 +328,64 @@ fn obtener_contenido_de_clase_caracter(
     }
 }
 
+/// Devuelve el caracter de un símbolo de intercalación o de una clase de equivalencia: el único
+/// caracter de su contenido, o el que corresponde a su nombre en el conjunto de caracteres
+/// portable de POSIX (ej.: "hyphen" → '-').
+///
+/// # Errores
+///
+/// Si el contenido no es un único caracter ni un nombre conocido, se devuelve el error
+/// correspondiente.
+fn caracter_intercalado(nombre: &str) -> Result<char, ErrorExpresionBracket> {
+    let mut chars = nombre.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(c);
+    }
+    let c = match nombre {
+        "NUL" => '\0',
+        "tab" => '\t',
+        "newline" => '\n',
+        "vertical-tab" => '\u{b}',
+        "form-feed" => '\u{c}',
+        "carriage-return" => '\r',
+        "space" => ' ',
+        "exclamation-mark" => '!',
+        "quotation-mark" => '"',
+        "number-sign" => '#',
+        "dollar-sign" => '$',
+        "percent-sign" => '%',
+        "ampersand" => '&',
+        "apostrophe" => '\'',
+        "left-parenthesis" => '(',
+        "right-parenthesis" => ')',
+        "asterisk" => '*',
+        "plus-sign" => '+',
+        "comma" => ',',
+        "hyphen" | "hyphen-minus" => '-',
+        "period" | "full-stop" => '.',
+        "slash" | "solidus" => '/',
+        "colon" => ':',
+        "semicolon" => ';',
+        "less-than-sign" => '<',
+        "equals-sign" => '=',
+        "greater-than-sign" => '>',
+        "question-mark" => '?',
+        "commercial-at" => '@',
+        "left-square-bracket" => '[',
+        "backslash" | "reverse-solidus" => '\\',
+        "right-square-bracket" => ']',
+        "circumflex" | "circumflex-accent" => '^',
+        "underscore" | "low-line" => '_',
+        "grave-accent" => '`',
+        "left-brace" | "left-curly-bracket" => '{',
+        "vertical-line" => '|',
+        "right-brace" | "right-curly-bracket" => '}',
+        "tilde" => '~',
+        _ => return Err(ErrorExpresionBracket::ElementoIntercaladoInvalido),
+    };
+    Ok(c)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -363,4 +464,45 @@ mod tests {
 
         assert_eq!(RegexToken::new(valor, repeticion), resultado);
     }
+
+    fn token_de_bracket(contenido: &str) -> Result<RegexToken, ErrorExpresionBracket> {
+        let v: Vec<char> = contenido.chars().collect();
+        let mut iter_chars = IteradorVecHaciaAtras::new(&v);
+        nuevo_token_expresion_bracket(&mut iter_chars)
+    }
+
+    #[test]
+    fn obtengo_token_con_simbolos_intercalados() {
+        let resultado = token_de_bracket("[.].][.hyphen.]a]").unwrap();
+        let valor = ValorRegexToken::ExpresionBracket(vec![
+            ClaseCaracter::Literal(']'),
+            ClaseCaracter::Literal('-'),
+            ClaseCaracter::Literal('a'),
+        ]);
+
+        assert_eq!(resultado.valor, valor);
+    }
+
+    #[test]
+    fn obtengo_token_con_clase_de_equivalencia() {
+        let resultado = token_de_bracket("^[=é=][:xdigit:]]").unwrap();
+        let valor = ValorRegexToken::ExpresionBracketNegada(vec![
+            ClaseCaracter::Equivalencia('e'),
+            ClaseCaracter::DigitoHexadecimal,
+        ]);
+
+        assert_eq!(resultado.valor, valor);
+    }
+
+    #[test]
+    fn error_elemento_intercalado() {
+        assert_eq!(
+            token_de_bracket("[.guion.]]"),
+            Err(ErrorExpresionBracket::ElementoIntercaladoInvalido)
+        );
+        assert_eq!(
+            token_de_bracket("[=ab=]]"),
+            Err(ErrorExpresionBracket::ElementoIntercaladoInvalido)
+        );
+    }
 }
@@ -0,0 +1,74 @@
+/// Búsqueda de una cadena de bytes con el algoritmo de Boyer–Moore–Horspool.
+///
+/// Al comparar el patrón en una posición del valor, se salta según el último byte comparado:
+/// tanto como la distancia de su última aparición en el patrón (sin contar el último byte) hasta
+/// el final de este, o el largo completo del patrón si no aparece.
+#[derive(Debug)]
+pub struct Horspool {
+    patron: Vec<u8>,
+    saltos: Vec<usize>,
+}
+
+impl Horspool {
+    /// Creación del buscador de un patrón, calculando la tabla de saltos de cada byte.
+    pub fn new(patron: &[u8]) -> Self {
+        let mut saltos = vec![patron.len().max(1); 256];
+        for (i, byte) in patron
+            .iter()
+            .enumerate()
+            .take(patron.len().saturating_sub(1))
+        {
+            saltos[*byte as usize] = patron.len() - 1 - i;
+        }
+        Self {
+            patron: patron.to_vec(),
+            saltos,
+        }
+    }
+
+    /// Devuelve el largo del patrón.
+    pub fn largo(&self) -> usize {
+        self.patron.len()
+    }
+
+    /// Búsqueda de la primera aparición del patrón en un valor, desde una posición.
+    /// Devuelve la posición en la que comienza, o None si no aparece.
+    pub fn buscar(&self, valor: &[u8], desde: usize) -> Option<usize> {
+        let largo = self.patron.len();
+        let mut posicion = desde;
+        while posicion + largo <= valor.len() {
+            if valor[posicion..posicion + largo] == self.patron[..] {
+                return Some(posicion);
+            }
+            if largo == 0 {
+                return None;
+            }
+            posicion += self.saltos[valor[posicion + largo - 1] as usize];
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn busca_primera_aparicion() {
+        let buscador = Horspool::new(b"a.b");
+
+        assert_eq!(buscador.buscar(b"xa.ba.b", 0), Some(1));
+        assert_eq!(buscador.buscar(b"xa.ba.b", 2), Some(4));
+        assert_eq!(buscador.buscar(b"xa.ba.b", 5), None);
+        assert_eq!(buscador.buscar(b"aab", 0), None);
+    }
+
+    #[test]
+    fn patron_vacio_aparece_en_cualquier_posicion() {
+        let buscador = Horspool::new(b"");
+
+        assert_eq!(buscador.buscar(b"ab", 1), Some(1));
+        assert_eq!(buscador.buscar(b"ab", 2), Some(2));
+        assert_eq!(buscador.buscar(b"ab", 3), None);
+    }
+}
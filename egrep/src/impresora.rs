@@ -0,0 +1,239 @@
+use crate::{
+    argumentos::{Argumentos, ListadoArchivos},
+    lectura_archivos::Linea,
+    searcher::{EstadisticasBusqueda, Sink},
+};
+use std::io::{self, Write};
+
+/// Impresora de los resultados de egrep en una salida, según los argumentos. De cada archivo se
+/// imprimen:
+///
+/// * Sus líneas seleccionadas y sus líneas de contexto (o solo las coincidencias de sus líneas
+///   seleccionadas, cada una como una línea, si así se indicó en los argumentos). Las líneas se
+///   escriben tal como fueron leídas, sin conversiones y con su terminador original. Si una línea
+///   no tenía terminador (última línea del archivo), se escribe el terminador de línea con el que
+///   se leyó ('\n' o '\0'). Si se pidieron líneas de contexto, los grupos de líneas no contiguos
+///   (de este u otro archivo) se separan con "--".
+///
+/// * La cantidad de líneas seleccionadas, si se debe contar.
+///
+/// * Su nombre, si se deben listar archivos y el archivo corresponde al listado, seguido de '\n'
+///   (o de '\0', si así se indicó en los argumentos).
+///
+/// Si se recibió más de un archivo, cada línea y cada cantidad se preceden por el nombre de su
+/// archivo, seguido de ':' (o de '\0', si así se indicó en los argumentos). En las líneas de
+/// contexto, el nombre se sigue de '-'.
+///
+/// Los resultados de cada archivo se reciben a través de un [Sink] (ver [Impresora::archivo]).
+pub struct Impresora<'a, W: Write> {
+    salida: W,
+    argumentos: &'a Argumentos,
+    hubo_lineas_impresas: bool,
+}
+
+impl<'a, W: Write> Impresora<'a, W> {
+    /// Creación de la impresora de los resultados en una salida, según los argumentos.
+    pub fn new(salida: W, argumentos: &'a Argumentos) -> Self {
+        Self {
+            salida,
+            argumentos,
+            hubo_lineas_impresas: false,
+        }
+    }
+
+    /// Devuelve el receptor con el que se imprimen los resultados de la búsqueda en un archivo.
+    pub fn archivo<'i>(&'i mut self, archivo: &'i str) -> ImpresoraArchivo<'i, 'a, W> {
+        ImpresoraArchivo {
+            impresora: self,
+            archivo,
+            numero_anterior: None,
+        }
+    }
+}
+
+/// Receptor de los resultados de la búsqueda en un archivo, que los imprime con una [Impresora].
+pub struct ImpresoraArchivo<'i, 'a, W: Write> {
+    impresora: &'i mut Impresora<'a, W>,
+    archivo: &'i str,
+    numero_anterior: Option<usize>,
+}
+
+impl<W: Write> ImpresoraArchivo<'_, '_, W> {
+    /// Devuelve true si se deben imprimir las líneas recibidas (si no se debe contar ni listar
+    /// archivos).
+    fn imprime_lineas(&self) -> bool {
+        let argumentos = self.impresora.argumentos;
+        !argumentos.contar && argumentos.listar_archivos.is_none()
+    }
+
+    /// Escritura de la separación entre grupos de líneas con contexto, si una línea comienza un
+    /// grupo nuevo y ya se imprimieron líneas (de este u otro archivo).
+    fn escribir_separador_de_grupo(&mut self, numero_linea: usize) -> io::Result<()> {
+        let argumentos = self.impresora.argumentos;
+        let hay_contexto = (argumentos.contexto_anterior > 0) | (argumentos.contexto_posterior > 0);
+        let grupo_nuevo = self.numero_anterior.is_none_or(|n| numero_linea != n + 1);
+        if hay_contexto && self.impresora.hubo_lineas_impresas && grupo_nuevo {
+            self.impresora.salida.write_all(b"--\n")?;
+        }
+        self.numero_anterior = Some(numero_linea);
+        self.impresora.hubo_lineas_impresas = true;
+        Ok(())
+    }
+
+    /// Escritura del nombre del archivo como prefijo, si se recibió más de un archivo, seguido de
+    /// un separador (o de '\0', si así se indicó en los argumentos).
+    fn escribir_nombre_archivo(&mut self, separador: u8) -> io::Result<()> {
+        let argumentos = self.impresora.argumentos;
+        if argumentos.archivos.len() > 1 {
+            let salida = &mut self.impresora.salida;
+            salida.write_all(self.archivo.as_bytes())?;
+            salida.write_all(&[if argumentos.nombre_nulo {
+                b'\0'
+            } else {
+                separador
+            }])?;
+        }
+        Ok(())
+    }
+
+    /// Escritura de una línea, precedida por el nombre de su archivo si corresponde (seguido de
+    /// un separador: ':' si es una línea seleccionada o '-' si es de contexto).
+    fn escribir_linea(&mut self, linea: &Linea, separador: u8) -> io::Result<()> {
+        self.escribir_nombre_archivo(separador)?;
+        let terminador = self.impresora.argumentos.lectura.terminador;
+        let salida = &mut self.impresora.salida;
+        salida.write_all(&linea.contenido)?;
+        if linea.terminador.is_empty() {
+            salida.write_all(&[terminador])
+        } else {
+            salida.write_all(&linea.terminador)
+        }
+    }
+
+    /// Escritura de cada coincidencia de una línea como una línea, precedida por el nombre de su
+    /// archivo si corresponde (seguido de ':') y seguida por el terminador de línea con el que se
+    /// leyó ('\n' o '\0').
+    fn escribir_coincidencias(
+        &mut self,
+        linea: &Linea,
+        coincidencias: &[(usize, usize)],
+    ) -> io::Result<()> {
+        let terminador = self.impresora.argumentos.lectura.terminador;
+        for (inicio, fin) in coincidencias {
+            self.escribir_nombre_archivo(b':')?;
+            let salida = &mut self.impresora.salida;
+            salida.write_all(&linea.contenido[*inicio..*fin])?;
+            salida.write_all(&[terminador])?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Sink for ImpresoraArchivo<'_, '_, W> {
+    fn matched(
+        &mut self,
+        numero_linea: usize,
+        _desplazamiento: usize,
+        linea: &Linea,
+        coincidencias: &[(usize, usize)],
+    ) -> io::Result<bool> {
+        if self.imprime_lineas() {
+            self.escribir_separador_de_grupo(numero_linea)?;
+            if self.impresora.argumentos.solo_coincidencias {
+                self.escribir_coincidencias(linea, coincidencias)?;
+            } else {
+                self.escribir_linea(linea, b':')?;
+            }
+        }
+        Ok(true)
+    }
+
+    fn context(
+        &mut self,
+        numero_linea: usize,
+        _desplazamiento: usize,
+        linea: &Linea,
+    ) -> io::Result<bool> {
+        if self.imprime_lineas() {
+            self.escribir_separador_de_grupo(numero_linea)?;
+            self.escribir_linea(linea, b'-')?;
+        }
+        Ok(true)
+    }
+
+    fn finish(&mut self, estadisticas: &EstadisticasBusqueda) -> io::Result<()> {
+        let argumentos = self.impresora.argumentos;
+        let cantidad_seleccionadas = estadisticas.lineas_seleccionadas;
+        if let Some(listado) = argumentos.listar_archivos {
+            let listar = match listado {
+                ListadoArchivos::ConSeleccion => cantidad_seleccionadas > 0,
+                ListadoArchivos::SinSeleccion => cantidad_seleccionadas == 0,
+            };
+            if listar {
+                let salida = &mut self.impresora.salida;
+                salida.write_all(self.archivo.as_bytes())?;
+                salida.write_all(if argumentos.nombre_nulo { b"\0" } else { b"\n" })?;
+            }
+        } else if argumentos.contar {
+            self.escribir_nombre_archivo(b':')?;
+            writeln!(self.impresora.salida, "{}", cantidad_seleccionadas)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linea(contenido: &[u8], terminador: &[u8]) -> Linea {
+        Linea {
+            contenido: contenido.to_vec(),
+            terminador: terminador.to_vec(),
+        }
+    }
+
+    fn argumentos(opciones: &[&str]) -> Argumentos {
+        let args: Vec<String> = ["egrep"]
+            .iter()
+            .chain(opciones)
+            .map(|a| a.to_string())
+            .collect();
+        Argumentos::new(&args).unwrap()
+    }
+
+    #[test]
+    fn grupos_de_contexto_separados_entre_archivos() {
+        let argumentos = argumentos(&["-A1", "a", "uno", "dos"]);
+        let mut impresora = Impresora::new(Vec::new(), &argumentos);
+
+        let mut uno = impresora.archivo("uno");
+        uno.matched(1, 0, &linea(b"a", b"\n"), &[(0, 1)]).unwrap();
+        uno.context(2, 2, &linea(b"b", b"")).unwrap();
+        let mut dos = impresora.archivo("dos");
+        dos.matched(3, 4, &linea(b"ca", b"\r\n"), &[(1, 2)])
+            .unwrap();
+
+        assert_eq!(impresora.salida, b"uno:a\nuno-b\n--\ndos:ca\r\n");
+    }
+
+    #[test]
+    fn cantidades_y_listados_al_terminar() {
+        let estadisticas = EstadisticasBusqueda {
+            lineas_seleccionadas: 2,
+            ..EstadisticasBusqueda::default()
+        };
+
+        let argumentos_contar = argumentos(&["-c", "a", "uno"]);
+        let mut impresora = Impresora::new(Vec::new(), &argumentos_contar);
+        let mut uno = impresora.archivo("uno");
+        uno.matched(1, 0, &linea(b"a", b"\n"), &[]).unwrap();
+        uno.finish(&estadisticas).unwrap();
+        assert_eq!(impresora.salida, b"2\n");
+
+        let argumentos_listar = argumentos(&["-lZ", "a", "uno"]);
+        let mut impresora = Impresora::new(Vec::new(), &argumentos_listar);
+        impresora.archivo("uno").finish(&estadisticas).unwrap();
+        assert_eq!(impresora.salida, b"uno\0");
+    }
+}
@@ -16,6 +16,12 @@ impl<'a, T> IteradorVecHaciaAtras<'a, T> {
         }
     }
 
+    /// Devuelve el índice del último elemento devuelto (0 si todavía no se devolvió ninguno).
+    /// Luego de recorrer todos los elementos, es mayor o igual al largo del vector.
+    pub fn posicion(&self) -> usize {
+        self.indice_actual.unwrap_or(0)
+    }
+
     pub fn prev(&mut self) -> Option<&'a T> {
         let nuevo_indice = match self.indice_actual {
             Some(0) | None => return None,
@@ -42,7 +48,6 @@ impl<'a, T> Iterator for IteradorVecHaciaAtras<'a, T> {
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
 
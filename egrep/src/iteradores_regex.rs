@@ -0,0 +1,167 @@
+use crate::{decodificacion_utf8::largo_primer_caracter, delimitacion::Delimitacion, regex::Regex};
+
+/// Iterador sobre las coincidencias con una expresión regular en un valor (ver
+/// [Regex::find_iter]), que devuelve las posiciones de inicio y fin de cada una.
+///
+/// Las coincidencias no se superponen: cada búsqueda comienza donde termina la coincidencia
+/// anterior, o en el siguiente caracter si esta es vacía. Una coincidencia vacía justo al final de
+/// otra no se devuelve.
+#[derive(Debug)]
+pub struct Coincidencias<'r, 'v> {
+    regex: &'r Regex,
+    valor: &'v [u8],
+    desde: usize,
+    ultimo_fin: Option<usize>,
+}
+
+impl<'r, 'v> Coincidencias<'r, 'v> {
+    /// Creación del iterador sobre las coincidencias de una expresión regular en un valor
+    /// recibido como slice de bytes.
+    pub(crate) fn new(regex: &'r Regex, valor: &'v [u8]) -> Self {
+        Self {
+            regex,
+            valor,
+            desde: 0,
+            ultimo_fin: None,
+        }
+    }
+}
+
+impl Iterator for Coincidencias<'_, '_> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.desde <= self.valor.len() {
+            let (inicio, fin) = self
+                .regex
+                .buscar(self.valor, self.desde, Delimitacion::Ninguna)?;
+            self.desde = if inicio == fin {
+                fin + largo_primer_caracter(&self.valor[fin..]).max(1)
+            } else {
+                fin
+            };
+            if (inicio == fin) & (self.ultimo_fin == Some(fin)) {
+                continue;
+            }
+            self.ultimo_fin = Some(fin);
+            return Some((inicio, fin));
+        }
+        None
+    }
+}
+
+/// Iterador sobre las partes de un valor separadas por las coincidencias con una expresión
+/// regular (ver [Regex::split] y [Regex::splitn]).
+///
+/// Si hay un límite de partes, la última es el resto del valor, aunque contenga coincidencias.
+#[derive(Debug)]
+pub struct Division<'r, 'v> {
+    valor: &'v str,
+    coincidencias: Coincidencias<'r, 'v>,
+    ultimo_fin: usize,
+    partes_restantes: Option<usize>,
+    terminado: bool,
+}
+
+impl<'r, 'v> Division<'r, 'v> {
+    /// Creación del iterador sobre las partes de un valor separadas por las coincidencias de una
+    /// expresión regular, con un límite opcional de partes.
+    pub(crate) fn new(regex: &'r Regex, valor: &'v str, limite: Option<usize>) -> Self {
+        Self {
+            valor,
+            coincidencias: Coincidencias::new(regex, valor.as_bytes()),
+            ultimo_fin: 0,
+            partes_restantes: limite,
+            terminado: false,
+        }
+    }
+
+    /// Devuelve el resto del valor, desde el fin de la última coincidencia, como última parte.
+    fn resto(&mut self) -> Option<&'v str> {
+        self.terminado = true;
+        Some(&self.valor[self.ultimo_fin..])
+    }
+}
+
+impl<'v> Iterator for Division<'_, 'v> {
+    type Item = &'v str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.terminado {
+            return None;
+        }
+        if let Some(restantes) = self.partes_restantes.as_mut() {
+            match restantes {
+                0 => return None,
+                1 => return self.resto(),
+                _ => *restantes -= 1,
+            }
+        }
+        match self.coincidencias.next() {
+            Some((inicio, fin)) => {
+                let parte = &self.valor[self.ultimo_fin..inicio];
+                self.ultimo_fin = fin;
+                Some(parte)
+            }
+            None => self.resto(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coincidencias_sin_superponerse() {
+        let regex = Regex::new("a*").unwrap();
+
+        assert_eq!(
+            regex.find_iter("baaac").collect::<Vec<_>>(),
+            vec![(0, 0), (1, 4), (5, 5)]
+        );
+        assert_eq!(
+            Regex::new("ñ|x")
+                .unwrap()
+                .find_iter("ñaxñ")
+                .collect::<Vec<_>>(),
+            vec![(0, 2), (3, 4), (4, 6)]
+        );
+        assert_eq!(Regex::new("z").unwrap().find_iter("abc").next(), None);
+    }
+
+    #[test]
+    fn division_por_separadores() {
+        let separador = Regex::new("[[:space:]]*[;,][[:space:]]*").unwrap();
+
+        assert_eq!(
+            separador.split("a ; b,c ,  d").collect::<Vec<_>>(),
+            vec!["a", "b", "c", "d"]
+        );
+        assert_eq!(
+            separador.split(",a,").collect::<Vec<_>>(),
+            vec!["", "a", ""]
+        );
+        assert_eq!(separador.split("").collect::<Vec<_>>(), vec![""]);
+        assert_eq!(
+            Regex::new("").unwrap().split("ab").collect::<Vec<_>>(),
+            vec!["", "a", "b", ""]
+        );
+    }
+
+    #[test]
+    fn division_con_limite_de_partes() {
+        let separador = Regex::new(",").unwrap();
+
+        assert_eq!(
+            separador.splitn("a,b,c,d", 2).collect::<Vec<_>>(),
+            vec!["a", "b,c,d"]
+        );
+        assert_eq!(
+            separador.splitn("a,b", 5).collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        assert_eq!(separador.splitn("a,b", 1).collect::<Vec<_>>(), vec!["a,b"]);
+        assert_eq!(separador.splitn("a,b", 0).next(), None);
+    }
+}
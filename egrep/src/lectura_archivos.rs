@@ -1,13 +1,35 @@
 use std::{
     fs::File,
     io::{BufRead, BufReader},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
-use crate::error_lectura_archivos::ErrorArchivo;
+use crate::{
+    codificacion::{Codificacion, LectorDecodificado},
+    error_lectura_archivos::ErrorArchivo,
+};
+
+/// Apertura de un archivo para su lectura, transcodificando su contenido a utf8.
+///
+/// Si no se indica la codificación del archivo, se detecta a través de su BOM (si no tiene,
+/// el contenido se lee sin modificaciones). El BOM nunca forma parte de la primera línea.
+///
+/// # Errores
+///
+/// Si el path al archivo no existe, devuelve un error indicando esto.
+fn abrir_archivo(
+    nombre_archivo: impl AsRef<Path>,
+    codificacion: Option<Codificacion>,
+) -> Result<BufReader<LectorDecodificado<File>>, ErrorArchivo> {
+    let file = File::open(&nombre_archivo)
+        .map_err(|_e| ErrorArchivo::Path(nombre_archivo.as_ref().to_path_buf()))?;
+    Ok(BufReader::new(LectorDecodificado::new(file, codificacion)))
+}
 
 /// Lectura de archivos. Dado un path a un archivo, devuelve las líneas del mismo, si este existe.
 ///
+/// La codificación del archivo se detecta a través de su BOM (utf8, utf-16le o utf-16be).
+///
 /// # Errores
 ///
 /// Si el path al archivo no existe, devuelve un error indicando esto.
@@ -15,9 +37,7 @@ use crate::error_lectura_archivos::ErrorArchivo;
 /// Si una de las lineas no se puede leer, devuelve un error indicando en qué linea del archivo ocurrió.
 ///  
 pub fn leer_lineas_archivo(nombre_archivo: impl AsRef<Path>) -> Result<Vec<String>, ErrorArchivo> {
-    let file = File::open(&nombre_archivo)
-        .map_err(|_e| ErrorArchivo::Path(nombre_archivo.as_ref().to_path_buf()))?;
-    let lector = BufReader::new(file);
+    let lector = abrir_archivo(&nombre_archivo, None)?;
     let mut lineas = Vec::new();
 
     for (i, resultado_lectura) in lector.lines().enumerate() {
@@ -31,6 +51,147 @@ pub fn leer_lineas_archivo(nombre_archivo: impl AsRef<Path>) -> Result<Vec<Strin
     Ok(lineas)
 }
 
+/// Línea de un archivo leída como bytes, junto con el terminador con el que se encontraba en el
+/// archivo (vacío si es la última línea y el archivo no termina en un terminador).
+#[derive(Debug, PartialEq, Clone)]
+pub struct Linea {
+    pub contenido: Vec<u8>,
+    pub terminador: Vec<u8>,
+}
+
+/// Opciones de lectura de las líneas de un archivo como bytes.
+///
+/// * codificacion: codificación del archivo. Si no se indica, se detecta a través de su BOM.
+///
+/// * terminador: byte que separa las líneas (por defecto '\n'; '\0' para datos separados por NUL).
+///
+/// * crlf: si es true y el terminador es '\n', el terminador de línea es "\r\n" (o '\n' si la
+///   línea no tiene '\r'), por lo que el '\r' no forma parte del contenido.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct OpcionesLectura {
+    pub codificacion: Option<Codificacion>,
+    pub terminador: u8,
+    pub crlf: bool,
+}
+
+impl Default for OpcionesLectura {
+    fn default() -> Self {
+        Self {
+            codificacion: None,
+            terminador: b'\n',
+            crlf: false,
+        }
+    }
+}
+
+/// Lector de las líneas de un archivo como bytes, de a una por vez.
+///
+/// Itera sobre las líneas a medida que las lee, por lo que la lectura puede interrumpirse en
+/// cualquier momento sin leer el resto del archivo. Si una línea no se puede leer, devuelve
+/// el error correspondiente y termina la iteración.
+pub struct LectorLineas<R: BufRead> {
+    lector: R,
+    nombre_archivo: PathBuf,
+    opciones: OpcionesLectura,
+    numero_linea: usize,
+    terminado: bool,
+}
+
+impl<R: BufRead> LectorLineas<R> {
+    /// Creación del lector de líneas, dado un lector con el contenido ya transcodificado a utf8,
+    /// el nombre del archivo (para los errores de lectura) y las opciones de lectura.
+    pub fn new(lector: R, nombre_archivo: impl AsRef<Path>, opciones: &OpcionesLectura) -> Self {
+        Self {
+            lector,
+            nombre_archivo: nombre_archivo.as_ref().to_path_buf(),
+            opciones: *opciones,
+            numero_linea: 0,
+            terminado: false,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for LectorLineas<R> {
+    type Item = Result<Linea, ErrorArchivo>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.terminado {
+            return None;
+        }
+        self.numero_linea += 1;
+        let mut contenido = Vec::new();
+        match self
+            .lector
+            .read_until(self.opciones.terminador, &mut contenido)
+        {
+            Ok(0) => {
+                self.terminado = true;
+                None
+            }
+            Ok(_) => {
+                let terminador = separar_terminador(&mut contenido, &self.opciones);
+                Some(Ok(Linea {
+                    contenido,
+                    terminador,
+                }))
+            }
+            Err(_e) => {
+                self.terminado = true;
+                Some(Err(ErrorArchivo::Lectura(
+                    self.nombre_archivo.clone(),
+                    self.numero_linea,
+                )))
+            }
+        }
+    }
+}
+
+/// Apertura de un archivo para leer sus líneas como bytes, separando su contenido de su
+/// terminador, de a una por vez.
+///
+/// A diferencia de [leer_lineas_archivo], las líneas no necesitan ser utf8 válido.
+///
+/// El contenido se transcodifica a utf8 desde la codificación indicada en las opciones. Si no se
+/// indica, se detecta a través del BOM del archivo.
+///
+/// # Errores
+///
+/// Si el path al archivo no existe, devuelve un error indicando esto.
+pub fn abrir_lineas_archivo(
+    nombre_archivo: impl AsRef<Path>,
+    opciones: &OpcionesLectura,
+) -> Result<LectorLineas<BufReader<LectorDecodificado<File>>>, ErrorArchivo> {
+    let lector = abrir_archivo(&nombre_archivo, opciones.codificacion)?;
+    Ok(LectorLineas::new(lector, nombre_archivo, opciones))
+}
+
+/// Lectura de archivos como bytes. Dado un path a un archivo, devuelve todas las líneas del mismo,
+/// separando su contenido de su terminador, si este existe.
+///
+/// # Errores
+///
+/// Si el path al archivo no existe, devuelve un error indicando esto.
+///
+/// Si una de las lineas no se puede leer, devuelve un error indicando en qué linea del archivo ocurrió.
+pub fn leer_lineas_archivo_bytes(
+    nombre_archivo: impl AsRef<Path>,
+    opciones: &OpcionesLectura,
+) -> Result<Vec<Linea>, ErrorArchivo> {
+    abrir_lineas_archivo(nombre_archivo, opciones)?.collect()
+}
+
+/// Separa el terminador del final del contenido de una línea y lo devuelve.
+fn separar_terminador(contenido: &mut Vec<u8>, opciones: &OpcionesLectura) -> Vec<u8> {
+    let mut largo_terminador = 0;
+    if contenido.last() == Some(&opciones.terminador) {
+        largo_terminador = 1;
+        if opciones.crlf && contenido.ends_with(b"\r\n") {
+            largo_terminador = 2;
+        }
+    }
+    contenido.split_off(contenido.len() - largo_terminador)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -56,4 +217,87 @@ mod tests {
 
         assert_eq!(result, Ok(lineas_archivo));
     }
+
+    #[test]
+    fn lee_lineas_archivo_existente_como_bytes() {
+        let path_archivo = "data/test_lectura_archivo.txt";
+        let lineas_archivo = vec![
+            Linea {
+                contenido: b"linea1".to_vec(),
+                terminador: b"\n".to_vec(),
+            },
+            Linea {
+                contenido: b"linea2".to_vec(),
+                terminador: b"\n".to_vec(),
+            },
+            Linea {
+                contenido: b"linea 3".to_vec(),
+                terminador: Vec::new(),
+            },
+        ];
+
+        let result = leer_lineas_archivo_bytes(path_archivo, &OpcionesLectura::default());
+
+        assert_eq!(result, Ok(lineas_archivo));
+    }
+
+    #[test]
+    fn separa_terminadores_crlf() {
+        let mut contenido1 = b"hola\r\n".to_vec();
+        let mut contenido2 = b"hola\r\n".to_vec();
+        let mut contenido3 = b"hola\n".to_vec();
+        let crlf = OpcionesLectura {
+            crlf: true,
+            ..OpcionesLectura::default()
+        };
+
+        assert_eq!(
+            b"\n".to_vec(),
+            separar_terminador(&mut contenido1, &OpcionesLectura::default())
+        );
+        assert_eq!(b"hola\r".to_vec(), contenido1);
+        assert_eq!(b"\r\n".to_vec(), separar_terminador(&mut contenido2, &crlf));
+        assert_eq!(b"hola".to_vec(), contenido2);
+        assert_eq!(b"\n".to_vec(), separar_terminador(&mut contenido3, &crlf));
+        assert_eq!(b"hola".to_vec(), contenido3);
+    }
+
+    #[test]
+    fn separa_terminador_nulo() {
+        let mut contenido = b"linea\ncon salto\0".to_vec();
+        let nulo = OpcionesLectura {
+            terminador: b'\0',
+            ..OpcionesLectura::default()
+        };
+
+        assert_eq!(b"\0".to_vec(), separar_terminador(&mut contenido, &nulo));
+        assert_eq!(b"linea\ncon salto".to_vec(), contenido);
+    }
+
+    #[test]
+    fn lee_lineas_archivo_utf16_con_bom() {
+        let path_archivo = "data/test_lectura_archivo_utf16.txt";
+        let lineas_archivo: Vec<String> = vec!["línea1".to_string(), "línea2".to_string()];
+
+        let result = leer_lineas_archivo(path_archivo);
+
+        assert_eq!(result, Ok(lineas_archivo));
+    }
+
+    #[test]
+    fn lector_de_lineas_puede_interrumpirse() {
+        let contenido: &[u8] = b"uno\ndos\ntres";
+        let mut lector = LectorLineas::new(contenido, "", &OpcionesLectura::default());
+
+        let primera = lector.next();
+
+        assert_eq!(
+            primera,
+            Some(Ok(Linea {
+                contenido: b"uno".to_vec(),
+                terminador: b"\n".to_vec(),
+            }))
+        );
+        assert_eq!(2, lector.count());
+    }
 }
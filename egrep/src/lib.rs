@@ -12,24 +12,55 @@
 //! * Period: .
 //! * Bracket expression: \[]
 //! * Bracket expression negada: \[^]
-//! * Character Classes: \[:alnum:], \[:alpha:], \[:digit:], \[:lower:], \[:upper:], \[:space:], \[:punct:],
+//! * Character Classes: \[:alnum:], \[:alpha:], \[:digit:], \[:xdigit:], \[:lower:], \[:upper:], \[:space:],
+//!   \[:blank:], \[:cntrl:], \[:graph:], \[:print:], \[:punct:], \[:word:]
+//! * Equivalence classes y collating symbols: \[=a=], \[.-.], \[.hyphen.]
+//! * [Propiedades de Unicode][propiedad_unicode]: \\p{L}, \\p{Greek}, \\P{...}. En modo Unicode, las
+//!   clases de caracter se evalúan según las categorías generales de Unicode.
 //! * Anchoring: ^, $
 //! * Repetition: ?, *, +, {n}, {n,}, {,m}, {n,m}
+//! * Grouping: ( )
+//! * Back-references: \\1 a \\9 (evaluadas con un motor de backtracking)
+//! * Escapes de GNU: \\w, \\W, \\s, \\S, \\d, \\D, y las [aserciones][asercion] \\b, \\B, \\<, \\>, \\\`, \\', y \\n (nueva línea)
 //!
 //! Además, la implementación permite la concatenación, la alternancia ('|'), y la precedencia de expresiones regulares.
 //!
-//! Esta implementación no admite el uso de metacaracteres de repeticion concatenados o al principio de una expresión.
-//! Tampoco admite el uso de range expressions dentro de una bracket expression.
+//! Como en GNU grep, los metacaracteres de repetición concatenados repiten al token ya repetido
+//! (ej.: 'a{2}{3}' equivale a 'a{6}'), y al principio de una expresión son literales (ej.: '*a',
+//! 'x|+y'). Luego de un anclaje, lo repiten (ej.: '^*a' matchea con 'xa'), salvo en sintaxis
+//! básica, donde el anclaje inicial cuenta como el principio de la expresión. El
+//! [constructor][regex_builder::RegexBuilder::strict] permite rechazarlos, junto con las
+//! repeticiones de anclajes y aserciones, como las repeticiones que POSIX no define.
+//!
+//! Esta implementación no admite el uso de range expressions dentro de una bracket expression.
 //!
 //! # Herramientas
 //!
 //! * [Lectura de archivos de texto][lectura_archivos] y la obtención de las líneas en dicho archivo.
+//!   Los archivos en utf-16 (con BOM) u otras [codificaciones][codificacion] se transcodifican a utf8.
 //! * Creación de [expresiones regulares][regex] a través de una cadena de caracteres.
 //!   Estas expresiones regulares pueden recibir una cadena de caracteres y reponder si coinciden o no con si misma.
 //!   Cada expresión regular puede ser una [expresión única][expresion] o una alternación de estas.
+//! * Creación de expresiones regulares con [sintaxis][syntax] básica, extendida o compatible con
+//!   Perl (con repeticiones perezosas, anticipaciones, retrospecciones y grupos con nombre) a través de un
+//!   [constructor][regex_builder]. Todas admiten grupos y referencias a ellos. El constructor
+//!   también permite ignorar mayúsculas, usar clases Unicode, anclajes multilínea, limitar el
+//!   tamaño de la expresión compilada y elegir el [motor][engine] con el que se evalúa.
+//! * Recorrido de las [coincidencias][iteradores_regex] con una expresión regular y división de un
+//!   texto en las partes separadas por ellas.
+//! * [Reemplazo][reemplazo] de las coincidencias con una expresión regular, con plantillas que
+//!   referencian las [capturas] de sus grupos (`$1`, `${nombre}`, `\1`) o con funciones.
+//! * Creación de [conjuntos de expresiones regulares][regex_set], que indican cuáles de sus patrones
+//!   coinciden con una cadena de caracteres recorriéndola una única vez.
+//! * [Búsqueda de cadenas fijas][busqueda_cadenas], sin interpretar metacaracteres ni crear
+//!   expresiones regulares.
+//! * [Búsqueda][searcher] de una expresión regular o de cadenas fijas en archivos sin imprimir nada,
+//!   con las opciones de egrep, entregando cada línea seleccionada y de contexto a un receptor propio. La [impresora] de la línea de comando es
+//!   uno de estos receptores.
 //! * Creación de [tokens][regex_token] particulares que forman parte de una expresión regular.
 //!   Para ello, puede crearse el [valor][valor_regex_token] de un token, que puede responder si coincide o no con el primer
-//!   caracter de esta; y la [repetición][repeticion_regex_token] del mismo, que puede modificarse una única vez.
+//!   caracter de esta; y la [repetición][repeticion_regex_token] del mismo. Un token se repite una única vez:
+//!   una repetición que sigue a otra repite al token ya repetido, dentro de un grupo que no captura.
 //!
 //! # grep y expresiones regulares
 //!
@@ -57,18 +88,96 @@
 /// Funcionalidad de egrep. Recepción de parámetros e impresión por pantalla de los resultados.
 pub mod egrep;
 
+/// Búsqueda de los patrones de egrep en archivos, que entrega sus resultados a un receptor.
+pub mod searcher;
+
+/// Impresora de los resultados de egrep, como receptor de los resultados de una búsqueda.
+pub mod impresora;
+
+/// Errores en una búsqueda, al leer un archivo o al entregar sus resultados.
+pub mod error_busqueda;
+
+/// Reescritura de archivos en el lugar, reemplazando las coincidencias de los patrones.
+pub mod reescritura;
+
+/// Argumentos de egrep recibidos por línea de comando.
+pub mod argumentos;
+
+/// Errores en los argumentos recibidos por línea de comando.
+pub mod error_argumentos;
+
 /// Lectura de líneas de archivos de texto.
 pub mod lectura_archivos;
 
+/// Codificaciones de texto y transcodificación a utf8 de su contenido.
+pub mod codificacion;
+
 /// Errores en lecturas de archivo.
 pub mod error_lectura_archivos;
 
 /// Expresiones regulares. Creación a través de un patrón y matcheos con texto.
 pub mod regex;
 
+/// Construcción de expresiones regulares con opciones, como su sintaxis.
+pub mod regex_builder;
+
+/// Sintaxis (dialectos) de las expresiones regulares: básica, extendida y compatible con Perl.
+pub mod syntax;
+
+/// Motores con los que se evalúan las expresiones regulares: un autómata o backtracking.
+pub mod engine;
+
+/// Iteradores sobre las coincidencias con una expresión regular y sobre el texto entre ellas.
+pub mod iteradores_regex;
+
+/// Capturas de una coincidencia con una expresión regular y su expansión en plantillas.
+pub mod capturas;
+
+/// Reemplazos de las coincidencias con una expresión regular: plantillas o funciones.
+pub mod reemplazo;
+
+/// Conjuntos de expresiones regulares que se evalúan a la vez, indicando cuáles matchean.
+pub mod regex_set;
+
+/// Autómata que reconoce varias expresiones a la vez, recorriendo el valor evaluado una única vez.
+pub mod automata;
+
+/// Motor de backtracking para expresiones regulares con referencias a grupos.
+mod backtracking;
+
+/// Búsqueda de cadenas fijas, sin interpretar metacaracteres.
+pub mod busqueda_cadenas;
+
+/// Búsqueda de una cadena con el algoritmo de Boyer–Moore–Horspool.
+mod horspool;
+
+/// Búsqueda simultánea de varias cadenas con el algoritmo de Aho–Corasick.
+mod aho_corasick;
+
+/// Delimitaciones de las coincidencias con un patrón (palabra o línea completa).
+pub mod delimitacion;
+
+/// Aserciones de límites de palabra y de inicio y final del texto.
+pub mod asercion;
+
+/// Propiedades de Unicode (categorías generales, escrituras) que puede cumplir un caracter.
+pub mod propiedad_unicode;
+
+/// Tablas de propiedades de Unicode generadas a partir de su base de datos.
+mod tablas_unicode;
+
+/// Patrones recibidos por línea de comando o en archivos, y su compilación en una única expresión regular.
+pub mod patrones;
+
+/// Errores en la creación de la expresión regular de un patrón, indicando su origen.
+pub mod error_patron;
+
 /// Expresiones regulares, sin alternancia de expresiones ('|').
 pub mod expresion;
 
+/// Error en la creación de una expresión regular, con su tipo y la parte del patrón en la que se encontró.
+pub mod error;
+
 /// Errores en la creación de expresiones regulares (provenientes del patrón de caracteres recibido, sin considerar errores de bracket expresiones).
 pub mod error_regex;
 
@@ -95,3 +204,6 @@ mod rango_repeticion;
 
 /// Iterador para un vector que añade la funcionalidad de iterar hacia atrás.
 mod iterador_vec;
+
+/// Decodificación de caracteres utf8 en secuencias de bytes que pueden no ser utf8 válido.
+mod decodificacion_utf8;
@@ -1,19 +1,21 @@
-use egrep::egrep::egrep;
-use egrep::egrep::imprimir;
-use std::env;
-
-const ARGS_GREP: usize = 3;
-const PATRON: usize = 1;
-const NOMBRE_ARCHIVO: usize = 2;
+use egrep::argumentos::Argumentos;
+use egrep::egrep::imprimir_busqueda;
+use egrep::egrep::SALIDA_ERROR;
+use egrep::reescritura::{aplicar_reescrituras, reescribir_bytes};
+use std::{env, process};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    // Si no se recibe la cantidad de argumentos correcta, se imprime un mensaje de error.
-    if args.len() == ARGS_GREP {
-        let patron = &args[PATRON];
-        let nombre_archivo = &args[NOMBRE_ARCHIVO];
-        imprimir(egrep(patron, nombre_archivo));
-    } else {
-        eprintln!("No se ingresaron los argumentos necesarios");
-    }
+    // Si los argumentos recibidos son inválidos, se imprime un mensaje de error.
+    let codigo_salida = match Argumentos::new(&args) {
+        Ok(argumentos) if argumentos.en_el_lugar => {
+            aplicar_reescrituras(reescribir_bytes(&argumentos), &argumentos)
+        }
+        Ok(argumentos) => imprimir_busqueda(&argumentos),
+        Err(e) => {
+            eprintln!("grep: {}", e);
+            SALIDA_ERROR
+        }
+    };
+    process::exit(codigo_salida);
 }
@@ -0,0 +1,192 @@
+use std::io;
+
+use crate::{
+    error_lectura_archivos::ErrorArchivo,
+    error_patron::ErrorPatron,
+    lectura_archivos::{abrir_lineas_archivo, LectorLineas, Linea, OpcionesLectura},
+    regex::Regex,
+    regex_builder::OpcionesRegex,
+};
+
+/// Fuente de patrones recibida por línea de comando: un patrón (que puede contener varios,
+/// separados por '\n') o un archivo con un patrón por línea ("-" para la entrada estándar).
+#[derive(Debug, PartialEq, Clone)]
+pub enum FuentePatrones {
+    Patron(String),
+    Archivo(String),
+}
+
+/// Origen de un patrón: un argumento de línea de comando o una línea de un archivo de patrones.
+#[derive(Debug, PartialEq, Clone)]
+pub enum OrigenPatron {
+    Argumento,
+    Archivo(String, usize),
+}
+
+/// Patrón a partir del cual se crea una expresión regular, junto con su origen.
+///
+/// Los patrones leídos de archivos no necesitan ser utf8 válido: se guardan como bytes.
+#[derive(Debug, PartialEq)]
+pub struct Patron {
+    pub texto: Vec<u8>,
+    pub origen: OrigenPatron,
+}
+
+/// Obtención de los patrones de cada una de las fuentes recibidas, en orden.
+///
+/// Los archivos de patrones se leen como bytes y su codificación se detecta a través de su BOM,
+/// que se descarta: los que están en utf-16 se transcodifican a utf8, mientras que los demás se
+/// leen sin modificaciones (sus patrones no necesitan ser utf8 válido). Los patrones de la entrada
+/// estándar ("-") se leen siempre sin modificaciones. Sus líneas pueden terminar en '\n' o en
+/// "\r\n".
+///
+/// # Errores
+///
+/// Si alguno de los archivos de patrones no existe o no se puede leer, se devuelve el error
+/// correspondiente.
+pub fn obtener_patrones(fuentes: &[FuentePatrones]) -> Result<Vec<Patron>, ErrorArchivo> {
+    let mut patrones = Vec::new();
+    for fuente in fuentes {
+        match fuente {
+            FuentePatrones::Patron(texto) => {
+                patrones.extend(texto.split('\n').map(|texto| Patron {
+                    texto: texto.as_bytes().to_vec(),
+                    origen: OrigenPatron::Argumento,
+                }));
+            }
+            FuentePatrones::Archivo(nombre_archivo) => {
+                let opciones = OpcionesLectura {
+                    crlf: true,
+                    ..OpcionesLectura::default()
+                };
+                let lineas: Result<Vec<Linea>, ErrorArchivo> = if nombre_archivo == "-" {
+                    LectorLineas::new(io::stdin().lock(), "-", &opciones).collect()
+                } else {
+                    abrir_lineas_archivo(nombre_archivo, &opciones)?.collect()
+                };
+                patrones.extend(lineas?.into_iter().enumerate().map(|(i, linea)| Patron {
+                    texto: linea.contenido,
+                    origen: OrigenPatron::Archivo(nombre_archivo.to_string(), i + 1),
+                }));
+            }
+        }
+    }
+    Ok(patrones)
+}
+
+/// Creación de una única expresión regular que matchea con un valor si alguno de los patrones
+/// recibidos (cada uno creado con las mismas [opciones][OpcionesRegex]) matchea con él.
+///
+/// Los patrones se analizan por separado, pero se compilan en un único autómata. Los patrones con
+/// referencias a grupos se evalúan aparte de los demás (ver [Regex::alternar]). Un patrón vacío
+/// matchea con cualquier valor. Si no se recibe ningún patrón, la expresión regular no matchea con
+/// ningún valor.
+///
+/// # Errores
+///
+/// Si alguno de los patrones es inválido, se devuelve su error junto con su origen.
+pub fn compilar_patrones(
+    patrones: &[Patron],
+    opciones: &OpcionesRegex,
+) -> Result<Regex, ErrorPatron> {
+    let analizados = patrones
+        .iter()
+        .map(|patron| {
+            Regex::analizar(&patron.texto, opciones).map_err(|error| ErrorPatron {
+                origen: patron.origen.clone(),
+                error,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Regex::desde_patrones(analizados))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patrones_de_argumentos_y_archivos() {
+        let fuentes = vec![
+            FuentePatrones::Patron("uno\ndos".to_string()),
+            FuentePatrones::Archivo("data/test_lectura_archivo.txt".to_string()),
+        ];
+
+        let patrones = obtener_patrones(&fuentes).unwrap();
+
+        assert_eq!(patrones.len(), 5);
+        assert_eq!(
+            patrones[1],
+            Patron {
+                texto: b"dos".to_vec(),
+                origen: OrigenPatron::Argumento,
+            }
+        );
+        assert_eq!(
+            patrones[4],
+            Patron {
+                texto: b"linea 3".to_vec(),
+                origen: OrigenPatron::Archivo("data/test_lectura_archivo.txt".to_string(), 3),
+            }
+        );
+    }
+
+    #[test]
+    fn archivos_de_patrones_que_no_son_utf8() {
+        let archivo = std::env::temp_dir().join(format!("egrep-patrones-{}", std::process::id()));
+        std::fs::write(&archivo, b"se\xF1al\r\n(x)\\1\n").unwrap();
+        let fuentes = vec![FuentePatrones::Archivo(archivo.display().to_string())];
+
+        let patrones = obtener_patrones(&fuentes).unwrap();
+        std::fs::remove_file(&archivo).unwrap();
+        let regex = compilar_patrones(&patrones, &OpcionesRegex::default()).unwrap();
+
+        assert_eq!(patrones[0].texto, b"se\xF1al");
+        assert!(regex.is_match_bytes(b"una se\xF1al"));
+        assert!(regex.matchea("axxb"));
+        assert!(!regex.matchea("se\u{F1}al"));
+    }
+
+    #[test]
+    fn compila_patrones_en_una_sola_expresion() {
+        let patrones = vec![
+            Patron {
+                texto: b"^hola".to_vec(),
+                origen: OrigenPatron::Argumento,
+            },
+            Patron {
+                texto: b"chau$|adios".to_vec(),
+                origen: OrigenPatron::Argumento,
+            },
+        ];
+
+        let regex = compilar_patrones(&patrones, &OpcionesRegex::default()).unwrap();
+
+        assert!(regex.matchea("hola mundo"));
+        assert!(regex.matchea("bueno, chau"));
+        assert!(regex.matchea("adios!"));
+        assert!(!regex.matchea("chau!"));
+        assert!(!compilar_patrones(&[], &OpcionesRegex::default())
+            .unwrap()
+            .matchea("hola"));
+    }
+
+    #[test]
+    fn error_indica_origen_del_patron() {
+        let patrones = vec![Patron {
+            texto: b"[ab".to_vec(),
+            origen: OrigenPatron::Archivo("patrones.txt".to_string(), 7),
+        }];
+
+        let error = compilar_patrones(&patrones, &OpcionesRegex::default()).unwrap_err();
+
+        assert_eq!(
+            error.origen,
+            OrigenPatron::Archivo("patrones.txt".to_string(), 7)
+        );
+        assert_eq!(
+            error.to_string(),
+            "patrones.txt:7: unmatched [, [^ or [:\n  [ab\n  ^~~".to_string()
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use std::fmt;
+
+use crate::tablas_unicode::{CATEGORIAS, ESCRITURAS, PROPIEDADES_BINARIAS};
+
+/// Propiedad de Unicode de un caracter: una o más categorías generales (ej.: L, Lu, Nd), una
+/// escritura (ej.: Greek, Latin) o una propiedad binaria (ej.: White_Space).
+///
+/// Se guarda su nombre y los rangos de caracteres que la cumplen, obtenidos de las tablas
+/// generadas a partir de la base de datos de Unicode.
+#[derive(PartialEq, Clone)]
+pub struct PropiedadUnicode {
+    nombre: String,
+    tablas: Vec<&'static [(u32, u32)]>,
+}
+
+impl PropiedadUnicode {
+    /// Creación de una propiedad a partir de su nombre, como se escribe en \p{...}:
+    ///
+    /// * Una categoría general de dos letras (ej.: Lu), o de una letra para todas las que
+    ///   comienzan con ella (ej.: L). LC (o L&) son las letras con mayúscula y minúscula.
+    /// * Una escritura (ej.: Greek, Han), opcionalmente precedida por `Script=` o `sc=`.
+    /// * Una propiedad binaria (White_Space o Alphabetic).
+    ///
+    /// Devuelve None si el nombre es desconocido.
+    pub fn new(nombre: &str) -> Option<Self> {
+        let categorias: &[&str] = match nombre {
+            "LC" | "L&" => &["Lu", "Ll", "Lt"],
+            _ => &[nombre],
+        };
+        if nombre.len() <= 2 {
+            let propiedad = Self::de_categorias(categorias);
+            if !propiedad.tablas.is_empty() {
+                return Some(Self::con_tablas(nombre, propiedad.tablas));
+            }
+        }
+        let escritura = nombre
+            .strip_prefix("Script=")
+            .or_else(|| nombre.strip_prefix("sc="))
+            .unwrap_or(nombre);
+        ESCRITURAS
+            .iter()
+            .chain(PROPIEDADES_BINARIAS)
+            .find(|(propiedad, _)| *propiedad == escritura)
+            .map(|(_, rangos)| Self::con_tablas(nombre, vec![*rangos]))
+    }
+
+    /// Creación de la propiedad formada por varias categorías generales a la vez, cada una de dos
+    /// letras o de una letra (para todas las que comienzan con ella).
+    pub(crate) fn de_categorias(categorias: &[&str]) -> Self {
+        let tablas = CATEGORIAS
+            .iter()
+            .filter(|(categoria, _)| {
+                categorias
+                    .iter()
+                    .any(|prefijo| !prefijo.is_empty() && categoria.starts_with(prefijo))
+            })
+            .map(|(_, rangos)| *rangos)
+            .collect();
+        Self::con_tablas(&categorias.join("|"), tablas)
+    }
+
+    fn con_tablas(nombre: &str, tablas: Vec<&'static [(u32, u32)]>) -> Self {
+        Self {
+            nombre: nombre.to_string(),
+            tablas,
+        }
+    }
+
+    /// Devuelve true si un caracter cumple con la propiedad.
+    pub fn contiene(&self, c: char) -> bool {
+        let codigo = c as u32;
+        self.tablas.iter().any(|rangos| {
+            rangos
+                .binary_search_by(|(inicio, fin)| {
+                    if *fin < codigo {
+                        std::cmp::Ordering::Less
+                    } else if *inicio > codigo {
+                        std::cmp::Ordering::Greater
+                    } else {
+                        std::cmp::Ordering::Equal
+                    }
+                })
+                .is_ok()
+        })
+    }
+
+    /// Devuelve una propiedad equivalente que no distingue mayúsculas de minúsculas: las
+    /// categorías de letras mayúsculas, minúsculas o de título se convierten en la de todas ellas.
+    pub fn ignorar_mayusculas(self) -> Self {
+        match self.nombre.as_str() {
+            "Lu" | "Ll" | "Lt" => Self::de_categorias(&["Lu", "Ll", "Lt"]),
+            _ => self,
+        }
+    }
+}
+
+impl fmt::Debug for PropiedadUnicode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "PropiedadUnicode({})", self.nombre)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn categorias_generales() {
+        let letras = PropiedadUnicode::new("L").unwrap();
+        let mayusculas = PropiedadUnicode::new("Lu").unwrap();
+
+        assert!(letras.contiene('ñ'));
+        assert!(letras.contiene('語'));
+        assert!(!letras.contiene('1'));
+        assert!(mayusculas.contiene('É'));
+        assert!(!mayusculas.contiene('é'));
+        assert!(mayusculas.ignorar_mayusculas().contiene('é'));
+        assert!(PropiedadUnicode::new("Nd").unwrap().contiene('٣'));
+    }
+
+    #[test]
+    fn escrituras_y_propiedades_binarias() {
+        let griego = PropiedadUnicode::new("Greek").unwrap();
+
+        assert!(griego.contiene('λ'));
+        assert!(!griego.contiene('l'));
+        assert!(PropiedadUnicode::new("sc=Latin").unwrap().contiene('ç'));
+        assert!(PropiedadUnicode::new("White_Space")
+            .unwrap()
+            .contiene('\u{a0}'));
+    }
+
+    #[test]
+    fn nombre_desconocido() {
+        assert_eq!(PropiedadUnicode::new("Klingon"), None);
+        assert_eq!(PropiedadUnicode::new("Q"), None);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     error_regex::ErrorRegex, iterador_vec::IteradorVecHaciaAtras,
-    repeticion_regex_token::RepeticionRegexToken,
+    repeticion_regex_token::RepeticionRegexToken, syntax::Syntax,
 };
 
 /// Obtención de un rango de repetición a partir de un caracter de repetición y
@@ -9,10 +9,15 @@ use crate::{
 ///
 /// Si el caracter de repetición es ?, + ó *, devuelve la repetición que corresponda.
 ///
-/// Si es {, decide si devuelve una repetición o nada.
+/// Si es {, decide si devuelve una repetición o nada. El rango se cierra con } o, en expresiones
+/// regulares básicas, con \\}.
 /// Si no devuelve nada, no modifica el iterador.
 /// Si devuelve un rango, avanza en el iterador para que el siguiente caracter sea el que le sigue a }.
 ///
+/// En sintaxis compatible con Perl, si a la repetición le sigue un ?, se consume y la repetición
+/// es perezosa (ej.: *?, {1,3}?); si le sigue un +, se consume y la repetición es posesiva
+/// (ej.: *+, {1,3}+).
+///
 /// # Errores
 ///
 /// Devuelve error si el contenido de un rango de repetición es inválido. Los casos son:
@@ -22,17 +27,30 @@ use crate::{
 ///
 /// * Si se tiene dos números corectamente y luego una coma. Ejemplo: '1', ',' '2' ',', '}' son los cinco
 ///   caracteres siguientes en el iterador.
+///
+/// * Si algún número supera la [máxima cantidad de repeticiones](MAXIMO_REPETICIONES) (ej.:
+///   '{40000}') o no se puede representar.
 pub fn obtener_repeticion(
     c: char,
     iter_chars: &mut IteradorVecHaciaAtras<char>,
+    sintaxis: Syntax,
 ) -> Result<Option<RepeticionRegexToken>, ErrorRegex> {
     let repeticion = match c {
         '*' => Some(RepeticionRegexToken::new(None, None)),
         '?' => Some(RepeticionRegexToken::new(None, Some(1))),
         '+' => Some(RepeticionRegexToken::new(Some(1), None)),
-        '{' => obtener_rango_repeticion(iter_chars)?,
+        '{' => obtener_rango_repeticion(iter_chars, sintaxis == Syntax::Bre)?,
         _ => None,
     };
+    if let (Some(rep), Syntax::Perl) = (&repeticion, sintaxis) {
+        match iter_chars.next() {
+            Some('?') => return Ok(Some(rep.clone().perezosa())),
+            Some('+') => return Ok(Some(rep.clone().posesiva())),
+            _ => {
+                iter_chars.prev();
+            }
+        }
+    }
     Ok(repeticion)
 }
 
@@ -47,11 +65,12 @@ pub fn obtener_repeticion(
 /// repetición en una expresión regular.
 fn obtener_rango_repeticion(
     iter_chars: &mut IteradorVecHaciaAtras<char>,
+    cierre_escapado: bool,
 ) -> Result<Option<RepeticionRegexToken>, ErrorRegex> {
-    let contenido_rango = obtener_contenido_de_rango_repeticion(iter_chars)?;
+    let contenido_rango = obtener_contenido_de_rango_repeticion(iter_chars, cierre_escapado)?;
     if let Some(contenido) = contenido_rango {
         let params_rango: Vec<&str> = contenido.split(',').collect();
-        let (min, max) = valores_rango(params_rango);
+        let (min, max) = valores_rango(params_rango)?;
         if (min > max) & (max.is_some()) {
             return Err(ErrorRegex::ContenidoInvalidoRepeticion);
         }
@@ -67,26 +86,32 @@ fn obtener_rango_repeticion(
 ///
 /// Si no es un rango, devuelve None y deja el iterador como estaba.
 ///
-/// Si es, devuelve el contenido y deja el iterador avanzado hasta después de }.
+/// Si es, devuelve el contenido y deja el iterador avanzado hasta después de } (o de \\}, si el
+/// cierre es escapado).
 ///
 /// Devuelve error si el contenido del rango es inválido.
 fn obtener_contenido_de_rango_repeticion(
     iter_chars: &mut IteradorVecHaciaAtras<char>,
+    cierre_escapado: bool,
 ) -> Result<Option<String>, ErrorRegex> {
     let mut pasos_adelante = 0;
     let mut comas = 0;
-    let es_rango = es_rango(iter_chars, &mut pasos_adelante, &mut comas)?;
+    let largo_cierre = if cierre_escapado { 2 } else { 1 };
+    let es_rango = es_rango(iter_chars, &mut pasos_adelante, &mut comas, cierre_escapado)?;
     if !es_rango {
         for _ in 0..pasos_adelante {
             iter_chars.prev();
         }
         Ok(None)
     } else {
-        if !(0..2).contains(&comas) | (pasos_adelante == 1) {
+        if !(0..2).contains(&comas) | (pasos_adelante == largo_cierre) {
             return Err(ErrorRegex::ContenidoInvalidoRepeticion);
         }
+        for _ in 1..largo_cierre {
+            iter_chars.prev();
+        }
         let mut contenido_rango_rev = String::new();
-        for _ in 0..pasos_adelante - 1 {
+        for _ in 0..pasos_adelante - largo_cierre {
             if let Some(c) = iter_chars.prev() {
                 contenido_rango_rev.push(*c);
             }
@@ -103,8 +128,9 @@ fn es_rango(
     iter_chars: &mut IteradorVecHaciaAtras<char>,
     pasos_adelante: &mut usize,
     comas: &mut usize,
+    cierre_escapado: bool,
 ) -> Result<bool, ErrorRegex> {
-    for c in iter_chars.by_ref() {
+    while let Some(c) = iter_chars.next() {
         if *comas > 1 {
             return Err(ErrorRegex::ContenidoInvalidoRepeticion);
         }
@@ -113,8 +139,15 @@ fn es_rango(
             continue;
         } else if *c == ',' {
             *comas += 1;
-        } else if *c == '}' {
+        } else if (*c == '}') & !cierre_escapado {
             return Ok(true);
+        } else if (*c == '\\') & cierre_escapado {
+            if iter_chars.next() == Some(&'}') {
+                *pasos_adelante += 1;
+                return Ok(true);
+            }
+            iter_chars.prev();
+            break;
         } else {
             break;
         }
@@ -122,35 +155,34 @@ fn es_rango(
     Ok(false)
 }
 
-fn valores_rango(params_rango: Vec<&str>) -> (Option<usize>, Option<usize>) {
-    let min: Option<usize>;
-    let max: Option<usize>;
-    if params_rango.len() == 1 {
-        match params_rango[0].parse::<usize>() {
-            Ok(n) => {
-                min = Some(n);
-                max = Some(n);
-            }
-            Err(_) => {
-                min = None;
-                max = None;
-            }
-        }
-    } else {
-        match params_rango[0].parse::<usize>() {
-            Ok(n) => min = Some(n),
-            Err(_) => min = None,
+/// Máxima cantidad de repeticiones de un rango (RE_DUP_MAX en GNU).
+const MAXIMO_REPETICIONES: usize = 32767;
+
+/// Obtiene el mínimo y el máximo de un rango a partir de sus parámetros (uno solo si el rango es
+/// de una cantidad exacta). Un parámetro vacío indica que el rango no tiene ese límite.
+///
+/// Devuelve error si algún límite supera la [máxima cantidad de repeticiones](MAXIMO_REPETICIONES)
+/// o no se puede representar.
+fn valores_rango(params_rango: Vec<&str>) -> Result<(Option<usize>, Option<usize>), ErrorRegex> {
+    let valor = |param: &str| {
+        if param.is_empty() {
+            return Ok(None);
         }
-        match params_rango[1].parse::<usize>() {
-            Ok(m) => max = Some(m),
-            Err(_) => max = None,
+        match param.parse::<usize>() {
+            Ok(n) if n > MAXIMO_REPETICIONES => Err(ErrorRegex::RepeticionDemasiadoGrande),
+            Ok(n) => Ok(Some(n)),
+            Err(_) => Err(ErrorRegex::ContenidoInvalidoRepeticion),
         }
+    };
+    if params_rango.len() == 1 {
+        let cantidad = valor(params_rango[0])?;
+        Ok((cantidad, cantidad))
+    } else {
+        Ok((valor(params_rango[0])?, valor(params_rango[1])?))
     }
-    (min, max)
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
 
@@ -159,7 +191,9 @@ mod tests {
         let v = Vec::new();
         let mut iter_chars = IteradorVecHaciaAtras::new(&v);
 
-        let repeticion = obtener_repeticion('*', &mut iter_chars).unwrap().unwrap();
+        let repeticion = obtener_repeticion('*', &mut iter_chars, Syntax::Ere)
+            .unwrap()
+            .unwrap();
 
         assert_eq!(RepeticionRegexToken::new(None, None), repeticion);
     }
@@ -169,7 +203,9 @@ mod tests {
         let v = Vec::new();
         let mut iter_chars = IteradorVecHaciaAtras::new(&v);
 
-        let repeticion = obtener_repeticion('?', &mut iter_chars).unwrap().unwrap();
+        let repeticion = obtener_repeticion('?', &mut iter_chars, Syntax::Ere)
+            .unwrap()
+            .unwrap();
 
         assert_eq!(RepeticionRegexToken::new(None, Some(1)), repeticion);
     }
@@ -179,7 +215,9 @@ mod tests {
         let v = Vec::new();
         let mut iter_chars = IteradorVecHaciaAtras::new(&v);
 
-        let repeticion = obtener_repeticion('+', &mut iter_chars).unwrap().unwrap();
+        let repeticion = obtener_repeticion('+', &mut iter_chars, Syntax::Ere)
+            .unwrap()
+            .unwrap();
 
         assert_eq!(RepeticionRegexToken::new(Some(1), None), repeticion);
     }
@@ -189,7 +227,9 @@ mod tests {
         let v = vec!['1', '}'];
         let mut iter_chars = IteradorVecHaciaAtras::new(&v);
 
-        let repeticion = obtener_repeticion('{', &mut iter_chars).unwrap().unwrap();
+        let repeticion = obtener_repeticion('{', &mut iter_chars, Syntax::Ere)
+            .unwrap()
+            .unwrap();
 
         assert_eq!(RepeticionRegexToken::new(Some(1), Some(1)), repeticion);
     }
@@ -199,7 +239,9 @@ mod tests {
         let v = vec!['1', ',', '5', '}'];
         let mut iter_chars = IteradorVecHaciaAtras::new(&v);
 
-        let repeticion = obtener_repeticion('{', &mut iter_chars).unwrap().unwrap();
+        let repeticion = obtener_repeticion('{', &mut iter_chars, Syntax::Ere)
+            .unwrap()
+            .unwrap();
 
         assert_eq!(RepeticionRegexToken::new(Some(1), Some(5)), repeticion);
     }
@@ -211,8 +253,12 @@ mod tests {
         let v2 = vec![',', '5', '}'];
         let mut iter_chars2 = IteradorVecHaciaAtras::new(&v2);
 
-        let repeticion1 = obtener_repeticion('{', &mut iter_chars1).unwrap().unwrap();
-        let repeticion2 = obtener_repeticion('{', &mut iter_chars2).unwrap().unwrap();
+        let repeticion1 = obtener_repeticion('{', &mut iter_chars1, Syntax::Ere)
+            .unwrap()
+            .unwrap();
+        let repeticion2 = obtener_repeticion('{', &mut iter_chars2, Syntax::Ere)
+            .unwrap()
+            .unwrap();
 
         assert_eq!(RepeticionRegexToken::new(Some(1), None), repeticion1);
         assert_eq!(RepeticionRegexToken::new(None, Some(5)), repeticion2);
@@ -225,8 +271,8 @@ mod tests {
         let mut iter_chars1 = IteradorVecHaciaAtras::new(&v1);
         let mut iter_chars2 = IteradorVecHaciaAtras::new(&v2);
 
-        let repeticion1 = obtener_repeticion('{', &mut iter_chars1).unwrap();
-        let repeticion2 = obtener_repeticion('{', &mut iter_chars2).unwrap();
+        let repeticion1 = obtener_repeticion('{', &mut iter_chars1, Syntax::Ere).unwrap();
+        let repeticion2 = obtener_repeticion('{', &mut iter_chars2, Syntax::Ere).unwrap();
 
         assert_eq!(repeticion1, None);
         assert_eq!(repeticion2, None);
@@ -239,10 +285,126 @@ mod tests {
         let v2 = vec!['1', ',', '2', ',', '3', '}'];
         let mut iter_chars2 = IteradorVecHaciaAtras::new(&v2);
 
-        let repeticion1 = obtener_repeticion('{', &mut iter_chars1);
-        let repeticion2 = obtener_repeticion('{', &mut iter_chars2);
+        let repeticion1 = obtener_repeticion('{', &mut iter_chars1, Syntax::Ere);
+        let repeticion2 = obtener_repeticion('{', &mut iter_chars2, Syntax::Ere);
 
         assert_eq!(Err(ErrorRegex::ContenidoInvalidoRepeticion), repeticion1);
         assert_eq!(Err(ErrorRegex::ContenidoInvalidoRepeticion), repeticion2);
     }
+
+    #[test]
+    fn error_con_rango_demasiado_grande() {
+        let obtener = |contenido: &str| {
+            let v: Vec<char> = contenido.chars().collect();
+            obtener_repeticion('{', &mut IteradorVecHaciaAtras::new(&v), Syntax::Ere)
+        };
+
+        assert_eq!(
+            Ok(Some(RepeticionRegexToken::new(Some(1), Some(32767)))),
+            obtener("1,32767}")
+        );
+        assert_eq!(
+            Err(ErrorRegex::RepeticionDemasiadoGrande),
+            obtener("32768}")
+        );
+        assert_eq!(
+            Err(ErrorRegex::RepeticionDemasiadoGrande),
+            obtener("1,100000000}")
+        );
+        assert_eq!(
+            Err(ErrorRegex::ContenidoInvalidoRepeticion),
+            obtener("99999999999999999999}")
+        );
+    }
+
+    #[test]
+    fn obtengo_rango_con_cierre_escapado() {
+        let v = vec!['2', ',', '3', '\\', '}', 'a'];
+        let mut iter_chars = IteradorVecHaciaAtras::new(&v);
+
+        let repeticion = obtener_repeticion('{', &mut iter_chars, Syntax::Bre)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(repeticion, RepeticionRegexToken::new(Some(2), Some(3)));
+        assert_eq!(iter_chars.next(), Some(&'a'));
+    }
+
+    #[test]
+    fn sin_cierre_escapado_no_es_rango() {
+        let v = vec!['{', '2', '}', 'a'];
+        let mut iter_chars = IteradorVecHaciaAtras::new(&v);
+        iter_chars.next();
+
+        let repeticion = obtener_repeticion('{', &mut iter_chars, Syntax::Bre).unwrap();
+
+        assert_eq!(repeticion, None);
+        assert_eq!(iter_chars.next(), Some(&'2'));
+    }
+
+    #[test]
+    fn obtengo_repeticiones_perezosas() {
+        let v = vec!['*', '?', 'a', '{', '1', ',', '2', '}', '?', '?'];
+        let mut iter_chars = IteradorVecHaciaAtras::new(&v);
+        iter_chars.next();
+
+        let repeticion = obtener_repeticion('*', &mut iter_chars, Syntax::Perl)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(repeticion, RepeticionRegexToken::new(None, None).perezosa());
+        assert_eq!(iter_chars.next(), Some(&'a'));
+
+        iter_chars.next();
+        let repeticion = obtener_repeticion('{', &mut iter_chars, Syntax::Perl)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            repeticion,
+            RepeticionRegexToken::new(Some(1), Some(2)).perezosa()
+        );
+        assert_eq!(iter_chars.next(), Some(&'?'));
+    }
+
+    #[test]
+    fn obtengo_repeticiones_posesivas() {
+        let v = vec!['?', '+', '{', '2', ',', '}', '+', '+'];
+        let mut iter_chars = IteradorVecHaciaAtras::new(&v);
+        iter_chars.next();
+
+        let repeticion = obtener_repeticion('?', &mut iter_chars, Syntax::Perl)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            repeticion,
+            RepeticionRegexToken::new(None, Some(1)).posesiva()
+        );
+
+        iter_chars.next();
+        let repeticion = obtener_repeticion('{', &mut iter_chars, Syntax::Perl)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            repeticion,
+            RepeticionRegexToken::new(Some(2), None).posesiva()
+        );
+        assert_eq!(iter_chars.next(), Some(&'+'));
+    }
+
+    #[test]
+    fn sin_sintaxis_perl_no_hay_repeticiones_perezosas() {
+        let v = vec!['+', '?'];
+        let mut iter_chars = IteradorVecHaciaAtras::new(&v);
+        iter_chars.next();
+
+        let repeticion = obtener_repeticion('+', &mut iter_chars, Syntax::Ere)
+            .unwrap()
+            .unwrap();
+
+        assert!(!repeticion.es_perezosa());
+        assert_eq!(iter_chars.next(), Some(&'?'));
+    }
 }
@@ -0,0 +1,28 @@
+use crate::capturas::Capturas;
+
+/// Forma de reemplazar las coincidencias con una expresión regular (ver
+/// [Regex::replace](crate::regex::Regex::replace)).
+///
+/// Se implementa para plantillas (`&str`), en las que se expanden las referencias a las capturas
+/// de cada coincidencia (ver [Capturas::expandir]), y para funciones que reciben las capturas y
+/// devuelven el reemplazo.
+pub trait Reemplazo {
+    /// Agrega a un destino el reemplazo de una coincidencia, a partir de sus capturas.
+    fn reemplazar(&mut self, capturas: &Capturas, destino: &mut Vec<u8>);
+}
+
+impl Reemplazo for &str {
+    fn reemplazar(&mut self, capturas: &Capturas, destino: &mut Vec<u8>) {
+        capturas.expandir(self, destino);
+    }
+}
+
+impl<F, T> Reemplazo for F
+where
+    F: FnMut(&Capturas) -> T,
+    T: AsRef<[u8]>,
+{
+    fn reemplazar(&mut self, capturas: &Capturas, destino: &mut Vec<u8>) {
+        destino.extend_from_slice(self(capturas).as_ref());
+    }
+}
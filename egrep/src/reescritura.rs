@@ -0,0 +1,395 @@
+use std::{
+    error,
+    fs::{self, OpenOptions, Permissions},
+    io::{self, BufReader, Write},
+    path::{Path, PathBuf},
+    process,
+};
+
+use crate::{
+    argumentos::Argumentos,
+    codificacion::{Codificacion, LectorDecodificado},
+    egrep::{Buscador, SALIDA_CON_SELECCION, SALIDA_ERROR, SALIDA_SIN_SELECCION},
+    error_lectura_archivos::ErrorArchivo,
+    lectura_archivos::{LectorLineas, Linea},
+};
+
+type Result<T> = std::result::Result<T, Box<dyn error::Error>>;
+
+/// Cantidad de líneas sin cambios que se muestran antes y después de cada cambio en las
+/// diferencias.
+const LINEAS_DE_CONTEXTO: usize = 3;
+
+/// Línea de un archivo a reescribir: la línea original y su contenido con las coincidencias
+/// reemplazadas.
+#[derive(Debug, PartialEq)]
+pub struct LineaReescrita {
+    pub linea: Linea,
+    pub contenido_nuevo: Vec<u8>,
+}
+
+impl LineaReescrita {
+    /// Devuelve true si el contenido de la línea cambió al reemplazar sus coincidencias.
+    pub fn cambio(&self) -> bool {
+        self.linea.contenido != self.contenido_nuevo
+    }
+}
+
+/// Resultado de la reescritura de uno de los archivos recibidos: sus líneas reescritas (ya
+/// transcodificadas a utf8) o el error que se produjo al leerlo, y la codificación y el BOM con
+/// los que se vuelven a escribir (sin codificación, si el contenido se leyó sin modificaciones).
+#[derive(Debug)]
+pub struct ReescrituraArchivo {
+    pub archivo: String,
+    pub lineas: std::result::Result<Vec<LineaReescrita>, ErrorArchivo>,
+    pub codificacion: Option<Codificacion>,
+    pub bom: Vec<u8>,
+}
+
+/// Dados los argumentos de egrep, devuelve las líneas de cada archivo recibido con sus
+/// coincidencias reemplazadas por la plantilla de reemplazo, sin modificar los archivos.
+///
+/// Los archivos se leen y se reescriben de a uno, a medida que se recorre el iterador devuelto,
+/// por lo que solo se guarda en memoria el contenido de uno de ellos a la vez.
+///
+/// Solo se consideran las coincidencias que cumplen con la delimitación indicada. Como en
+/// [egrep_bytes](crate::egrep::egrep_bytes), el contenido de cada archivo se transcodifica a utf8
+/// según las opciones de lectura (sin su BOM) antes de buscar, y al reescribirlo se vuelve a
+/// codificar como estaba, con el mismo BOM. Las secuencias inválidas en su codificación se
+/// reescriben como el caracter de reemplazo, salvo en utf8, en el que se conservan.
+///
+/// # Errores
+///
+/// Si algún archivo de patrones no se puede leer o algún patrón es inválido, se devuelve su error.
+/// Los errores de lectura de cada archivo se devuelven en su resultado, sin interrumpir la
+/// reescritura de los demás.
+pub fn reescribir_bytes(
+    argumentos: &Argumentos,
+) -> Result<impl Iterator<Item = ReescrituraArchivo> + '_> {
+    let buscador = Buscador::desde_argumentos(argumentos)?;
+    let plantilla = argumentos.reemplazo.as_deref().unwrap_or("$0");
+    let reescrituras = argumentos
+        .archivos
+        .iter()
+        .map(move |archivo| reescribir_archivo(&buscador, archivo, plantilla, argumentos));
+    Ok(reescrituras)
+}
+
+/// Devuelve las líneas de un archivo con sus coincidencias reemplazadas por una plantilla, junto
+/// con su codificación y su BOM.
+fn reescribir_archivo(
+    buscador: &Buscador,
+    archivo: &str,
+    plantilla: &str,
+    argumentos: &Argumentos,
+) -> ReescrituraArchivo {
+    let mut reescritura = ReescrituraArchivo {
+        archivo: archivo.to_string(),
+        lineas: Ok(Vec::new()),
+        codificacion: None,
+        bom: Vec::new(),
+    };
+    let contenido = match fs::read(archivo) {
+        Ok(contenido) => contenido,
+        Err(_e) => {
+            reescritura.lineas = Err(ErrorArchivo::Path(PathBuf::from(archivo)));
+            return reescritura;
+        }
+    };
+    let lectura = &argumentos.lectura;
+    if let Some((codificacion, largo_bom)) =
+        Codificacion::del_texto(lectura.codificacion, &contenido)
+    {
+        reescritura.codificacion = Some(codificacion);
+        reescritura.bom = contenido[..largo_bom].to_vec();
+    }
+    let lector = LectorDecodificado::new(contenido.as_slice(), lectura.codificacion);
+    reescritura.lineas = LectorLineas::new(BufReader::new(lector), archivo, lectura)
+        .map(|linea| {
+            let linea = linea?;
+            let (contenido_nuevo, _) =
+                buscador.reemplazar(&linea.contenido, argumentos.delimitacion, plantilla);
+            Ok(LineaReescrita {
+                linea,
+                contenido_nuevo,
+            })
+        })
+        .collect();
+    reescritura
+}
+
+/// Reescritura de los archivos que cambian al reemplazar sus coincidencias, según los
+/// resultados obtenidos de [reescribir_bytes]. Devuelve el código de salida que corresponde: el
+/// de selección si cambió algún archivo.
+///
+/// Cada archivo se escribe (o se muestran sus diferencias) antes de obtener la reescritura del
+/// siguiente.
+///
+/// Cada archivo se escribe primero en un archivo temporal en su mismo directorio, con los mismos
+/// permisos, que luego se renombra sobre el original, por lo que este nunca queda escrito a
+/// medias. Si se indicó un sufijo de respaldo, antes se copia el original a un archivo con su
+/// nombre seguido del sufijo.
+///
+/// Si se debe simular la reescritura, no se modifica ningún archivo: se imprimen las diferencias
+/// que resultarían de hacerlo, en formato unificado.
+pub fn aplicar_reescrituras(
+    resultado: Result<impl IntoIterator<Item = ReescrituraArchivo>>,
+    argumentos: &Argumentos,
+) -> i32 {
+    let reescrituras = match resultado {
+        Ok(reescrituras) => reescrituras,
+        Err(e) => {
+            eprintln!("grep: {}", e);
+            return SALIDA_ERROR;
+        }
+    };
+    let mut salida = io::stdout().lock();
+    let mut hubo_error = false;
+    let mut hubo_cambios = false;
+    for reescritura in reescrituras {
+        let lineas = match &reescritura.lineas {
+            Ok(lineas) => lineas,
+            Err(e) => {
+                eprintln!("grep: {}", e);
+                hubo_error = true;
+                continue;
+            }
+        };
+        if !lineas.iter().any(LineaReescrita::cambio) {
+            continue;
+        }
+        hubo_cambios = true;
+        if argumentos.simular {
+            if let Err(e) = escribir_diferencias(&mut salida, &reescritura.archivo, lineas) {
+                eprintln!("grep: {}", e);
+                return SALIDA_ERROR;
+            }
+        } else if let Err(e) = codificar_lineas(&reescritura, lineas).and_then(|contenido| {
+            reemplazar_archivo(
+                &reescritura.archivo,
+                &contenido,
+                argumentos.sufijo_respaldo.as_deref(),
+            )
+        }) {
+            eprintln!("grep: {}", e);
+            hubo_error = true;
+        }
+    }
+    if hubo_error {
+        SALIDA_ERROR
+    } else if hubo_cambios {
+        SALIDA_CON_SELECCION
+    } else {
+        SALIDA_SIN_SELECCION
+    }
+}
+
+/// Devuelve el contenido reescrito de un archivo: su BOM original y sus líneas reescritas (cada
+/// una con su terminador original), codificadas en la codificación con la que se leyó.
+///
+/// # Errores
+///
+/// Si algún reemplazo agrega un caracter que no puede representarse en la codificación del
+/// archivo, se devuelve un error de codificación.
+fn codificar_lineas(
+    reescritura: &ReescrituraArchivo,
+    lineas: &[LineaReescrita],
+) -> std::result::Result<Vec<u8>, ErrorArchivo> {
+    let mut contenido = reescritura.bom.clone();
+    for linea in lineas {
+        for texto in [&linea.contenido_nuevo, &linea.linea.terminador] {
+            match reescritura.codificacion {
+                Some(codificacion) => {
+                    codificacion.codificar(texto, &mut contenido).map_err(|c| {
+                        ErrorArchivo::Codificacion(PathBuf::from(&reescritura.archivo), c)
+                    })?
+                }
+                None => contenido.extend_from_slice(texto),
+            }
+        }
+    }
+    Ok(contenido)
+}
+
+/// Reemplazo atómico del contenido de un archivo, guardando antes una copia del original si se
+/// recibe un sufijo de respaldo.
+///
+/// # Errores
+///
+/// Si no se puede escribir el archivo temporal, la copia de respaldo o renombrar el temporal, se
+/// devuelve un error de escritura y el archivo original no se modifica.
+fn reemplazar_archivo(
+    archivo: &str,
+    contenido: &[u8],
+    sufijo_respaldo: Option<&str>,
+) -> std::result::Result<(), ErrorArchivo> {
+    let ruta = Path::new(archivo);
+    let temporal = ruta_temporal(ruta);
+    let resultado = fs::metadata(ruta)
+        .and_then(|metadatos| escribir_temporal(&temporal, contenido, metadatos.permissions()))
+        .and_then(|_| match sufijo_respaldo {
+            Some(sufijo) => fs::copy(ruta, format!("{}{}", archivo, sufijo)).map(|_| ()),
+            None => Ok(()),
+        })
+        .and_then(|_| fs::rename(&temporal, ruta));
+    if resultado.is_err() {
+        let _ = fs::remove_file(&temporal);
+    }
+    resultado.map_err(|_e| ErrorArchivo::Escritura(ruta.to_path_buf()))
+}
+
+/// Devuelve la ruta de un archivo temporal oculto en el mismo directorio que un archivo.
+fn ruta_temporal(ruta: &Path) -> PathBuf {
+    let nombre = ruta
+        .file_name()
+        .map(|nombre| nombre.to_string_lossy())
+        .unwrap_or_default();
+    ruta.with_file_name(format!(".{}.{}.tmp", nombre, process::id()))
+}
+
+/// Escritura de un contenido en un archivo temporal nuevo, con los permisos recibidos.
+fn escribir_temporal(temporal: &Path, contenido: &[u8], permisos: Permissions) -> io::Result<()> {
+    let mut archivo = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(temporal)?;
+    archivo.write_all(contenido)?;
+    archivo.set_permissions(permisos)?;
+    archivo.sync_all()
+}
+
+/// Escritura de las diferencias entre el contenido original y el reescrito de un archivo, en
+/// formato unificado.
+///
+/// Luego de un encabezado con el nombre del archivo, cada grupo de cambios cercanos se escribe
+/// con sus líneas originales (precedidas por '-'), las reescritas ('+') y hasta tres líneas sin
+/// cambios (' ') antes y después.
+fn escribir_diferencias(
+    salida: &mut impl Write,
+    archivo: &str,
+    lineas: &[LineaReescrita],
+) -> io::Result<()> {
+    writeln!(salida, "--- {}", archivo)?;
+    writeln!(salida, "+++ {}", archivo)?;
+    for (inicio, fin) in grupos_de_cambios(lineas) {
+        // Las líneas se reescriben de a una, por lo que los rangos original y nuevo coinciden.
+        let rango = match fin - inicio {
+            1 => format!("{}", inicio + 1),
+            largo => format!("{},{}", inicio + 1, largo),
+        };
+        writeln!(salida, "@@ -{} +{} @@", rango, rango)?;
+        let mut i = inicio;
+        while i < fin {
+            let cambiadas = lineas[i..fin].iter().take_while(|l| l.cambio()).count();
+            if cambiadas == 0 {
+                let linea = &lineas[i].linea;
+                escribir_linea_diferencias(salida, b' ', &linea.contenido, linea)?;
+                i += 1;
+                continue;
+            }
+            for reescrita in &lineas[i..i + cambiadas] {
+                let linea = &reescrita.linea;
+                escribir_linea_diferencias(salida, b'-', &linea.contenido, linea)?;
+            }
+            for reescrita in &lineas[i..i + cambiadas] {
+                let linea = &reescrita.linea;
+                escribir_linea_diferencias(salida, b'+', &reescrita.contenido_nuevo, linea)?;
+            }
+            i += cambiadas;
+        }
+    }
+    Ok(())
+}
+
+/// Devuelve las posiciones de inicio y fin de cada grupo de líneas a mostrar en las
+/// diferencias: las cambiadas y sus líneas de contexto. Los cambios separados por hasta el doble
+/// de líneas de contexto forman un mismo grupo.
+fn grupos_de_cambios(lineas: &[LineaReescrita]) -> Vec<(usize, usize)> {
+    let mut grupos: Vec<(usize, usize)> = Vec::new();
+    for (i, _) in lineas.iter().enumerate().filter(|(_, l)| l.cambio()) {
+        let inicio = i.saturating_sub(LINEAS_DE_CONTEXTO);
+        let fin = (i + 1 + LINEAS_DE_CONTEXTO).min(lineas.len());
+        match grupos.last_mut() {
+            Some((_, fin_grupo)) if inicio <= *fin_grupo => *fin_grupo = fin,
+            _ => grupos.push((inicio, fin)),
+        }
+    }
+    grupos
+}
+
+/// Escritura de una línea de las diferencias: un prefijo, el contenido y el terminador de la
+/// línea. Si la línea no tenía terminador, se indica que el archivo no termina en '\n'.
+fn escribir_linea_diferencias(
+    salida: &mut impl Write,
+    prefijo: u8,
+    contenido: &[u8],
+    linea: &Linea,
+) -> io::Result<()> {
+    salida.write_all(&[prefijo])?;
+    salida.write_all(contenido)?;
+    if linea.terminador.is_empty() {
+        salida.write_all(b"\n\\ No newline at end of file\n")
+    } else {
+        salida.write_all(&linea.terminador)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lineas(originales: &[&str], nuevas: &[&str]) -> Vec<LineaReescrita> {
+        originales
+            .iter()
+            .zip(nuevas)
+            .enumerate()
+            .map(|(i, (original, nueva))| LineaReescrita {
+                linea: Linea {
+                    contenido: original.as_bytes().to_vec(),
+                    terminador: if i + 1 < originales.len() {
+                        b"\n".to_vec()
+                    } else {
+                        Vec::new()
+                    },
+                },
+                contenido_nuevo: nueva.as_bytes().to_vec(),
+            })
+            .collect()
+    }
+
+    fn diferencias(lineas: &[LineaReescrita]) -> String {
+        let mut salida = Vec::new();
+        escribir_diferencias(&mut salida, "a.txt", lineas).unwrap();
+        String::from_utf8(salida).unwrap()
+    }
+
+    #[test]
+    fn diferencias_en_formato_unificado() {
+        let originales = [
+            "1", "2", "x", "x", "5", "6", "7", "8", "9", "10", "11", "12", "x",
+        ];
+        let nuevas = [
+            "1", "2", "y", "y", "5", "6", "7", "8", "9", "10", "11", "12", "y",
+        ];
+
+        assert_eq!(
+            diferencias(&lineas(&originales, &nuevas)),
+            "--- a.txt\n+++ a.txt\n\
+             @@ -1,7 +1,7 @@\n 1\n 2\n-x\n-x\n+y\n+y\n 5\n 6\n 7\n\
+             @@ -10,4 +10,4 @@\n 10\n 11\n 12\n\
+             -x\n\\ No newline at end of file\n+y\n\\ No newline at end of file\n"
+        );
+    }
+
+    #[test]
+    fn cambios_cercanos_forman_un_grupo() {
+        let originales = ["x", "2", "3", "4", "5", "6", "7", "x"];
+        let nuevas = ["y", "2", "3", "4", "5", "6", "7", "y"];
+
+        assert_eq!(
+            grupos_de_cambios(&lineas(&originales, &nuevas)),
+            vec![(0, 8)]
+        );
+        assert_eq!(grupos_de_cambios(&lineas(&["a"], &["a"])), vec![]);
+    }
+}
@@ -1,16 +1,93 @@
-use std::error;
-
 use crate::{
-    error_regex::ErrorRegex, expresion::Expresion,
-    expresion_bracket::nuevo_token_expresion_bracket, iterador_vec::IteradorVecHaciaAtras,
-    rango_repeticion::obtener_repeticion, regex_token::RegexToken,
-    repeticion_regex_token::RepeticionRegexToken, valor_regex_token::ValorRegexToken,
+    asercion::Asercion,
+    automata::Automata,
+    backtracking::Backtracking,
+    capturas::Capturas,
+    clase_caracter::ClaseCaracter,
+    decodificacion_utf8::decodificar_primer_caracter,
+    delimitacion::Delimitacion,
+    engine::Engine,
+    error::{Error, TipoError},
+    error_regex::ErrorRegex,
+    expresion::Expresion,
+    expresion_bracket::nuevo_token_expresion_bracket,
+    iterador_vec::IteradorVecHaciaAtras,
+    iteradores_regex::{Coincidencias, Division},
+    propiedad_unicode::PropiedadUnicode,
+    rango_repeticion::obtener_repeticion,
+    reemplazo::Reemplazo,
+    regex_builder::OpcionesRegex,
+    regex_token::RegexToken,
+    repeticion_regex_token::RepeticionRegexToken,
+    syntax::Syntax,
+    valor_regex_token::ValorRegexToken,
 };
 
 /// Representación de una expresión regular.
+///
+/// Las expresiones alternativas que forman la expresión regular se evalúan con un
+/// [autómata][Automata], salvo las que tienen referencias a grupos, repeticiones perezosas,
+/// anticipaciones o retrospecciones, que se evalúan aparte con un motor de backtracking (o todas,
+/// si así se indicó en sus opciones). En sintaxis básica y extendida, el backtracking explora
+/// todas las alternativas para encontrar la coincidencia más larga, como el autómata; en sintaxis
+/// compatible con Perl (o si se forzó el backtracking), se queda con la primera que encuentra.
 #[derive(Debug)]
 pub struct Regex {
     expresiones: Vec<Expresion>,
+    automata: Automata,
+    con_backtracking: Vec<Expresion>,
+    backtracking_forzado: bool,
+    coincidencia_mas_larga: bool,
+    nombres_grupos: Vec<(String, usize)>,
+}
+
+/// Resultado del análisis de un patrón: sus expresiones alternativas (sin compilar), los nombres de
+/// sus grupos, si se fuerza el uso de backtracking y si este busca la coincidencia más larga.
+#[derive(Debug)]
+pub(crate) struct PatronAnalizado {
+    expresiones: Vec<Expresion>,
+    nombres_grupos: Vec<(String, usize)>,
+    backtracking_forzado: bool,
+    coincidencia_mas_larga: bool,
+}
+
+/// Estado del análisis de un patrón: el patrón, los bytes inválidos que contenía (por índice de
+/// caracter), su sintaxis, si es estricto con las repeticiones que POSIX no define, los grupos
+/// abiertos y cerrados hasta el momento (las referencias solo pueden ser a grupos cerrados) y los
+/// nombres de los grupos con nombre.
+struct EstadoAnalisis<'p> {
+    patron: &'p str,
+    bytes_invalidos: &'p [Option<u8>],
+    sintaxis: Syntax,
+    estricta: bool,
+    cantidad_grupos: usize,
+    grupos_cerrados: Vec<usize>,
+    nombres_grupos: Vec<(String, usize)>,
+}
+
+impl EstadoAnalisis<'_> {
+    /// Creación de un error en la parte del patrón entre dos índices de caracteres (el de inicio
+    /// y el siguiente al último), que se convierten en posiciones en bytes.
+    fn error(&self, tipo: impl Into<TipoError>, inicio: usize, fin: usize) -> Error {
+        let posicion = |indice| {
+            self.patron
+                .char_indices()
+                .nth(indice)
+                .map_or(self.patron.len(), |(posicion, _)| posicion)
+        };
+        Error::new(tipo.into(), self.patron, posicion(inicio), posicion(fin))
+    }
+
+    /// Creación de un error en la parte del patrón desde un índice de caracter hasta el último
+    /// caracter recorrido por un iterador (incluido).
+    fn error_hasta(
+        &self,
+        tipo: impl Into<TipoError>,
+        inicio: usize,
+        iter_chars: &IteradorVecHaciaAtras<char>,
+    ) -> Error {
+        self.error(tipo, inicio, iter_chars.posicion() + 1)
+    }
 }
 
 impl Regex {
@@ -18,11 +95,18 @@ impl Regex {
     ///
     /// # Errores
     ///
-    /// * Si se recibe un \\ al final del patrón.
+    /// * Si se recibe un \\ al final del patrón, o seguido por una letra o un dígito sin
+    ///   significado (ej.: \\q).
+    ///
+    /// * Si se recibe un metacaracter de repetición al inicio de una expresión, luego de otro del
+    ///   mismo tipo o de un anclaje, solo si se indica que se rechacen (ver
+    ///   [RegexBuilder::strict](crate::regex_builder::RegexBuilder::strict)).
     ///
-    /// * Si se recibe un metacaracter de repetición al inicio del patrón o luego de otro del mismo tipo.
+    /// * Si se recibe un contenido inválido dentro de un rango de repetición (ej.: {2,1}, {1,2,_}),
+    ///   o un número de repeticiones mayor a 32767 (ej.: {40000}).
     ///
-    /// * Si se recibe un contenido inválido dentro de un rango de repetición (ej.: {2,1}, {1,2,_}).
+    /// * Si el autómata en el que se compila la expresión supera el [límite de
+    ///   tamaño](crate::regex_builder::LIMITE_TAMANO_POR_DEFECTO) (ej.: a{1000}{1000}{1000}).
     ///
     /// * Si se abre una bracket expresión y no cierra (ej.: \[ab, \[\[:space:]).
     ///
@@ -30,47 +114,496 @@ impl Regex {
     ///
     /// * Si el nombre de la clase de caracter es inválido (ej.: \[\[:spac:]]).
     ///
+    /// * Si un grupo no cierra o se cierra uno que no se abrió (ej.: \\(ab, ab\\) en sintaxis básica).
+    ///
+    /// * Si se recibe una propiedad de Unicode desconocida (ej.: \\p{Klingon}).
+    ///
+    /// * Si se referencia un grupo inexistente o que todavía no se cerró (ej.: (a\\1), \\1(a)).
     ///
-    pub fn new(patron: &str) -> Result<Self, Box<dyn error::Error>> {
+    /// * Si una retrospección no tiene un largo máximo acotado (ej.: (?<=a+), en sintaxis
+    ///   compatible con Perl).
+    ///
+    /// * Si el nombre de un grupo es vacío, no se cierra o tiene caracteres que no son letras,
+    ///   dígitos o '_' (ej.: `(?<a-b>x)`, en sintaxis compatible con Perl).
+    ///
+    /// El [error][Error] indica su tipo y la parte del patrón en la que se encontró.
+    ///
+    /// La expresión se interpreta con sintaxis extendida; para usar otra, ver [RegexBuilder](crate::regex_builder::RegexBuilder).
+    pub fn new(patron: &str) -> Result<Self, Error> {
+        Self::con_opciones(patron, &OpcionesRegex::default())
+    }
+
+    /// Creación de una nueva expresión regular recibiendo un patrón y las opciones con las que
+    /// se interpreta.
+    pub(crate) fn con_opciones(patron: &str, opciones: &OpcionesRegex) -> Result<Self, Error> {
+        Self::con_opciones_bytes(patron.as_bytes(), opciones)
+    }
+
+    /// Creación de una nueva expresión regular recibiendo un patrón como bytes (que no necesitan
+    /// ser utf8 válido) y las opciones con las que se interpreta.
+    ///
+    /// Cada byte que no forma parte de un caracter utf8 válido es un literal que matchea con ese
+    /// mismo byte, salvo dentro de una bracket expresión, donde se toma como el caracter de
+    /// reemplazo (U+FFFD). En los errores, estos bytes se muestran también como dicho caracter.
+    pub(crate) fn con_opciones_bytes(
+        patron: &[u8],
+        opciones: &OpcionesRegex,
+    ) -> Result<Self, Error> {
+        Ok(Self::desde_patrones([Self::analizar(patron, opciones)?]))
+    }
+
+    /// Análisis de un patrón recibido como bytes con las opciones con las que se interpreta (ver
+    /// [con_opciones_bytes](Self::con_opciones_bytes)), sin compilar su autómata. Permite crear
+    /// una única expresión regular a partir de varios patrones (ver
+    /// [desde_patrones](Self::desde_patrones)) compilando un solo autómata.
+    pub(crate) fn analizar(
+        patron: &[u8],
+        opciones: &OpcionesRegex,
+    ) -> Result<PatronAnalizado, Error> {
+        let mut chars = Vec::new();
+        let mut bytes_invalidos = Vec::new();
+        let mut resto = patron;
+        while let Some(unidad) = decodificar_primer_caracter(resto) {
+            let (caracter, byte_invalido) = match unidad {
+                Ok(caracter) => (caracter, None),
+                Err(byte) => (char::REPLACEMENT_CHARACTER, Some(byte)),
+            };
+            resto = &resto[byte_invalido.map_or(caracter.len_utf8(), |_| 1)..];
+            chars.push(caracter);
+            bytes_invalidos.push(byte_invalido);
+        }
+        let patron: String = chars.iter().collect();
+        let mut iter_chars = IteradorVecHaciaAtras::new(&chars);
+        let mut estado = EstadoAnalisis {
+            patron: &patron,
+            bytes_invalidos: &bytes_invalidos,
+            sintaxis: opciones.sintaxis,
+            estricta: opciones.estricta,
+            cantidad_grupos: 0,
+            grupos_cerrados: Vec::new(),
+            nombres_grupos: Vec::new(),
+        };
+        let expresiones: Vec<Expresion> =
+            Self::nuevas_expresiones(&mut iter_chars, &mut estado, None)?
+                .into_iter()
+                .map(|mut expresion| {
+                    if opciones.unicode {
+                        expresion = expresion.unicode();
+                    }
+                    if opciones.ignorar_mayusculas {
+                        expresion = expresion.ignorar_mayusculas();
+                    }
+                    if opciones.multilinea {
+                        expresion = expresion.multilinea(opciones.terminador_linea);
+                    }
+                    if !opciones.comodin_nueva_linea {
+                        expresion = expresion.comodin_sin_terminador(opciones.terminador_linea);
+                    }
+                    expresion
+                })
+                .collect();
+        let nombres_grupos = std::mem::take(&mut estado.nombres_grupos);
+        let error_del_patron = |tipo| estado.error(tipo, 0, chars.len());
+        let backtracking_forzado = match opciones.motor {
+            Engine::Automata if expresiones.iter().any(Expresion::requiere_backtracking) => {
+                return Err(error_del_patron(ErrorRegex::MotorIncompatible));
+            }
+            motor => motor == Engine::Backtracking,
+        };
+        // El tamaño del autómata se calcula antes de compilarlo, para no expandir repeticiones
+        // que superen el límite.
+        if let Some(limite) = opciones.limite_tamano {
+            let sin_backtracking = expresiones
+                .iter()
+                .filter(|expresion| !backtracking_forzado && !expresion.requiere_backtracking());
+            if Automata::cantidad_instrucciones_de(sin_backtracking) > limite {
+                return Err(error_del_patron(ErrorRegex::LimiteDeTamanoExcedido(limite)));
+            }
+        }
+        Ok(PatronAnalizado {
+            expresiones,
+            nombres_grupos,
+            backtracking_forzado,
+            coincidencia_mas_larga: !backtracking_forzado && opciones.sintaxis != Syntax::Perl,
+        })
+    }
+
+    /// Crea las expresiones alternativas que forman un patrón o un grupo, según los caracteres
+    /// siguientes (guardados en un iterador). Si se crean las de un grupo, se recibe la parte
+    /// del patrón que lo abre (sus índices de caracteres de inicio y fin) y se termina al
+    /// encontrar el cierre de este.
+    fn nuevas_expresiones(
+        iter_chars: &mut IteradorVecHaciaAtras<char>,
+        estado: &mut EstadoAnalisis,
+        apertura_grupo: Option<(usize, usize)>,
+    ) -> Result<Vec<Expresion>, Error> {
         let mut tokens: Vec<RegexToken> = Vec::new();
         let mut expresiones: Vec<Expresion> = Vec::new();
-        let chars = patron.chars().collect();
-        let mut iter_chars = IteradorVecHaciaAtras::new(&chars);
         while let Some(c) = iter_chars.next() {
-            if Self::es_metacaracter_de_repeticion(*c) {
-                let modified =
-                    Self::modificar_repeticion_token(tokens.last_mut(), *c, &mut iter_chars)?;
-                if modified {
-                    continue;
+            let inicio = iter_chars.posicion();
+            match Self::metacaracter_estructural(*c, iter_chars, estado.sintaxis) {
+                Some(r @ ('*' | '?' | '+' | '{')) => {
+                    // En expresiones regulares básicas, el anclaje inicial cuenta como el inicio
+                    // de la expresión, donde '*' es literal. Un metacaracter de repetición al
+                    // inicio solo es literal si no se lo rechaza; en las demás sintaxis, luego de
+                    // un anclaje lo repite.
+                    let al_inicio = tokens.last().is_none_or(|t| {
+                        (estado.sintaxis == Syntax::Bre)
+                            & (t.valor == ValorRegexToken::AnclajeInicio)
+                    });
+                    let literal = al_inicio
+                        && ((estado.sintaxis == Syntax::Bre) & (r == '*') || !estado.estricta);
+                    if literal
+                        || !Self::modificar_repeticion_token(&mut tokens, r, iter_chars, estado)
+                            .map_err(|tipo| estado.error_hasta(tipo, inicio, iter_chars))?
+                    {
+                        tokens.push(Self::nuevo_token_literal(r));
+                    }
+                }
+                Some('|') => {
+                    Self::guardar_expresion(&mut expresiones, tokens);
+                    tokens = Vec::new();
+                }
+                Some('(') => {
+                    let apertura = (inicio, iter_chars.posicion() + 1);
+                    tokens.push(Self::nuevo_token_grupo(iter_chars, estado, apertura)?)
+                }
+                Some(')') if apertura_grupo.is_some() => {
+                    Self::guardar_expresion(&mut expresiones, tokens);
+                    return Ok(expresiones);
+                }
+                Some(')') if estado.sintaxis != Syntax::Bre => {
+                    tokens.push(Self::nuevo_token_literal(')'))
                 }
+                Some(')') => {
+                    return Err(estado.error_hasta(
+                        ErrorRegex::ParentesisDesbalanceado,
+                        inicio,
+                        iter_chars,
+                    ))
+                }
+                _ => {
+                    let token = Self::nuevo_token(*c, iter_chars, estado, &tokens)
+                        .map_err(|tipo| estado.error_hasta(tipo, inicio, iter_chars))?;
+                    if let Some(t) = token {
+                        tokens.push(t);
+                    }
+                }
+            }
+        }
+        if let Some((inicio, fin)) = apertura_grupo {
+            return Err(estado.error(ErrorRegex::ParentesisDesbalanceado, inicio, fin));
+        }
+        Self::guardar_expresion(&mut expresiones, tokens);
+        Ok(expresiones)
+    }
+
+    /// Crea el token de un grupo, luego de recibir el '(' que lo abre (cuyos índices de caracteres
+    /// de inicio y fin se reciben), con las expresiones alternativas que contiene hasta su cierre.
+    ///
+    /// En sintaxis compatible con Perl, si el grupo comienza con ?=, ?!, ?<= o ?<!, se crea una
+    /// anticipación o una retrospección (negada con !), que no se numera como grupo y no admite
+    /// repetición. Una retrospección debe tener un largo máximo acotado. Si comienza con ?>, se
+    /// crea un grupo atómico, y si comienza con ?:, un grupo que no captura; ninguno de los dos se
+    /// numera. Si comienza con ?<nombre> o ?P<nombre>, el grupo se numera como los demás y además
+    /// se guarda su nombre. Cualquier otro grupo que comience con ? (ej.: (?i), (?#...)) es un
+    /// error.
+    fn nuevo_token_grupo(
+        iter_chars: &mut IteradorVecHaciaAtras<char>,
+        estado: &mut EstadoAnalisis,
+        apertura: (usize, usize),
+    ) -> Result<RegexToken, Error> {
+        if estado.sintaxis == Syntax::Perl {
+            if let Some(asercion) = Self::obtener_anticipacion_o_retrospeccion(iter_chars) {
+                let expresiones = Self::nuevas_expresiones(iter_chars, estado, Some(apertura))?;
+                let valor = asercion(expresiones);
+                if matches!(
+                    valor,
+                    ValorRegexToken::Retrospeccion(_) | ValorRegexToken::RetrospeccionNegada(_)
+                ) && valor
+                    .expresiones()
+                    .iter()
+                    .any(|e| e.largo_maximo().is_none())
+                {
+                    return Err(estado.error_hasta(
+                        ErrorRegex::RetrospeccionNoAcotada,
+                        apertura.0,
+                        iter_chars,
+                    ));
+                }
+                return Ok(RegexToken::new(
+                    valor,
+                    RepeticionRegexToken::new(Some(0), Some(0)),
+                ));
             }
-            if *c == '|' {
-                Self::guardar_expresion(&mut expresiones, tokens);
-                tokens = Vec::new();
-                continue;
+            if Self::obtener_prefijo_grupo(iter_chars, '>') {
+                let expresiones = Self::nuevas_expresiones(iter_chars, estado, Some(apertura))?;
+                return Ok(RegexToken::new(
+                    ValorRegexToken::GrupoAtomico(expresiones),
+                    RepeticionRegexToken::new(Some(1), Some(1)),
+                ));
             }
-            let token = Self::nuevo_token(*c, &mut iter_chars)?;
-            if let Some(t) = token {
-                tokens.push(t);
+            if Self::obtener_prefijo_grupo(iter_chars, ':') {
+                let expresiones = Self::nuevas_expresiones(iter_chars, estado, Some(apertura))?;
+                return Ok(RegexToken::new(
+                    ValorRegexToken::Grupo(None, expresiones),
+                    RepeticionRegexToken::new(Some(1), Some(1)),
+                ));
+            }
+        }
+        estado.cantidad_grupos += 1;
+        let numero = estado.cantidad_grupos;
+        if estado.sintaxis == Syntax::Perl {
+            if let Some(nombre) = Self::obtener_nombre_grupo(iter_chars)
+                .map_err(|tipo| estado.error_hasta(tipo, apertura.0, iter_chars))?
+            {
+                estado.nombres_grupos.push((nombre, numero));
+            } else if iter_chars.next() == Some(&'?') {
+                return Err(estado.error_hasta(
+                    ErrorRegex::GrupoDesconocido,
+                    apertura.0,
+                    iter_chars,
+                ));
+            } else {
+                iter_chars.prev();
+            }
+        }
+        let expresiones_grupo = Self::nuevas_expresiones(iter_chars, estado, Some(apertura))?;
+        estado.grupos_cerrados.push(numero);
+        Ok(RegexToken::new(
+            ValorRegexToken::Grupo(Some(numero), expresiones_grupo),
+            RepeticionRegexToken::new(Some(1), Some(1)),
+        ))
+    }
+
+    /// Si los caracteres siguientes son ?=, ?!, ?<= o ?<!, los consume y devuelve la función que
+    /// crea la anticipación o retrospección correspondiente. Si no, no modifica el iterador.
+    fn obtener_anticipacion_o_retrospeccion(
+        iter_chars: &mut IteradorVecHaciaAtras<char>,
+    ) -> Option<fn(Vec<Expresion>) -> ValorRegexToken> {
+        let mut consumidos = 0;
+        let mut siguiente = || {
+            consumidos += 1;
+            iter_chars.next().copied()
+        };
+        let asercion: Option<fn(Vec<Expresion>) -> ValorRegexToken> =
+            match (siguiente(), siguiente()) {
+                (Some('?'), Some('=')) => Some(ValorRegexToken::Anticipacion),
+                (Some('?'), Some('!')) => Some(ValorRegexToken::AnticipacionNegada),
+                (Some('?'), Some('<')) => match siguiente() {
+                    Some('=') => Some(ValorRegexToken::Retrospeccion),
+                    Some('!') => Some(ValorRegexToken::RetrospeccionNegada),
+                    _ => None,
+                },
+                _ => None,
+            };
+        if asercion.is_none() {
+            for _ in 0..consumidos {
+                iter_chars.prev();
+            }
+        }
+        asercion
+    }
+
+    /// Si los caracteres siguientes son '?' y el caracter recibido (ej.: ?> en un grupo atómico),
+    /// los consume y devuelve true. Si no, no modifica el iterador.
+    fn obtener_prefijo_grupo(iter_chars: &mut IteradorVecHaciaAtras<char>, caracter: char) -> bool {
+        if iter_chars.next() == Some(&'?') {
+            if iter_chars.next() == Some(&caracter) {
+                return true;
+            }
+            iter_chars.prev();
+        }
+        iter_chars.prev();
+        false
+    }
+
+    /// Si los caracteres siguientes son ?<nombre> o ?P<nombre>, los consume y devuelve el nombre.
+    /// Si no, no modifica el iterador.
+    ///
+    /// El nombre debe comenzar con una letra o '_', seguida por letras, dígitos o '_'.
+    fn obtener_nombre_grupo(
+        iter_chars: &mut IteradorVecHaciaAtras<char>,
+    ) -> Result<Option<String>, ErrorRegex> {
+        let mut consumidos = 0;
+        let mut siguiente = || {
+            consumidos += 1;
+            iter_chars.next().copied()
+        };
+        let tiene_nombre = match (siguiente(), siguiente()) {
+            (Some('?'), Some('<')) => true,
+            (Some('?'), Some('P')) => siguiente() == Some('<'),
+            _ => false,
+        };
+        if !tiene_nombre {
+            for _ in 0..consumidos {
+                iter_chars.prev();
+            }
+            return Ok(None);
+        }
+        let mut nombre = String::new();
+        loop {
+            match iter_chars.next() {
+                Some('>') if !nombre.is_empty() => return Ok(Some(nombre)),
+                Some(c)
+                    if (c.is_alphanumeric() || *c == '_')
+                        && !(nombre.is_empty() && c.is_numeric()) =>
+                {
+                    nombre.push(*c)
+                }
+                _ => return Err(ErrorRegex::NombreDeGrupoInvalido),
             }
         }
-        Self::guardar_expresion(&mut expresiones, tokens);
-        Ok(Regex { expresiones })
     }
 
-    /// Crea un nuevo token según un caracter y los que le siguen (guardados en un iterador).
+    /// Devuelve el metacaracter de repetición, alternancia o agrupación que representa un
+    /// caracter según la sintaxis, o None si no representa ninguno.
+    ///
+    /// En expresiones regulares básicas, estos metacaracteres (salvo '*') se escriben precedidos
+    /// por una barra invertida: en ese caso, se avanza el iterador para consumirlos. En
+    /// expresiones regulares extendidas, un ')' sin grupo abierto es literal.
+    fn metacaracter_estructural(
+        c: char,
+        iter_chars: &mut IteradorVecHaciaAtras<char>,
+        sintaxis: Syntax,
+    ) -> Option<char> {
+        match (sintaxis, c) {
+            (_, '*') => Some(c),
+            (Syntax::Ere | Syntax::Perl, '?' | '+' | '{' | '|' | '(' | ')') => Some(c),
+            (Syntax::Bre, '\\') => match iter_chars.next() {
+                Some(s @ ('?' | '+' | '{' | '|' | '(' | ')')) => Some(*s),
+                _ => {
+                    iter_chars.prev();
+                    None
+                }
+            },
+            _ => None,
+        }
+    }
+
+    /// Creación de una expresión regular a partir de las expresiones que la forman, compilando en
+    /// un autómata para las búsquedas las que no requieren backtracking (ninguna, si se fuerza el
+    /// uso de backtracking).
+    pub(crate) fn desde_expresiones(
+        expresiones: Vec<Expresion>,
+        backtracking_forzado: bool,
+    ) -> Self {
+        let (con_backtracking, sin_backtracking): (Vec<&Expresion>, Vec<&Expresion>) = expresiones
+            .iter()
+            .partition(|expresion| backtracking_forzado || expresion.requiere_backtracking());
+        let automata = Automata::new(
+            sin_backtracking.into_iter().map(|expresion| (0, expresion)),
+            1,
+        );
+        let con_backtracking = con_backtracking.into_iter().cloned().collect();
+        Regex {
+            expresiones,
+            automata,
+            con_backtracking,
+            backtracking_forzado,
+            coincidencia_mas_larga: true,
+            nombres_grupos: Vec::new(),
+        }
+    }
+
+    /// Creación de una única expresión regular a partir de varias, que matchea con un valor
+    /// si alguna de ellas matchea con él.
+    ///
+    /// Las expresiones de cada una se evalúan como alternativas de una misma expresión regular.
+    /// Solo las que requieren backtracking se evalúan con este (todas, si alguna de las
+    /// expresiones regulares lo fuerza), por lo que un patrón con referencias a grupos no impide
+    /// que los demás se evalúen con el autómata. Se conservan los nombres de los grupos de todas
+    /// ellas. El backtracking busca la coincidencia más larga solo si todas ellas lo hacen.
+    pub fn alternar(regexes: impl IntoIterator<Item = Regex>) -> Self {
+        Self::desde_patrones(regexes.into_iter().map(|regex| PatronAnalizado {
+            expresiones: regex.expresiones,
+            nombres_grupos: regex.nombres_grupos,
+            backtracking_forzado: regex.backtracking_forzado,
+            coincidencia_mas_larga: regex.coincidencia_mas_larga,
+        }))
+    }
+
+    /// Creación de una única expresión regular a partir de patrones ya analizados, que matchea
+    /// con un valor si alguno de ellos matchea con él (como [alternar](Self::alternar)). Las
+    /// expresiones de todos ellos se compilan en un único autómata.
+    pub(crate) fn desde_patrones(patrones: impl IntoIterator<Item = PatronAnalizado>) -> Self {
+        let mut backtracking_forzado = false;
+        let mut coincidencia_mas_larga = true;
+        let mut nombres_grupos = Vec::new();
+        let expresiones = patrones
+            .into_iter()
+            .flat_map(|patron| {
+                backtracking_forzado |= patron.backtracking_forzado;
+                coincidencia_mas_larga &= patron.coincidencia_mas_larga;
+                nombres_grupos.extend(patron.nombres_grupos);
+                patron.expresiones
+            })
+            .collect();
+        let mut regex = Self::desde_expresiones(expresiones, backtracking_forzado);
+        regex.nombres_grupos = nombres_grupos;
+        regex.coincidencia_mas_larga = coincidencia_mas_larga;
+        regex
+    }
+
+    /// Devuelve una expresión regular equivalente que no distingue mayúsculas de minúsculas.
+    pub fn ignorar_mayusculas(self) -> Self {
+        let expresiones = self
+            .expresiones
+            .into_iter()
+            .map(Expresion::ignorar_mayusculas)
+            .collect();
+        let mut regex = Self::desde_expresiones(expresiones, self.backtracking_forzado);
+        regex.nombres_grupos = self.nombres_grupos;
+        regex.coincidencia_mas_larga = self.coincidencia_mas_larga;
+        regex
+    }
+
+    /// Devuelve true si la expresión regular tiene referencias a grupos, por lo que no puede
+    /// evaluarse con un autómata.
+    pub(crate) fn tiene_referencias(&self) -> bool {
+        self.expresiones.iter().any(Expresion::tiene_referencias)
+    }
+
+    /// Devuelve las expresiones cuya alternancia forma la expresión regular.
+    pub(crate) fn expresiones(&self) -> &[Expresion] {
+        &self.expresiones
+    }
+
+    /// Crea un nuevo token según un caracter, los que le siguen (guardados en un iterador) y los
+    /// tokens ya creados de la expresión.
     ///
     /// Devuelve errores que luego devolverá new().
     fn nuevo_token(
         c: char,
         iter_chars: &mut IteradorVecHaciaAtras<char>,
-    ) -> Result<Option<RegexToken>, Box<dyn error::Error>> {
+        estado: &EstadoAnalisis,
+        tokens: &[RegexToken],
+    ) -> Result<Option<RegexToken>, TipoError> {
+        let byte_invalido = |iter_chars: &IteradorVecHaciaAtras<char>| {
+            estado.bytes_invalidos[iter_chars.posicion()].map(|byte| {
+                RegexToken::new(
+                    ValorRegexToken::Byte(byte),
+                    RepeticionRegexToken::new(Some(1), Some(1)),
+                )
+            })
+        };
+        if let Some(token) = byte_invalido(iter_chars) {
+            return Ok(Some(token));
+        }
         let token = match c {
             '.' => Some(Self::nuevo_token_comodin()),
-            '\\' => Self::siguiente_como_literal(iter_chars)?,
-
-            '^' => Self::nuevo_token_de_anclaje_inicio(iter_chars)?,
-            '$' => Some(Self::nuevo_token_de_anclaje_final()),
+            '\\' => match iter_chars.next() {
+                Some(_) if byte_invalido(iter_chars).is_some() => byte_invalido(iter_chars),
+                Some(d @ '1'..='9') => Some(Self::nuevo_token_referencia(*d, estado)?),
+                Some(p @ ('p' | 'P')) => Some(Self::nuevo_token_propiedad(*p == 'P', iter_chars)?),
+                Some(e) => Some(Self::nuevo_token_escapado(*e)?),
+                None => return Err(ErrorRegex::BarraInvertidaAlFinal.into()),
+            },
+            '^' => Self::nuevo_token_de_anclaje_inicio(tokens, estado.sintaxis),
+            '$' => Some(Self::nuevo_token_de_anclaje_final(
+                iter_chars,
+                estado.sintaxis,
+            )),
             '[' => {
                 let t = nuevo_token_expresion_bracket(iter_chars)?;
                 Some(t)
@@ -94,55 +627,165 @@ impl Regex {
         )
     }
 
-    /// Devuelve un token de anclaje de inicio, dependiendo del caracter que le antecede en el patrón
-    /// recibido en new().
+    /// Devuelve un token de anclaje de inicio, dependiendo de los tokens que le anteceden en la
+    /// expresión (o en el grupo o la alternativa en la que se encuentra).
     ///
-    /// * Si no le antecede nada o un |, devuelve un anclaje válido (sin repetición para que no falle el matcheo).
-    /// * Si le antecede un ^, devuelve None (se ignora).
-    /// * Si le antecede otra cosa, se devuelve un error de expresión inválida.
+    /// * Si no le antecede ninguno, devuelve un anclaje válido (sin repetición para que no falle el matcheo).
+    /// * Si le antecede alguno (incluso otro anclaje de inicio), en expresiones regulares básicas
+    ///   es un literal (ej.: ^^x matchea con ^x al inicio).
+    /// * Si le antecede únicamente otro anclaje de inicio, en las demás devuelve None (se ignora).
+    /// * Si le antecede otra cosa, en las demás es un anclaje, que no consume caracteres y solo se
+    ///   cumple al inicio del valor evaluado (ej.: a^b nunca matchea, pero (^|,)b matchea con b al
+    ///   inicio o luego de una coma).
     fn nuevo_token_de_anclaje_inicio(
-        iter_chars: &mut IteradorVecHaciaAtras<char>,
-    ) -> Result<Option<RegexToken>, ErrorRegex> {
-        if let Some(c) = iter_chars.prev() {
-            iter_chars.next();
-            match c {
-                '^' => Ok(None),
-                '|' => Ok(Some(RegexToken::new(
-                    ValorRegexToken::AnclajeInicio,
-                    RepeticionRegexToken::new(Some(0), Some(0)),
-                ))),
-                _ => Err(ErrorRegex::ExpresionInvalidaAnclajeInicio),
-            }
-        } else {
-            Ok(Some(RegexToken::new(
+        tokens: &[RegexToken],
+        sintaxis: Syntax,
+    ) -> Option<RegexToken> {
+        match tokens {
+            [_, ..] if sintaxis == Syntax::Bre => Some(Self::nuevo_token_literal('^')),
+            [t] if t.valor == ValorRegexToken::AnclajeInicio => None,
+            _ => Some(RegexToken::new(
                 ValorRegexToken::AnclajeInicio,
                 RepeticionRegexToken::new(Some(0), Some(0)),
-            )))
+            )),
         }
     }
 
-    fn nuevo_token_de_anclaje_final() -> RegexToken {
-        RegexToken::new(
-            ValorRegexToken::AnclajeFinal,
-            RepeticionRegexToken::new(Some(0), Some(0)),
-        )
+    /// Devuelve un token de anclaje final, o un literal '$' si, en una expresión regular básica,
+    /// no está al final del patrón, de un grupo (\)) o de una alternativa (\|).
+    fn nuevo_token_de_anclaje_final(
+        iter_chars: &mut IteradorVecHaciaAtras<char>,
+        sintaxis: Syntax,
+    ) -> RegexToken {
+        let es_anclaje = match sintaxis {
+            Syntax::Ere | Syntax::Perl => true,
+            Syntax::Bre => match iter_chars.next() {
+                None => true,
+                Some('\\') => {
+                    let siguiente = iter_chars.next().copied();
+                    if siguiente.is_some() {
+                        iter_chars.prev();
+                    }
+                    iter_chars.prev();
+                    matches!(siguiente, Some(')' | '|'))
+                }
+                Some(_) => {
+                    iter_chars.prev();
+                    false
+                }
+            },
+        };
+        if es_anclaje {
+            RegexToken::new(
+                ValorRegexToken::AnclajeFinal,
+                RepeticionRegexToken::new(Some(0), Some(0)),
+            )
+        } else {
+            Self::nuevo_token_literal('$')
+        }
     }
 
-    /// Dado un iterador con los chars restantes de un patrón, devuelve
-    /// el siguiente caracter como literal, si este existe.
+    /// Devuelve un token de referencia al grupo indicado por un dígito.
     ///
     /// # Errores
     ///
-    /// Si no hay caracter siguiente, significa que se encontró una barra invertida
-    /// al final del patrón y se devuelve el error corresponidiente.
-    fn siguiente_como_literal(
+    /// Si el grupo no existe o todavía no se cerró, se devuelve el error correspondiente.
+    fn nuevo_token_referencia(
+        digito: char,
+        estado: &EstadoAnalisis,
+    ) -> Result<RegexToken, ErrorRegex> {
+        let numero = digito.to_digit(10).unwrap_or(0) as usize;
+        if !estado.grupos_cerrados.contains(&numero) {
+            return Err(ErrorRegex::ReferenciaInvalida);
+        }
+        Ok(RegexToken::new(
+            ValorRegexToken::Referencia(numero, false),
+            RepeticionRegexToken::new(Some(1), Some(1)),
+        ))
+    }
+
+    /// Devuelve el token que representa un caracter precedido por una barra invertida.
+    ///
+    /// * \\w, \\s y \\d son las clases de caracteres de palabra (letras, dígitos y '_'), espacios y
+    ///   dígitos, y \\W, \\S y \\D sus negaciones.
+    /// * \\b, \\B, \\<, \\>, \\` y \\' son [aserciones][Asercion].
+    /// * \\n es el caracter de nueva línea, que solo puede matchear al evaluar varias líneas a la
+    ///   vez (ej.: con el modo multilínea de egrep).
+    /// * Cualquier otro caracter que no sea una letra ni un dígito es un literal (ej.: \\., \\[).
+    ///
+    /// # Errores
+    ///
+    /// Si el caracter es una letra o un dígito sin significado, se devuelve el error de escape
+    /// desconocido (en vez de tomarlo como literal, para que \\x no matchee silenciosamente con x).
+    fn nuevo_token_escapado(c: char) -> Result<RegexToken, ErrorRegex> {
+        let palabra = || vec![ClaseCaracter::Palabra];
+        let valor = match c {
+            'w' => ValorRegexToken::ExpresionBracket(palabra()),
+            'W' => ValorRegexToken::ExpresionBracketNegada(palabra()),
+            's' => ValorRegexToken::ExpresionBracket(vec![ClaseCaracter::Espacio]),
+            'S' => ValorRegexToken::ExpresionBracketNegada(vec![ClaseCaracter::Espacio]),
+            'd' => ValorRegexToken::ExpresionBracket(vec![ClaseCaracter::Digito]),
+            'D' => ValorRegexToken::ExpresionBracketNegada(vec![ClaseCaracter::Digito]),
+            'b' => return Ok(Self::nuevo_token_asercion(Asercion::LimitePalabra)),
+            'B' => return Ok(Self::nuevo_token_asercion(Asercion::NoLimitePalabra)),
+            '<' => return Ok(Self::nuevo_token_asercion(Asercion::InicioPalabra)),
+            '>' => return Ok(Self::nuevo_token_asercion(Asercion::FinPalabra)),
+            '`' => return Ok(Self::nuevo_token_asercion(Asercion::InicioTexto)),
+            '\'' => return Ok(Self::nuevo_token_asercion(Asercion::FinTexto)),
+            'n' => ValorRegexToken::Literal('\n'),
+            c if c.is_alphanumeric() => return Err(ErrorRegex::EscapeDesconocido(c)),
+            c => ValorRegexToken::Literal(c),
+        };
+        Ok(RegexToken::new(
+            valor,
+            RepeticionRegexToken::new(Some(1), Some(1)),
+        ))
+    }
+
+    /// Devuelve el token de una propiedad de Unicode (\\p{...}, o \\pX si su nombre es una letra),
+    /// o de su negación (\\P{...}), según los caracteres que le siguen a la p.
+    ///
+    /// # Errores
+    ///
+    /// Si falta el nombre, la llave no cierra o el nombre es desconocido, se devuelve el error
+    /// de propiedad desconocida.
+    fn nuevo_token_propiedad(
+        negada: bool,
         iter_chars: &mut IteradorVecHaciaAtras<char>,
-    ) -> Result<Option<RegexToken>, ErrorRegex> {
-        if let Some(literal) = iter_chars.next() {
-            Ok(Some(Self::nuevo_token_literal(*literal)))
+    ) -> Result<RegexToken, ErrorRegex> {
+        let nombre = match iter_chars.next() {
+            Some('{') => {
+                let mut nombre = String::new();
+                loop {
+                    match iter_chars.next() {
+                        Some('}') => break nombre,
+                        Some(c) => nombre.push(*c),
+                        None => return Err(ErrorRegex::PropiedadDesconocida(nombre)),
+                    }
+                }
+            }
+            Some(c) => c.to_string(),
+            None => String::new(),
+        };
+        let propiedad = PropiedadUnicode::new(&nombre)
+            .ok_or_else(|| ErrorRegex::PropiedadDesconocida(nombre.clone()))?;
+        let clases_caracter = vec![ClaseCaracter::Propiedad(propiedad)];
+        let valor = if negada {
+            ValorRegexToken::ExpresionBracketNegada(clases_caracter)
         } else {
-            Err(ErrorRegex::BarraInvertidaAlFinal)
-        }
+            ValorRegexToken::ExpresionBracket(clases_caracter)
+        };
+        Ok(RegexToken::new(
+            valor,
+            RepeticionRegexToken::new(Some(1), Some(1)),
+        ))
+    }
+
+    fn nuevo_token_asercion(asercion: Asercion) -> RegexToken {
+        RegexToken::new(
+            ValorRegexToken::Asercion(asercion),
+            RepeticionRegexToken::new(Some(0), Some(0)),
+        )
     }
 
     fn guardar_expresion(expresiones: &mut Vec<Expresion>, tokens: Vec<RegexToken>) {
@@ -150,10 +793,6 @@ impl Regex {
         expresiones.push(expresion);
     }
 
-    fn es_metacaracter_de_repeticion(c: char) -> bool {
-        (c == '?') | (c == '*') | (c == '+') | (c == '{')
-    }
-
     /// Modifica la repetición de un token. Devuelve true o false, según si se pudo realizar
     /// la modificación.
     ///
@@ -171,24 +810,43 @@ impl Regex {
     /// * Si no se puede modificar la repetición del token, significa que ya había un rango de repetición
     ///   luego de otro.
     fn modificar_repeticion_token(
-        token: Option<&mut RegexToken>,
+        tokens: &mut Vec<RegexToken>,
         c: char,
         iter_chars: &mut IteradorVecHaciaAtras<char>,
+        estado: &EstadoAnalisis,
     ) -> Result<bool, ErrorRegex> {
-        let repeticion = obtener_repeticion(c, iter_chars)?;
-        if let Some(rep) = repeticion {
-            if let Some(ultimo_token) = token {
-                if !ultimo_token.modificar_repeticion(rep) {
-                    Err(ErrorRegex::RepeticionInvalida)
-                } else {
-                    Ok(true)
-                }
-            } else {
-                Err(ErrorRegex::RepeticionInvalida)
+        let repeticion = obtener_repeticion(c, iter_chars, estado.sintaxis)?;
+        let Some(mut rep) = repeticion else {
+            return Ok(false);
+        };
+        let posesiva = rep.es_posesiva();
+        if posesiva {
+            rep = RepeticionRegexToken::new(rep.min(), rep.max());
+        }
+        let Some(mut ultimo_token) = tokens.pop() else {
+            return Err(ErrorRegex::RepeticionInvalida);
+        };
+        if !ultimo_token.modificar_repeticion(rep.clone()) {
+            if estado.estricta {
+                return Err(ErrorRegex::RepeticionInvalida);
             }
-        } else {
-            Ok(false)
+            // El token ya repetido (o que no admite repetición) se repite dentro de un grupo que
+            // no captura lo que matchea, que también queda repetido.
+            ultimo_token = RegexToken::repetido(
+                ValorRegexToken::Grupo(None, vec![Expresion::new(vec![ultimo_token])]),
+                rep,
+            );
+        }
+        if posesiva {
+            // Una repetición posesiva es un grupo atómico con la repetición, que ya cuenta como
+            // repetido: una repetición siguiente lo repite dentro de otro grupo.
+            ultimo_token = RegexToken::repetido(
+                ValorRegexToken::GrupoAtomico(vec![Expresion::new(vec![ultimo_token])]),
+                RepeticionRegexToken::new(Some(1), Some(1)),
+            );
         }
+        tokens.push(ultimo_token);
+        Ok(true)
     }
 
     /// Matcheo de un valor recibido como string slice con una expresión regular.
@@ -196,28 +854,247 @@ impl Regex {
     /// Si alguna de las expresiones particulares que la conforman matchea con el valor,
     /// devuelve true. Caso contrario, devuelve false.
     pub fn matchea(&self, valor: &str) -> bool {
-        for expresion in &self.expresiones {
-            if expresion.matchea(valor) {
-                return true;
-            }
+        self.is_match_bytes(valor.as_bytes())
+    }
+
+    /// Matcheo de un valor recibido como slice de bytes con una expresión regular.
+    ///
+    /// El valor no necesita ser utf8 válido: los literales del patrón se matchean con su
+    /// codificación utf8, mientras que el comodín y las bracket expresiones negadas matchean
+    /// con un único byte cuando este no forma parte de un caracter utf8 válido.
+    pub fn is_match_bytes(&self, valor: &[u8]) -> bool {
+        self.hay_coincidencia(valor, Delimitacion::Ninguna)
+    }
+
+    /// Devuelve true si hay alguna coincidencia con la expresión regular en un valor recibido como
+    /// slice de bytes que cumple con una delimitación.
+    ///
+    /// A diferencia de [buscar](Self::buscar), termina en la primera coincidencia que encuentra.
+    pub(crate) fn hay_coincidencia(&self, valor: &[u8], delimitacion: Delimitacion) -> bool {
+        let por_automata = self.con_backtracking.len() < self.expresiones.len()
+            && self.automata.matchea(valor, delimitacion);
+        por_automata
+            || (!self.con_backtracking.is_empty()
+                && Backtracking::new(valor)
+                    .buscar(&self.con_backtracking, 0, |inicio, fin| {
+                        delimitacion.permite_inicio(valor, inicio)
+                            && delimitacion.permite_fin(valor, fin)
+                    })
+                    .is_some())
+    }
+
+    /// Búsqueda en un valor recibido como slice de bytes, desde una posición, de la coincidencia
+    /// con la expresión regular que comienza primero (y, entre las que comienzan allí, la más
+    /// larga) y que cumple con una delimitación.
+    ///
+    /// Devuelve las posiciones de inicio y fin de la coincidencia, o None si no hay ninguna.
+    pub fn buscar(
+        &self,
+        valor: &[u8],
+        desde: usize,
+        delimitacion: Delimitacion,
+    ) -> Option<(usize, usize)> {
+        let por_automata = (self.con_backtracking.len() < self.expresiones.len())
+            .then(|| self.automata.buscar(valor, desde, delimitacion))
+            .flatten();
+        if self.con_backtracking.is_empty() {
+            return por_automata;
         }
-        false
+        let por_backtracking = Backtracking::new(valor)
+            .mas_largo(self.coincidencia_mas_larga)
+            .buscar(&self.con_backtracking, desde, |inicio, fin| {
+                delimitacion.permite_inicio(valor, inicio) && delimitacion.permite_fin(valor, fin)
+            });
+        // La que comienza primero y, entre las que comienzan en la misma posición, la más larga.
+        por_automata
+            .into_iter()
+            .chain(por_backtracking)
+            .min_by_key(|(inicio, fin)| (*inicio, std::cmp::Reverse(*fin)))
+    }
+
+    /// Búsqueda en un valor recibido como slice de bytes, desde una posición, de la coincidencia
+    /// con la expresión regular que cumple con una delimitación (como [buscar](Self::buscar)).
+    ///
+    /// Devuelve las capturas de la coincidencia, o None si no hay ninguna.
+    pub fn buscar_capturas<'a>(
+        &'a self,
+        valor: &'a [u8],
+        desde: usize,
+        delimitacion: Delimitacion,
+    ) -> Option<Capturas<'a>> {
+        self.buscar(valor, desde, delimitacion)
+            .map(|coincidencia| self.capturas_de(valor, coincidencia))
+    }
+
+    /// Devuelve las capturas de una coincidencia con la expresión regular, recibiendo sus
+    /// posiciones de inicio y fin. Lo que matcheó cada grupo se obtiene con el motor de
+    /// backtracking, entre los matcheos que terminan en el fin de la coincidencia.
+    pub(crate) fn capturas_de<'a>(
+        &'a self,
+        valor: &'a [u8],
+        (inicio, fin): (usize, usize),
+    ) -> Capturas<'a> {
+        let mut backtracking = Backtracking::new(valor);
+        backtracking.matchear_desde(&self.expresiones, inicio, |f| f == fin);
+        let mut posiciones = backtracking.capturas().to_vec();
+        if posiciones.is_empty() {
+            posiciones.push(None);
+        }
+        posiciones[0] = Some((inicio, fin));
+        Capturas::new(valor, posiciones, &self.nombres_grupos)
+    }
+
+    /// Devuelve un valor con la primera coincidencia con la expresión regular reemplazada.
+    ///
+    /// El reemplazo puede ser una plantilla, en la que se expanden las referencias a las
+    /// capturas de la coincidencia (`$1`, `${nombre}`, `\1`; ver [Capturas::expandir]), o una
+    /// función que recibe las capturas y devuelve el reemplazo.
+    ///
+    /// ```
+    /// use egrep::regex::Regex;
+    ///
+    /// let regex = Regex::new("([[:lower:]]+)@([[:lower:]]+)").unwrap();
+    /// assert_eq!(regex.replace("ana@casa y luis@club", "$2:$1"), "casa:ana y luis@club");
+    /// assert_eq!(regex.replace_all("ana@casa y luis@club", "$2:$1"), "casa:ana y club:luis");
+    /// ```
+    pub fn replace(&self, valor: &str, reemplazo: impl Reemplazo) -> String {
+        self.replacen(valor, 1, reemplazo)
+    }
+
+    /// Devuelve un valor con todas las coincidencias con la expresión regular reemplazadas (ver
+    /// [replace](Self::replace)).
+    ///
+    /// Se reemplazan también las coincidencias vacías, a diferencia de la opción `--replace` de
+    /// la línea de comando, que las ignora como `-o` (ej.: con 'x*', `replace_all("ab", "-")`
+    /// devuelve "-a-b-", mientras que `--replace -` deja la línea como "ab").
+    pub fn replace_all(&self, valor: &str, reemplazo: impl Reemplazo) -> String {
+        self.replacen(valor, 0, reemplazo)
+    }
+
+    /// Devuelve un valor con las primeras coincidencias con la expresión regular, hasta un
+    /// límite, reemplazadas (ver [replace](Self::replace)). Si el límite es 0, se reemplazan
+    /// todas.
+    pub fn replacen(&self, valor: &str, limite: usize, reemplazo: impl Reemplazo) -> String {
+        let resultado = self.replacen_bytes(valor.as_bytes(), limite, reemplazo);
+        String::from_utf8_lossy(&resultado).into_owned()
+    }
+
+    /// Devuelve un valor recibido como slice de bytes con las primeras coincidencias con la
+    /// expresión regular, hasta un límite (0 para todas), reemplazadas (ver
+    /// [replace](Self::replace)). Las coincidencias se recorren como en
+    /// [find_iter](Self::find_iter).
+    pub fn replacen_bytes(
+        &self,
+        valor: &[u8],
+        limite: usize,
+        mut reemplazo: impl Reemplazo,
+    ) -> Vec<u8> {
+        let limite = if limite == 0 { usize::MAX } else { limite };
+        let mut resultado = Vec::with_capacity(valor.len());
+        let mut copiado = 0;
+        for (inicio, fin) in self.find_iter_bytes(valor).take(limite) {
+            resultado.extend_from_slice(&valor[copiado..inicio]);
+            reemplazo.reemplazar(&self.capturas_de(valor, (inicio, fin)), &mut resultado);
+            copiado = fin;
+        }
+        resultado.extend_from_slice(&valor[copiado..]);
+        resultado
+    }
+
+    /// Devuelve un iterador sobre las posiciones de inicio y fin de las coincidencias con la
+    /// expresión regular en un valor, en orden y sin superponerse.
+    ///
+    /// Cada búsqueda comienza donde termina la coincidencia anterior, o en el siguiente caracter si
+    /// esta es vacía. Una coincidencia vacía justo al final de otra no se devuelve.
+    ///
+    /// ```
+    /// use egrep::regex::Regex;
+    ///
+    /// let regex = Regex::new("[[:digit:]]+").unwrap();
+    /// let numeros: Vec<(usize, usize)> = regex.find_iter("a1b22c").collect();
+    /// assert_eq!(numeros, vec![(1, 2), (3, 5)]);
+    /// ```
+    pub fn find_iter<'r, 'v>(&'r self, valor: &'v str) -> Coincidencias<'r, 'v> {
+        self.find_iter_bytes(valor.as_bytes())
+    }
+
+    /// Devuelve un iterador sobre las coincidencias con la expresión regular en un valor recibido
+    /// como slice de bytes (ver [find_iter](Self::find_iter)).
+    pub fn find_iter_bytes<'r, 'v>(&'r self, valor: &'v [u8]) -> Coincidencias<'r, 'v> {
+        Coincidencias::new(self, valor)
+    }
+
+    /// Devuelve un iterador sobre las partes de un valor separadas por las coincidencias con la
+    /// expresión regular (las de [find_iter](Self::find_iter)).
+    ///
+    /// Si el valor comienza o termina con una coincidencia, la primera o la última parte es vacía.
+    ///
+    /// ```
+    /// use egrep::regex::Regex;
+    ///
+    /// let separador = Regex::new("[[:space:]]*[;,][[:space:]]*").unwrap();
+    /// let campos: Vec<&str> = separador.split("a ; b,c").collect();
+    /// assert_eq!(campos, vec!["a", "b", "c"]);
+    /// ```
+    pub fn split<'r, 'v>(&'r self, valor: &'v str) -> Division<'r, 'v> {
+        Division::new(self, valor, None)
+    }
+
+    /// Devuelve un iterador sobre a lo sumo un límite de partes de un valor separadas por las
+    /// coincidencias con la expresión regular (ver [split](Self::split)). La última parte es el
+    /// resto del valor, aunque contenga coincidencias.
+    pub fn splitn<'r, 'v>(&'r self, valor: &'v str, limite: usize) -> Division<'r, 'v> {
+        Division::new(self, valor, Some(limite))
     }
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
+    use crate::{error_expresion_bracket::ErrorExpresionBracket, regex_builder::RegexBuilder};
 
     #[test]
     fn error_al_querer_añadir_repeticion() {
-        let resultado1 = Regex::new("hol{3}{5}a");
-        let resultado2 = Regex::new("?hola");
+        let resultado1 = RegexBuilder::new("hol{3}{5}a").strict(true).build();
+        let resultado2 = RegexBuilder::new("?hola").strict(true).build();
         assert!(resultado1.is_err());
         assert!(resultado2.is_err());
     }
 
+    #[test]
+    fn repeticiones_concatenadas_y_al_inicio() {
+        let regex = Regex::new("a**b+?c{1}{2}").unwrap();
+
+        assert!(regex.matchea("bcc"));
+        assert!(regex.matchea("aaacc"));
+        assert!(!regex.matchea("aac"));
+        let tres_veces = Regex::new("^a{2}{3}{2}$").unwrap();
+        assert!(tres_veces.matchea(&"a".repeat(12)));
+        assert!(!tres_veces.matchea("aaaa"));
+        assert!(Regex::new("?hola").unwrap().matchea("?hola"));
+        assert!(Regex::new("x|{2}").unwrap().matchea("{2}"));
+        assert!(Regex::new(r"\b+a").unwrap().matchea(" a"));
+    }
+
+    #[test]
+    fn repeticiones_del_anclaje_inicio() {
+        for patron in ["^*a", "^?a", "^{0,1}a"] {
+            let regex = Regex::new(patron).unwrap();
+            assert!(regex.matchea("xa"), "{}", patron);
+            assert!(!regex.matchea("*b"), "{}", patron);
+        }
+        let una_o_mas = Regex::new("^+a").unwrap();
+        assert!(una_o_mas.matchea("ab"));
+        assert!(!una_o_mas.matchea("xa"));
+
+        for patron in ["^*a", r"^\{1\}a", r"^\+a"] {
+            let literal = &patron[1..].replace('\\', "");
+            let regex = regex_basica(patron).unwrap();
+            assert!(regex.matchea(literal), "{}", patron);
+            assert!(!regex.matchea("a"), "{}", patron);
+        }
+    }
+
     #[test]
     fn error_al_enviar_barra_invertida_al_final() {
         let resultado = Regex::new("hola\\");
@@ -225,8 +1102,487 @@ mod tests {
     }
 
     #[test]
-    fn error_anclaje_inicio_en_medio() {
+    fn anclaje_inicio_en_medio() {
         let resultado = Regex::new("ho^la");
-        assert!(resultado.is_err());
+        assert!(!resultado.unwrap().matchea("ho^la"));
+    }
+
+    #[test]
+    fn anclajes_en_medio_de_una_expresion() {
+        let campo = Regex::new("(^|,)campo($|,)").unwrap();
+
+        assert!(campo.matchea("campo,otro"));
+        assert!(campo.matchea("otro,campo"));
+        assert!(campo.matchea("a,campo,b"));
+        assert!(!campo.matchea("a,campos"));
+        assert!(!campo.matchea("micampo"));
+        assert!(Regex::new("a$|^b").unwrap().matchea("bc"));
+        assert!(!Regex::new("a$b").unwrap().matchea("a$b"));
+        assert!(regex_basica("ho^la").unwrap().matchea("ho^la"));
+    }
+
+    #[test]
+    fn anclaje_inicio_en_grupos_y_alternativas() {
+        let extendida = Regex::new("x(^a)|(^b|c)d").unwrap();
+        let basica = regex_basica("\\(^a\\)b\\|c\\|^d").unwrap();
+        let multilinea = RegexBuilder::new("(^a|,)b|c|^d")
+            .multi_line(true)
+            .build()
+            .unwrap();
+        let basica_multilinea = RegexBuilder::new("\\(^a\\)\\|x^y")
+            .syntax(Syntax::Bre)
+            .multi_line(true)
+            .build()
+            .unwrap();
+        let buscar = |regex: &Regex, valor| regex.buscar(valor, 0, Delimitacion::Ninguna);
+
+        assert_eq!(buscar(&extendida, b"bd"), Some((0, 2)));
+        assert_eq!(buscar(&extendida, b"xbd xa"), None);
+        assert_eq!(buscar(&extendida, b"xbd cd"), Some((4, 6)));
+        assert_eq!(buscar(&basica, b"ab"), Some((0, 2)));
+        assert_eq!(buscar(&basica, b"xab"), None);
+        assert_eq!(buscar(&basica, b"d"), Some((0, 1)));
+        assert_eq!(buscar(&basica, b"xd"), None);
+        assert_eq!(buscar(&multilinea, b"xab\nab"), Some((4, 6)));
+        assert_eq!(buscar(&multilinea, b"xd\nd"), Some((3, 4)));
+        assert_eq!(buscar(&multilinea, b"x,b"), Some((1, 3)));
+        assert_eq!(buscar(&basica_multilinea, b"ba\na"), Some((3, 4)));
+        assert_eq!(buscar(&basica_multilinea, b"x^y"), Some((0, 3)));
+    }
+
+    #[test]
+    fn busqueda_ignorando_mayusculas() {
+        let regex = Regex::new("ho[[:lower:]]a|chau")
+            .unwrap()
+            .ignorar_mayusculas();
+
+        assert!(regex.matchea("¡HOLA!"));
+        assert_eq!(
+            regex.buscar(b"Hola, CHAU", 0, Delimitacion::Ninguna),
+            Some((0, 4))
+        );
+        assert_eq!(
+            regex.buscar(b"Hola, CHAU", 1, Delimitacion::Ninguna),
+            Some((6, 10))
+        );
+    }
+
+    fn regex_basica(patron: &str) -> Result<Regex, Error> {
+        RegexBuilder::new(patron).syntax(Syntax::Bre).build()
+    }
+
+    #[test]
+    fn metacaracteres_de_sintaxis_basica() {
+        let regex = regex_basica(r"^a\{2\}b\+c?\(d\|e\)$").unwrap();
+
+        assert!(regex.matchea("aabbc?e"));
+        assert!(!regex.matchea("aabbce"));
+
+        let regex = regex_basica("*a{2}|b^$c").unwrap();
+
+        assert!(regex.matchea("x*a{2}|b^$c"));
+        assert!(!regex.matchea("aab"));
+    }
+
+    #[test]
+    fn anclaje_inicio_repetido_en_sintaxis_basica() {
+        for patron in ["^^x", r"q\|^^x", r"\(^^x\)"] {
+            let regex = regex_basica(patron).unwrap();
+
+            assert!(regex.matchea("^xy"), "{patron}");
+            assert!(!regex.matchea("x"), "{patron}");
+            assert!(!regex.matchea("a^x"), "{patron}");
+        }
+
+        let regex = Regex::new("^^x").unwrap();
+
+        assert!(regex.matchea("xy"));
+        assert!(!regex.matchea("^x"));
+    }
+
+    #[test]
+    fn grupos_de_sintaxis_extendida() {
+        let regex = Regex::new("^(ab|c)+d)$").unwrap();
+
+        assert!(regex.matchea("abcabd)"));
+        assert!(!regex.matchea("d)"));
+        assert!(Regex::new("(ab").is_err());
+    }
+
+    #[test]
+    fn referencias_de_sintaxis_basica() {
+        let regex = regex_basica(r"\([abc]\)x\1").unwrap();
+
+        assert!(regex.matchea("zbxb"));
+        assert!(!regex.matchea("zbxc"));
+        assert_eq!(
+            regex.buscar(b"axb cxc", 0, Delimitacion::Palabra),
+            Some((4, 7))
+        );
+    }
+
+    #[test]
+    fn errores_de_sintaxis_basica() {
+        assert!(regex_basica(r"\(a").is_err());
+        assert!(regex_basica(r"a\)").is_err());
+        assert!(regex_basica(r"\1\(a\)").is_err());
+        assert!(regex_basica(r"\(a\1\)").is_err());
+    }
+
+    #[test]
+    fn referencias_de_sintaxis_extendida() {
+        let regex = Regex::new("([[:alpha:]]+) \\1").unwrap();
+
+        assert!(regex.matchea("es es el fin"));
+        assert!(!regex.matchea("es el fin"));
+        assert_eq!(
+            regex.buscar(b"el fin fin", 0, Delimitacion::Palabra),
+            Some((3, 10))
+        );
+        assert!(Regex::new("(a)\\2").is_err());
+    }
+
+    #[test]
+    fn referencias_con_la_alternativa_mas_larga() {
+        let extendida = Regex::new("(x)\\1|(x)\\2yy").unwrap();
+        let basica = regex_basica("\\(x\\)\\1\\|\\(x\\)\\2yy").unwrap();
+        let perl = RegexBuilder::new("(x)\\1|(x)\\2yy")
+            .syntax(Syntax::Perl)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            extendida.buscar(b"xxyy", 0, Delimitacion::Ninguna),
+            Some((0, 4))
+        );
+        assert_eq!(
+            basica.buscar(b"xxyy", 0, Delimitacion::Ninguna),
+            Some((0, 4))
+        );
+        assert_eq!(perl.buscar(b"xxyy", 0, Delimitacion::Ninguna), Some((0, 2)));
+        assert_eq!(extendida.replace("xxyy!", "<$2>"), "<x>!");
+    }
+
+    #[test]
+    fn referencias_ignorando_mayusculas() {
+        let regex = RegexBuilder::new("(a)\\1")
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+        let unicode = RegexBuilder::new("(ñ+)-\\1")
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+
+        assert!(regex.matchea("aA"));
+        assert!(regex.matchea("Aa"));
+        assert!(!regex.matchea("ab"));
+        assert_eq!(
+            unicode.buscar("xñÑ-ÑñY".as_bytes(), 0, Delimitacion::Ninguna),
+            Some((1, 10))
+        );
+        assert!(!Regex::new("(a)\\1").unwrap().matchea("aA"));
+    }
+
+    #[test]
+    fn escapes_de_clases_de_caracteres() {
+        let regex = Regex::new(r"\d+\s\w+\W\S\D").unwrap();
+
+        assert_eq!(
+            regex.buscar(b"tel: 42 mi_num!xy", 0, Delimitacion::Ninguna),
+            Some((5, 17))
+        );
+        assert!(!regex.matchea("42 abc!12"));
+        assert!(Regex::new(r"\.\[").unwrap().matchea("a.[b"));
+        assert!(Regex::new(r"a\nb").unwrap().matchea("a\nb"));
+        assert!(!Regex::new(r"a\nb").unwrap().matchea("anb"));
+    }
+
+    #[test]
+    fn escapes_de_aserciones() {
+        let regex = Regex::new(r"\bes\b").unwrap();
+
+        assert_eq!(
+            regex.buscar(b"este es", 0, Delimitacion::Ninguna),
+            Some((5, 7))
+        );
+        assert!(Regex::new(r"\<fin\>").unwrap().matchea("el fin."));
+        assert!(!Regex::new(r"\<fin\>").unwrap().matchea("confines"));
+        assert!(Regex::new(r"\Bin\B").unwrap().matchea("confines"));
+        assert!(Regex::new(r"\`a.*z\'").unwrap().matchea("abcz"));
+        assert!(!Regex::new(r"a\'").unwrap().matchea("ab"));
+    }
+
+    #[test]
+    fn error_escape_desconocido() {
+        let error = Regex::new(r"a\qb").unwrap_err();
+
+        assert_eq!(
+            error.tipo(),
+            &TipoError::Regex(ErrorRegex::EscapeDesconocido('q'))
+        );
+        assert_eq!(error.posiciones(), (1, 3));
+        assert!(Regex::new(r"\0").is_err());
+    }
+
+    #[test]
+    fn propiedades_de_unicode() {
+        let regex = Regex::new(r"\p{Greek}+\s\PL\pN").unwrap();
+
+        assert_eq!(
+            regex.buscar("x αβγ -٣".as_bytes(), 0, Delimitacion::Ninguna),
+            Some((2, 12))
+        );
+        assert!(!regex.matchea("abc -3"));
+        assert_eq!(
+            Regex::new(r"\p{Klingon}").unwrap_err().tipo(),
+            &TipoError::Regex(ErrorRegex::PropiedadDesconocida("Klingon".to_string()))
+        );
+        assert!(Regex::new(r"\p{L").is_err());
+    }
+
+    #[test]
+    fn repeticiones_perezosas_de_sintaxis_perl() {
+        let regex = RegexBuilder::new("<.+?>")
+            .syntax(Syntax::Perl)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            regex.buscar(b"<a><b>", 0, Delimitacion::Ninguna),
+            Some((0, 3))
+        );
+        assert_eq!(
+            Regex::new("<.+>")
+                .unwrap()
+                .buscar(b"<a><b>", 0, Delimitacion::Ninguna),
+            Some((0, 6))
+        );
+
+        let regex = RegexBuilder::new("a{2,3}?b??")
+            .syntax(Syntax::Perl)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            regex.buscar(b"aaab", 0, Delimitacion::Ninguna),
+            Some((0, 2))
+        );
+        assert_eq!(regex.buscar(b"aaab", 0, Delimitacion::Linea), Some((0, 4)));
+        assert_eq!(
+            Regex::new("a+?")
+                .unwrap()
+                .buscar(b"aaab", 0, Delimitacion::Ninguna),
+            Some((0, 3))
+        );
+    }
+
+    #[test]
+    fn repeticiones_posesivas_y_grupos_atomicos_de_sintaxis_perl() {
+        let regex_perl = |patron| RegexBuilder::new(patron).syntax(Syntax::Perl).build();
+        let buscar = |patron, valor| {
+            regex_perl(patron)
+                .unwrap()
+                .buscar(valor, 0, Delimitacion::Ninguna)
+        };
+
+        assert_eq!(buscar("a*+a", b"aaa"), None);
+        assert_eq!(buscar("a*+b", b"aaab"), Some((0, 4)));
+        assert_eq!(buscar("a++", b"baab"), Some((1, 3)));
+        assert_eq!(buscar("a?+a", b"a"), None);
+        assert_eq!(buscar("x{1,2}+x", b"xxx"), Some((0, 3)));
+        assert_eq!(buscar("x{1,2}+x", b"xx"), None);
+        assert_eq!(buscar("(?>a|ab)c", b"abc"), None);
+        assert_eq!(buscar("(?>a|ab)c", b"xac"), Some((1, 3)));
+        assert_eq!(buscar("(?>a+)+b", b"aab"), Some((0, 3)));
+        assert_eq!(buscar("a++{2}", b"aaa"), None);
+        assert_eq!(buscar("a{2}+{2}", b"aaaaa"), Some((0, 4)));
+        assert!(Regex::new("a*+a").unwrap().matchea("aaa"));
+
+        let regex = regex_perl("(?>(a+))(b)").unwrap();
+        let capturas = regex
+            .buscar_capturas(b"aab", 0, Delimitacion::Ninguna)
+            .unwrap();
+        assert_eq!(capturas.get(1), Some(&b"aa"[..]));
+        assert_eq!(capturas.get(2), Some(&b"b"[..]));
+        assert!(RegexBuilder::new("a*+*")
+            .syntax(Syntax::Perl)
+            .strict(true)
+            .build()
+            .is_err());
+        assert_eq!(
+            RegexBuilder::new("(?>a)")
+                .syntax(Syntax::Perl)
+                .engine(Engine::Automata)
+                .build()
+                .unwrap_err()
+                .tipo(),
+            &TipoError::Regex(ErrorRegex::MotorIncompatible)
+        );
+    }
+
+    #[test]
+    fn anticipaciones_y_retrospecciones_de_sintaxis_perl() {
+        let regex_perl = |patron| RegexBuilder::new(patron).syntax(Syntax::Perl).build();
+        let anticipacion = regex_perl("foo(?=bar)").unwrap();
+        let comilla_sin_escapar = regex_perl(r#"(?<!\\)""#).unwrap();
+
+        assert_eq!(
+            anticipacion.buscar(b"foobaz foobar", 0, Delimitacion::Ninguna),
+            Some((7, 10))
+        );
+        assert_eq!(
+            comilla_sin_escapar.buscar(br#"a\"b"c"#, 0, Delimitacion::Ninguna),
+            Some((4, 5))
+        );
+        assert!(regex_perl("^(?!.*error).*ok").unwrap().matchea("todo ok"));
+        assert!(!regex_perl("^(?!.*error).*ok").unwrap().matchea("error, ok"));
+        assert!(regex_perl("(?<=ñ|ab{1,2})c").unwrap().matchea("abbc"));
+        assert!(regex_perl("(?<=ñ|ab{1,2})c").unwrap().matchea("ñc"));
+        assert!(!regex_perl("(?<=ñ|ab{1,2})c").unwrap().matchea("ac"));
+        let error = regex_perl("(?<=a+)b").unwrap_err();
+
+        assert_eq!(
+            error.tipo(),
+            &TipoError::Regex(ErrorRegex::RetrospeccionNoAcotada)
+        );
+        assert_eq!(error.posiciones(), (0, 7));
+        assert!(!Regex::new("foo(?=bar)").unwrap().matchea("foobar"));
+    }
+
+    #[test]
+    fn errores_indican_la_parte_del_patron() {
+        let posiciones = |patron: &str| Regex::new(patron).unwrap_err().posiciones();
+
+        assert_eq!(posiciones("ab[[:spac:]]c"), (2, 12));
+        assert_eq!(posiciones("ñ(a|b"), (2, 3));
+        assert_eq!(posiciones(r"(a)\2"), (3, 5));
+        assert_eq!(posiciones("a{2,1}"), (1, 6));
+        assert_eq!(posiciones("ab\\"), (2, 3));
+        assert_eq!(
+            regex_basica(r"a\)b").unwrap_err().to_string(),
+            "Paréntesis ( o ) desbalanceado\n  a\\)b\n   ^~"
+        );
+        assert_eq!(
+            Regex::new("x[ab").unwrap_err().tipo(),
+            &TipoError::ExpresionBracket(ErrorExpresionBracket::BracketNoMatchea)
+        );
+    }
+
+    #[test]
+    fn reemplazos_con_plantillas_y_funciones() {
+        let regex = Regex::new("([[:digit:]]+)-([[:digit:]]+)").unwrap();
+        let valor = "1-2, 30-40, 5-6";
+
+        assert_eq!(regex.replace(valor, "$2-$1"), "2-1, 30-40, 5-6");
+        assert_eq!(regex.replace_all(valor, "${2}0:$1"), "20:1, 400:30, 60:5");
+        assert_eq!(regex.replacen(valor, 2, r"<\0>"), "<1-2>, <30-40>, 5-6");
+        assert_eq!(
+            regex.replace_all(valor, |capturas: &Capturas| {
+                let numero = |i| -> u32 {
+                    std::str::from_utf8(capturas.get(i).unwrap())
+                        .unwrap()
+                        .parse()
+                        .unwrap()
+                };
+                (numero(1) + numero(2)).to_string()
+            }),
+            "3, 70, 11"
+        );
+        assert_eq!(
+            regex_basica(r"\(a\)\(x\)*")
+                .unwrap()
+                .replace_all("ab ax", r"[\1\2]"),
+            "[a]b [ax]"
+        );
+        assert_eq!(regex.replace("sin numeros", "$1"), "sin numeros");
+    }
+
+    #[test]
+    fn reemplazos_de_coincidencias_vacias() {
+        let regex = Regex::new("a*").unwrap();
+
+        assert_eq!(regex.replace_all("baaac", "-"), "-b-c-");
+        assert_eq!(regex.replace_all("ñ", "-"), "-ñ-");
+        assert_eq!(regex.replacen_bytes(b"\xffa", 0, "-"), b"-\xff-");
+    }
+
+    #[test]
+    fn grupos_con_nombre_de_sintaxis_perl() {
+        let regex_perl = |patron| RegexBuilder::new(patron).syntax(Syntax::Perl).build();
+        let fecha = regex_perl(r"(?<dia>\d+)/(?P<mes>\d+)/(\d+)").unwrap();
+        let capturas = fecha
+            .buscar_capturas(b"hoy: 19/10/2026", 0, Delimitacion::Ninguna)
+            .unwrap();
+
+        assert_eq!(capturas.nombre("mes"), Some(&b"10"[..]));
+        assert_eq!(capturas.posiciones(3), Some((11, 15)));
+        assert_eq!(
+            fecha.replace("19/10/2026", "${3}-${mes}-${dia}"),
+            "2026-10-19"
+        );
+        assert_eq!(
+            regex_perl("(?<1a>x)").unwrap_err().tipo(),
+            &TipoError::Regex(ErrorRegex::NombreDeGrupoInvalido)
+        );
+        assert_eq!(regex_perl("a(?<b").unwrap_err().posiciones(), (1, 5));
+        assert!(Regex::new("(?<b>x)").unwrap().matchea("?<b>x"));
+    }
+
+    #[test]
+    fn grupos_que_no_capturan_y_desconocidos_de_sintaxis_perl() {
+        let regex_perl = |patron| RegexBuilder::new(patron).syntax(Syntax::Perl).build();
+        let regex = regex_perl(r"(?:ab)+(c)\1").unwrap();
+        let capturas = regex
+            .buscar_capturas(b"xababcc", 0, Delimitacion::Ninguna)
+            .unwrap();
+
+        assert_eq!(capturas.posiciones(0), Some((1, 7)));
+        assert_eq!(capturas.posiciones(1), Some((5, 6)));
+        assert!(regex_perl("^(?:ab)$").unwrap().matchea("ab"));
+        for patron in ["(?i)a", "(?#comentario)a", "x(?"] {
+            assert_eq!(
+                regex_perl(patron).unwrap_err().tipo(),
+                &TipoError::Regex(ErrorRegex::GrupoDesconocido),
+                "{}",
+                patron
+            );
+        }
+        assert!(Regex::new("(?:a)").unwrap().matchea("?:a"));
+    }
+
+    #[test]
+    fn solo_las_alternativas_con_referencias_usan_backtracking() {
+        let regex = Regex::alternar([Regex::new("(a)\\1").unwrap(), Regex::new("b+|c").unwrap()]);
+
+        assert_eq!(regex.con_backtracking.len(), 1);
+        assert_eq!(
+            regex.buscar(b"xbbbaa", 0, Delimitacion::Ninguna),
+            Some((1, 4))
+        );
+        assert_eq!(
+            regex.buscar(b"xaabbb", 0, Delimitacion::Ninguna),
+            Some((1, 3))
+        );
+        assert_eq!(regex.buscar(b"xab", 0, Delimitacion::Ninguna), Some((2, 3)));
+    }
+
+    #[test]
+    fn bytes_invalidos_en_el_patron() {
+        let opciones = OpcionesRegex::default();
+        let regex = Regex::con_opciones_bytes(b"se\xF1al|\\\xFF+", &opciones).unwrap();
+
+        assert!(regex.is_match_bytes(b"una se\xF1al"));
+        assert!(!regex.is_match_bytes("una señal".as_bytes()));
+        assert_eq!(
+            regex.buscar(b"a\xFF\xFF", 0, Delimitacion::Ninguna),
+            Some((1, 3))
+        );
+        assert_eq!(
+            Regex::con_opciones_bytes(b"\xF1(", &opciones)
+                .unwrap_err()
+                .posiciones(),
+            (3, 4)
+        );
     }
 }
@@ -0,0 +1,288 @@
+use crate::{engine::Engine, error::Error, regex::Regex, syntax::Syntax};
+
+/// Máxima cantidad de instrucciones del autómata en el que se compila una expresión regular, si
+/// no se indica otra (ver [RegexBuilder::size_limit]). Es la que usan [Regex::new] y egrep.
+pub const LIMITE_TAMANO_POR_DEFECTO: usize = 2_000_000;
+
+/// Opciones con las que se crea una expresión regular. Por defecto, son las que usa [Regex::new].
+#[derive(Debug, PartialEq, Clone)]
+pub struct OpcionesRegex {
+    pub sintaxis: Syntax,
+    pub ignorar_mayusculas: bool,
+    pub unicode: bool,
+    pub estricta: bool,
+    pub multilinea: bool,
+    pub comodin_nueva_linea: bool,
+    pub terminador_linea: u8,
+    pub limite_tamano: Option<usize>,
+    pub motor: Engine,
+}
+
+impl Default for OpcionesRegex {
+    fn default() -> Self {
+        Self {
+            sintaxis: Syntax::default(),
+            ignorar_mayusculas: false,
+            unicode: false,
+            estricta: false,
+            multilinea: false,
+            comodin_nueva_linea: false,
+            terminador_linea: b'\n',
+            limite_tamano: Some(LIMITE_TAMANO_POR_DEFECTO),
+            motor: Engine::default(),
+        }
+    }
+}
+
+/// Constructor de expresiones regulares que permite configurar cómo se interpreta el patrón y
+/// cómo se evalúa. Es el mismo que usa [egrep](crate::egrep::egrep) con las opciones recibidas.
+///
+/// ```
+/// use egrep::{regex_builder::RegexBuilder, syntax::Syntax};
+///
+/// let regex = RegexBuilder::new(r"\(ab\)\{2\}").syntax(Syntax::Bre).build().unwrap();
+/// assert!(regex.matchea("xababx"));
+///
+/// let regex = RegexBuilder::new("^hola$")
+///     .case_insensitive(true)
+///     .multi_line(true)
+///     .build()
+///     .unwrap();
+/// assert!(regex.matchea("chau\nHola\n"));
+/// ```
+#[derive(Debug)]
+pub struct RegexBuilder {
+    patron: String,
+    opciones: OpcionesRegex,
+}
+
+impl RegexBuilder {
+    /// Creación del constructor recibiendo un patrón, con las opciones por defecto (las mismas
+    /// que usa [Regex::new]).
+    pub fn new(patron: &str) -> Self {
+        Self {
+            patron: patron.to_string(),
+            opciones: OpcionesRegex::default(),
+        }
+    }
+
+    /// Indica la sintaxis con la que se interpreta el patrón (por defecto, [Syntax::Ere]).
+    pub fn syntax(&mut self, sintaxis: Syntax) -> &mut Self {
+        self.opciones.sintaxis = sintaxis;
+        self
+    }
+
+    /// Indica si no se distinguen mayúsculas de minúsculas (por defecto, se distinguen).
+    pub fn case_insensitive(&mut self, ignorar_mayusculas: bool) -> &mut Self {
+        self.opciones.ignorar_mayusculas = ignorar_mayusculas;
+        self
+    }
+
+    /// Indica si las clases de caracter (ej.: \[\[:alpha:]], \\w) incluyen caracteres no ASCII,
+    /// según las categorías generales de Unicode (por defecto, no).
+    pub fn unicode(&mut self, unicode: bool) -> &mut Self {
+        self.opciones.unicode = unicode;
+        self
+    }
+
+    /// Indica si se rechazan las repeticiones que POSIX no define (por defecto, no): las de un
+    /// anclaje o aserción, las que siguen a otra (ej.: a**, a{2}{3}) y las que están al inicio de
+    /// una expresión (ej.: *a, a|+b).
+    ///
+    /// Si no se rechazan, se interpretan como GNU grep: una repetición de otra repite al token ya
+    /// repetido (a{2}{3} equivale a a{6}), una al inicio de una expresión es literal y una luego
+    /// de un anclaje lo repite (^*a matchea con xa). En sintaxis básica, una repetición luego del
+    /// anclaje inicial es literal, como al inicio de una expresión, y un * allí siempre es literal.
+    pub fn strict(&mut self, estricta: bool) -> &mut Self {
+        self.opciones.estricta = estricta;
+        self
+    }
+
+    /// Indica si los anclajes ^ y $ matchean al inicio y al final de cada línea del valor evaluado
+    /// (luego y antes de cada terminador de línea), en vez de solo al inicio y al final de este
+    /// (por defecto, no).
+    pub fn multi_line(&mut self, multilinea: bool) -> &mut Self {
+        self.opciones.multilinea = multilinea;
+        self
+    }
+
+    /// Indica si el comodín '.' matchea con el terminador de línea (por defecto, no).
+    pub fn dot_matches_new_line(&mut self, comodin_nueva_linea: bool) -> &mut Self {
+        self.opciones.comodin_nueva_linea = comodin_nueva_linea;
+        self
+    }
+
+    /// Indica el byte que separa las líneas del valor evaluado (por defecto, '\n'): el que no
+    /// matchea con el comodín y junto al que matchean los anclajes en modo multilínea (ej.: b'\0'
+    /// para registros separados por NUL).
+    pub fn line_terminator(&mut self, terminador: u8) -> &mut Self {
+        self.opciones.terminador_linea = terminador;
+        self
+    }
+
+    /// Indica la máxima cantidad de instrucciones del autómata en el que se compila la expresión
+    /// (por defecto, [LIMITE_TAMANO_POR_DEFECTO]). Las repeticiones acotadas repiten las
+    /// instrucciones de lo que repiten (ej.: (a|b){1000} tiene unas 4000), por lo que un patrón
+    /// corto puede requerir mucha memoria. El tamaño se calcula antes de compilar el autómata,
+    /// por lo que un patrón que supera el límite se rechaza sin expandir sus repeticiones.
+    pub fn size_limit(&mut self, limite: usize) -> &mut Self {
+        self.opciones.limite_tamano = Some(limite);
+        self
+    }
+
+    /// Indica el motor con el que se evalúa la expresión (por defecto, [Engine::Auto]).
+    pub fn engine(&mut self, motor: Engine) -> &mut Self {
+        self.opciones.motor = motor;
+        self
+    }
+
+    /// Creación de la expresión regular con las opciones indicadas.
+    ///
+    /// # Errores
+    ///
+    /// Si el patrón es inválido según la sintaxis indicada, se devuelven los mismos errores que
+    /// en [Regex::new]. Además:
+    ///
+    /// * Si el autómata compilado supera el límite de tamaño indicado.
+    ///
+    /// * Si se indica evaluar con un autómata una expresión que requiere backtracking.
+    pub fn build(&self) -> Result<Regex, Error> {
+        Regex::con_opciones(&self.patron, &self.opciones)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{delimitacion::Delimitacion, error::TipoError, error_regex::ErrorRegex};
+
+    #[test]
+    fn sintaxis_basica_y_extendida() {
+        let basica = RegexBuilder::new("a+(b)")
+            .syntax(Syntax::Bre)
+            .build()
+            .unwrap();
+        let extendida = RegexBuilder::new("a+(b)").build().unwrap();
+
+        assert!(basica.matchea("a+(b)"));
+        assert!(!basica.matchea("aab"));
+        assert!(extendida.matchea("aab"));
+        assert!(!extendida.matchea("a+(b)"));
+    }
+
+    #[test]
+    fn clases_en_modo_unicode() {
+        let ascii = RegexBuilder::new("^[[:alpha:]]+$").build().unwrap();
+        let unicode = RegexBuilder::new("^[[:alpha:]]+$")
+            .unicode(true)
+            .build()
+            .unwrap();
+
+        assert!(!ascii.matchea("ñandú"));
+        assert!(unicode.matchea("ñandú"));
+        assert!(!unicode.matchea("ñandú1"));
+    }
+
+    #[test]
+    fn repeticiones_no_definidas_por_posix() {
+        let permisiva = RegexBuilder::new("^*a{2}{2}|+b").build().unwrap();
+
+        assert!(permisiva.matchea("*aaaa"));
+        assert!(!permisiva.matchea("*aaa"));
+        assert!(permisiva.matchea("+b"));
+        assert!(!permisiva.matchea("b"));
+        assert!(RegexBuilder::new("a{2}{2}").strict(true).build().is_err());
+        assert!(RegexBuilder::new("+b").strict(true).build().is_err());
+        assert!(RegexBuilder::new("*a")
+            .syntax(Syntax::Bre)
+            .strict(true)
+            .build()
+            .unwrap()
+            .matchea("*a"));
+    }
+
+    #[test]
+    fn opciones_de_coincidencia() {
+        let regex = RegexBuilder::new("^a.b$")
+            .multi_line(true)
+            .dot_matches_new_line(false)
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+
+        assert!(regex.matchea("x\nA-B\ny"));
+        assert!(!regex.matchea("a\nb"));
+        assert!(!regex.matchea("xa-b"));
+        assert!(!RegexBuilder::new("a.b").build().unwrap().matchea("a\nb"));
+        assert!(RegexBuilder::new("a.b")
+            .dot_matches_new_line(true)
+            .build()
+            .unwrap()
+            .matchea("a\nb"));
+
+        let registros = RegexBuilder::new("^a.b$")
+            .multi_line(true)
+            .line_terminator(0)
+            .build()
+            .unwrap();
+
+        assert!(registros.matchea("x\0a\nb\0y"));
+        assert!(!registros.matchea("a\0b"));
+        assert!(!registros.matchea("x\na-b"));
+    }
+
+    #[test]
+    fn limite_de_tamano_y_motor() {
+        let error = RegexBuilder::new("(a|b){1000}")
+            .size_limit(1000)
+            .build()
+            .unwrap_err();
+
+        assert_eq!(
+            error.tipo(),
+            &TipoError::Regex(ErrorRegex::LimiteDeTamanoExcedido(1000))
+        );
+        assert!(RegexBuilder::new("(a|b){10}")
+            .size_limit(1000)
+            .build()
+            .is_ok());
+        assert_eq!(
+            RegexBuilder::new("a{1000}{1000}{1000}{1000}{1000}{1000}{1000}")
+                .size_limit(1_000_000)
+                .build()
+                .unwrap_err()
+                .tipo(),
+            &TipoError::Regex(ErrorRegex::LimiteDeTamanoExcedido(1_000_000))
+        );
+        assert_eq!(
+            Regex::new("a{1000}{1000}{1000}").unwrap_err().tipo(),
+            &TipoError::Regex(ErrorRegex::LimiteDeTamanoExcedido(
+                LIMITE_TAMANO_POR_DEFECTO
+            ))
+        );
+        assert_eq!(
+            RegexBuilder::new(r"(a)\1")
+                .engine(Engine::Automata)
+                .build()
+                .unwrap_err()
+                .tipo(),
+            &TipoError::Regex(ErrorRegex::MotorIncompatible)
+        );
+
+        let backtracking = RegexBuilder::new("a|ab")
+            .engine(Engine::Backtracking)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            backtracking.buscar(b"ab", 0, Delimitacion::Ninguna),
+            Some((0, 1))
+        );
+        assert_eq!(
+            Regex::new("a|ab")
+                .unwrap()
+                .buscar(b"ab", 0, Delimitacion::Ninguna),
+            Some((0, 2))
+        );
+    }
+}
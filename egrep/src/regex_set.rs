@@ -0,0 +1,170 @@
+use crate::{automata::Automata, error::Error, expresion::Expresion, regex::Regex};
+use std::iter;
+
+/// Conjunto de expresiones regulares que se evalúan a la vez.
+///
+/// Los patrones se compilan en un único [autómata][Automata], por lo que cada valor se recorre
+/// una única vez para saber cuáles de ellos matchean, en vez de una vez por patrón. Los patrones
+/// con referencias a grupos no pueden formar parte del autómata, por lo que se evalúan aparte.
+///
+/// Un conjunto puede convertirse en una única expresión regular que matchea si alguno de sus
+/// patrones matchea (ej.: para buscarlos con un [Searcher](crate::searcher::Searcher)).
+#[derive(Debug)]
+pub struct RegexSet {
+    automata: Automata,
+    sin_referencias: Vec<Expresion>,
+    con_referencias: Vec<(usize, Regex)>,
+    cantidad_patrones: usize,
+}
+
+impl RegexSet {
+    /// Creación de un conjunto de expresiones regulares recibiendo sus patrones. Cada patrón se
+    /// identifica por su índice en el orden recibido.
+    ///
+    /// # Errores
+    ///
+    /// Si alguno de los patrones es inválido, se devuelve el error que devolvería [Regex::new].
+    pub fn new<I, S>(patrones: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let regexes = patrones
+            .into_iter()
+            .map(|patron| Regex::new(patron.as_ref()))
+            .collect::<Result<Vec<Regex>, _>>()?;
+        let cantidad_patrones = regexes.len();
+        let (con_referencias, sin_referencias): (Vec<_>, Vec<_>) = regexes
+            .into_iter()
+            .enumerate()
+            .partition(|(_, regex)| regex.tiene_referencias());
+        let expresiones = sin_referencias.iter().flat_map(|(indice, regex)| {
+            regex
+                .expresiones()
+                .iter()
+                .map(move |expresion| (*indice, expresion))
+        });
+        let automata = Automata::new(expresiones, cantidad_patrones);
+        let sin_referencias = sin_referencias
+            .iter()
+            .flat_map(|(_, regex)| regex.expresiones().iter().cloned())
+            .collect();
+        Ok(Self {
+            automata,
+            sin_referencias,
+            con_referencias,
+            cantidad_patrones,
+        })
+    }
+
+    /// Devuelve la cantidad de patrones del conjunto.
+    pub fn len(&self) -> usize {
+        self.cantidad_patrones
+    }
+
+    /// Devuelve true si el conjunto no tiene patrones.
+    pub fn is_empty(&self) -> bool {
+        self.cantidad_patrones == 0
+    }
+
+    /// Devuelve true si alguno de los patrones matchea con un valor recibido como string slice.
+    pub fn matchea(&self, valor: &str) -> bool {
+        !self.patrones_que_matchean(valor).is_empty()
+    }
+
+    /// Devuelve los índices (ordenados) de los patrones que matchean con un valor recibido como
+    /// string slice.
+    pub fn patrones_que_matchean(&self, valor: &str) -> Vec<usize> {
+        self.patrones_que_matchean_bytes(valor.as_bytes())
+    }
+
+    /// Devuelve los índices (ordenados) de los patrones que matchean con un valor recibido como
+    /// slice de bytes, que no necesita ser utf8 válido.
+    pub fn patrones_que_matchean_bytes(&self, valor: &[u8]) -> Vec<usize> {
+        let mut patrones = self.automata.patrones_que_matchean(valor);
+        if !self.con_referencias.is_empty() {
+            patrones.extend(
+                self.con_referencias
+                    .iter()
+                    .filter(|(_, regex)| regex.is_match_bytes(valor))
+                    .map(|(indice, _)| *indice),
+            );
+            patrones.sort_unstable();
+        }
+        patrones
+    }
+}
+
+impl From<RegexSet> for Regex {
+    /// Alternancia de los patrones del conjunto (ver [Regex::alternar]).
+    fn from(conjunto: RegexSet) -> Self {
+        let sin_referencias = Regex::desde_expresiones(conjunto.sin_referencias, false);
+        Regex::alternar(
+            iter::once(sin_referencias)
+                .chain(conjunto.con_referencias.into_iter().map(|(_, regex)| regex)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_con_patron_invalido() {
+        let resultado = RegexSet::new(["hola", "[ab"]);
+
+        assert!(resultado.is_err());
+    }
+
+    #[test]
+    fn conjunto_vacio_no_matchea() {
+        let conjunto = RegexSet::new(Vec::<String>::new()).unwrap();
+
+        assert!(conjunto.is_empty());
+        assert!(!conjunto.matchea("hola"));
+    }
+
+    #[test]
+    fn patron_con_alternancia_se_indica_una_vez() {
+        let conjunto = RegexSet::new(["ab|cd", "x+y?"]).unwrap();
+
+        assert_eq!(conjunto.len(), 2);
+        assert_eq!(conjunto.patrones_que_matchean("abcd"), vec![0]);
+        assert_eq!(conjunto.patrones_que_matchean("zcdxx"), vec![0, 1]);
+    }
+
+    #[test]
+    fn coincide_con_cada_regex_por_separado() {
+        let patrones = ["^a.c", "b{2,3}$", "[^[:alpha:]]", "ñ?o*", "a[bc]+d"];
+        let conjunto = RegexSet::new(patrones).unwrap();
+        let valores = ["abc", "xbbb", "abbbbd", "123", "ñandú", "acbcd"];
+
+        for valor in valores {
+            let esperados: Vec<usize> = (0..patrones.len())
+                .filter(|i| Regex::new(patrones[*i]).unwrap().matchea(valor))
+                .collect();
+            assert_eq!(conjunto.patrones_que_matchean(valor), esperados);
+        }
+    }
+
+    #[test]
+    fn patrones_con_referencias_se_evaluan_aparte() {
+        let conjunto = RegexSet::new(["(a|b)\\1", "c", "([[:alpha:]]+) \\1"]).unwrap();
+
+        assert_eq!(conjunto.patrones_que_matchean("abba"), vec![0]);
+        assert_eq!(conjunto.patrones_que_matchean("ab c ab"), vec![1]);
+        assert_eq!(conjunto.patrones_que_matchean("c es es"), vec![1, 2]);
+        assert_eq!(conjunto.patrones_que_matchean("bb c c"), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn se_convierte_en_la_alternancia_de_sus_patrones() {
+        let regex = Regex::from(RegexSet::new(["b{2}", "(c)\\1", "^x"]).unwrap());
+
+        assert!(regex.matchea("abba"));
+        assert!(regex.matchea("acca"));
+        assert!(regex.matchea("xa"));
+        assert!(!regex.matchea("abcax"));
+    }
+}
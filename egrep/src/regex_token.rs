@@ -5,7 +5,7 @@ use crate::{repeticion_regex_token::RepeticionRegexToken, valor_regex_token::Val
 /// Posee un valor y una rango de repetición.
 ///
 /// El rango de repetición puede ser modificado una única vez.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct RegexToken {
     pub valor: ValorRegexToken,
     repeticion: RepeticionRegexToken,
@@ -30,6 +30,16 @@ impl RegexToken {
         }
     }
 
+    /// Creación de un token dado un valor y el rango de repetición que ya se le indicó, por lo que
+    /// su repetición no puede modificarse (ej.: el grupo atómico de una repetición posesiva).
+    pub fn repetido(valor: ValorRegexToken, repeticion: RepeticionRegexToken) -> Self {
+        Self {
+            valor,
+            repeticion,
+            repeticion_modificable: false,
+        }
+    }
+
     /// Modifica la repetición de un token, en caso de ser posible.
     ///
     /// Devuelve true o false, según si se pudo realizar la modificación.
@@ -52,6 +62,11 @@ impl RegexToken {
     pub fn max_repeticiones(&self) -> Option<usize> {
         self.repeticion.max()
     }
+
+    /// Devuelve true si la repetición es perezosa.
+    pub fn es_perezoso(&self) -> bool {
+        self.repeticion.es_perezosa()
+    }
 }
 
 #[cfg(test)]
@@ -98,4 +113,15 @@ mod tests {
 
         assert!(modifico);
     }
+
+    #[test]
+    fn no_puedo_modificar_repeticion_token_repetido() {
+        let repeticion = RepeticionRegexToken::new(Some(1), Some(1));
+        let mut token = RegexToken::repetido(ValorRegexToken::Comodin, repeticion);
+
+        let modifico = token.modificar_repeticion(RepeticionRegexToken::new(None, None));
+
+        assert!(!modifico);
+        assert_eq!(Some(1), token.max_repeticiones());
+    }
 }
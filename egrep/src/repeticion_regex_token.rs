@@ -4,16 +4,52 @@
 ///
 /// El mínimo (y el máximo) de este rango puede ser numérico o no existir.
 /// Si no existe (representado por None), significa que el rango no está limitado inferiormente (o superiormente).
-#[derive(Debug, PartialEq)]
+///
+/// Una repetición perezosa prefiere la menor cantidad de repeticiones posible, en vez de la mayor.
+/// Una repetición posesiva toma la mayor cantidad de repeticiones posible y no vuelve atrás.
+#[derive(Debug, PartialEq, Clone)]
 pub struct RepeticionRegexToken {
     min: Option<usize>,
     max: Option<usize>,
+    perezosa: bool,
+    posesiva: bool,
 }
 
 impl RepeticionRegexToken {
-    /// Creación de una repetición con un mínimo y un máximo.
+    /// Creación de una repetición (ni perezosa ni posesiva) con un mínimo y un máximo.
     pub fn new(min: Option<usize>, max: Option<usize>) -> Self {
-        Self { min, max }
+        Self {
+            min,
+            max,
+            perezosa: false,
+            posesiva: false,
+        }
+    }
+
+    /// Devuelve la misma repetición, pero perezosa.
+    pub fn perezosa(self) -> Self {
+        Self {
+            perezosa: true,
+            ..self
+        }
+    }
+
+    /// Devuelve true si la repetición es perezosa.
+    pub fn es_perezosa(&self) -> bool {
+        self.perezosa
+    }
+
+    /// Devuelve la misma repetición, pero posesiva.
+    pub fn posesiva(self) -> Self {
+        Self {
+            posesiva: true,
+            ..self
+        }
+    }
+
+    /// Devuelve true si la repetición es posesiva.
+    pub fn es_posesiva(&self) -> bool {
+        self.posesiva
     }
 
     /// Devuelve el mínimo de la repetición.
@@ -28,7 +64,6 @@ impl RepeticionRegexToken {
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
 
@@ -38,7 +73,15 @@ mod tests {
         let max = Some(1);
         let rep = RepeticionRegexToken::new(min, max);
 
-        assert_eq!(RepeticionRegexToken { min, max }, rep);
+        assert_eq!(
+            RepeticionRegexToken {
+                min,
+                max,
+                perezosa: false,
+                posesiva: false
+            },
+            rep
+        );
     }
 
     #[test]
@@ -0,0 +1,488 @@
+use crate::{
+    argumentos::Argumentos,
+    codificacion::LectorDecodificado,
+    delimitacion::Delimitacion,
+    egrep::{egrep_lineas, Buscador, LineaResultado},
+    error_busqueda::ErrorBusqueda,
+    error_lectura_archivos::ErrorArchivo,
+    lectura_archivos::{abrir_lineas_archivo, LectorLineas, Linea, OpcionesLectura},
+};
+use std::{
+    error,
+    io::{self, BufReader, Read},
+    iter,
+};
+
+/// Estadísticas de una búsqueda en un archivo: la cantidad de líneas seleccionadas y de líneas
+/// de contexto entregadas, y la cantidad de coincidencias en las líneas seleccionadas.
+///
+/// Las coincidencias solo se cuentan si la búsqueda calcula sus posiciones (ver
+/// [OpcionesBusqueda::calcula_coincidencias]). Si no, su cantidad es None.
+#[derive(Debug, PartialEq, Default, Clone, Copy)]
+pub struct EstadisticasBusqueda {
+    pub lineas_seleccionadas: usize,
+    pub lineas_de_contexto: usize,
+    pub coincidencias: Option<usize>,
+}
+
+/// Receptor de los resultados de una búsqueda (ver [Searcher]), que decide qué hacer con ellos:
+/// imprimirlos (como la [impresora](crate::impresora::Impresora) de egrep), guardarlos, etc.
+///
+/// Cada línea se recibe con su número de línea, la posición de su inicio en el contenido del
+/// archivo (ya transcodificado a utf8, si corresponde) y la línea en sí. Las líneas seleccionadas
+/// y sus líneas de contexto se reciben en el orden en el que aparecen en el archivo.
+pub trait Sink {
+    /// Recibe una línea seleccionada, junto con las posiciones de inicio y fin de sus
+    /// coincidencias en su contenido. Devuelve si se debe seguir buscando en el archivo.
+    ///
+    /// Si se deben reemplazar las coincidencias, el contenido de la línea es el que resulta de
+    /// reemplazarlas, y las posiciones son las de los reemplazos. Las líneas seleccionadas por
+    /// no matchear (si se debe invertir la selección) no tienen coincidencias.
+    fn matched(
+        &mut self,
+        numero_linea: usize,
+        desplazamiento: usize,
+        linea: &Linea,
+        coincidencias: &[(usize, usize)],
+    ) -> io::Result<bool>;
+
+    /// Recibe una línea de contexto de alguna línea seleccionada. Devuelve si se debe seguir
+    /// buscando en el archivo. Por defecto, las líneas de contexto se ignoran.
+    fn context(
+        &mut self,
+        _numero_linea: usize,
+        _desplazamiento: usize,
+        _linea: &Linea,
+    ) -> io::Result<bool> {
+        Ok(true)
+    }
+
+    /// Recibe las estadísticas de la búsqueda al terminar, aunque esta se haya interrumpido.
+    /// Por defecto, no hace nada.
+    fn finish(&mut self, _estadisticas: &EstadisticasBusqueda) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Opciones con las que un [Searcher] selecciona las líneas de un archivo:
+///
+/// * delimitacion: la que deben cumplir las coincidencias (palabras o líneas completas).
+/// * invertir: si se seleccionan las líneas que no matchean.
+/// * maximo_seleccionadas: la cantidad máxima de líneas a seleccionar, o None si no hay máximo.
+/// * contexto_anterior y contexto_posterior: la cantidad de líneas de contexto que se entregan
+///   antes y después de cada línea seleccionada.
+/// * multilinea: si los patrones se evalúan sobre el contenido completo del archivo, con sus
+///   terminadores. Se seleccionan las líneas que toca alguna coincidencia.
+/// * solo_coincidencias: en modo multilínea, si se entregan bloques de las líneas tocadas por
+///   coincidencias (con las coincidencias completas) en vez de cada línea tocada.
+/// * con_coincidencias: si se calculan las posiciones de las coincidencias de cada línea
+///   seleccionada. Si no, las líneas se entregan sin coincidencias (salvo los reemplazos).
+/// * reemplazo: la plantilla por la que se reemplazan las coincidencias de las líneas
+///   seleccionadas (ver [Capturas::expandir](crate::capturas::Capturas::expandir)).
+/// * lectura: las opciones con las que se leen las líneas de los archivos.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct OpcionesBusqueda {
+    pub delimitacion: Delimitacion,
+    pub invertir: bool,
+    pub maximo_seleccionadas: Option<usize>,
+    pub contexto_anterior: usize,
+    pub contexto_posterior: usize,
+    pub multilinea: bool,
+    pub solo_coincidencias: bool,
+    pub con_coincidencias: bool,
+    pub reemplazo: Option<String>,
+    pub lectura: OpcionesLectura,
+}
+
+impl OpcionesBusqueda {
+    /// Adaptación de los argumentos de egrep a las opciones de la búsqueda.
+    ///
+    /// Si se deben contar las líneas seleccionadas o listar archivos, no se entregan líneas de
+    /// contexto ni bloques de coincidencias, y al listar archivos solo se selecciona una línea.
+    /// Las posiciones de las coincidencias solo se calculan si se deben imprimir solo las
+    /// coincidencias, y las líneas de contexto solo se entregan si se imprimen líneas completas.
+    pub fn desde_argumentos(argumentos: &Argumentos) -> Self {
+        let resumen = argumentos.contar || argumentos.listar_archivos.is_some();
+        let (contexto_anterior, contexto_posterior) = if resumen || argumentos.solo_coincidencias {
+            (0, 0)
+        } else {
+            (argumentos.contexto_anterior, argumentos.contexto_posterior)
+        };
+        Self {
+            delimitacion: argumentos.delimitacion,
+            invertir: argumentos.invertir,
+            maximo_seleccionadas: match argumentos.listar_archivos {
+                Some(_) => Some(1),
+                None => argumentos.maximo_seleccionadas,
+            },
+            contexto_anterior,
+            contexto_posterior,
+            multilinea: argumentos.multilinea,
+            solo_coincidencias: argumentos.solo_coincidencias && !resumen,
+            con_coincidencias: argumentos.solo_coincidencias,
+            reemplazo: argumentos.reemplazo.clone(),
+            lectura: argumentos.lectura,
+        }
+    }
+
+    /// Devuelve true si se calculan las posiciones de las coincidencias de las líneas
+    /// seleccionadas: si se piden, si se deben reemplazar o si se entregan bloques de
+    /// coincidencias en modo multilínea.
+    pub fn calcula_coincidencias(&self) -> bool {
+        self.con_coincidencias
+            || self.reemplazo.is_some()
+            || self.multilinea && self.solo_coincidencias
+    }
+}
+
+/// Buscador de los patrones de una expresión regular o de un [buscador de
+/// cadenas](crate::busqueda_cadenas::BuscadorCadenas), que entrega las líneas seleccionadas de cada archivo y sus líneas
+/// de contexto a un [Sink] a medida que las encuentra, sin imprimir nada.
+///
+/// Las líneas se seleccionan según las [opciones de la búsqueda](OpcionesBusqueda), como en
+/// [egrep_bytes](crate::egrep::egrep_bytes). En modo multilínea, la expresión regular se evalúa
+/// sobre el archivo completo, por lo que sus anclajes solo matchean en cada línea si se creó con
+/// [multi_line](crate::regex_builder::RegexBuilder::multi_line).
+///
+/// ```
+/// use egrep::lectura_archivos::Linea;
+/// use egrep::regex::Regex;
+/// use egrep::searcher::{OpcionesBusqueda, Searcher, Sink};
+/// use std::io;
+///
+/// struct Numeros(Vec<usize>);
+///
+/// impl Sink for Numeros {
+///     fn matched(&mut self, numero: usize, _: usize, _: &Linea, _: &[(usize, usize)]) -> io::Result<bool> {
+///         self.0.push(numero);
+///         Ok(true)
+///     }
+/// }
+///
+/// let opciones = OpcionesBusqueda {
+///     con_coincidencias: true,
+///     ..OpcionesBusqueda::default()
+/// };
+/// let searcher = Searcher::new(Regex::new("b+").unwrap(), opciones);
+/// let mut numeros = Numeros(Vec::new());
+/// let estadisticas = searcher.search_reader(&b"abc\nxyz\nbb\n"[..], "texto", &mut numeros).unwrap();
+///
+/// assert_eq!(numeros.0, vec![1, 3]);
+/// assert_eq!(estadisticas.coincidencias, Some(2));
+/// ```
+pub struct Searcher {
+    buscador: Buscador,
+    opciones: OpcionesBusqueda,
+}
+
+impl Searcher {
+    /// Creación del buscador a partir de una [expresión regular](crate::regex::Regex), un
+    /// [conjunto de expresiones regulares](crate::regex_set::RegexSet) o un
+    /// [buscador de cadenas](crate::busqueda_cadenas::BuscadorCadenas), con las opciones de la búsqueda.
+    pub fn new(buscador: impl Into<Buscador>, opciones: OpcionesBusqueda) -> Self {
+        Self {
+            buscador: buscador.into(),
+            opciones,
+        }
+    }
+
+    /// Creación del buscador de los patrones de los argumentos de egrep, con las
+    /// [opciones](OpcionesBusqueda::desde_argumentos) que les corresponden.
+    ///
+    /// # Errores
+    ///
+    /// Si algún archivo de patrones no se puede leer o algún patrón es inválido, se devuelve su error.
+    pub fn desde_argumentos(argumentos: &Argumentos) -> Result<Self, Box<dyn error::Error>> {
+        Ok(Self::new(
+            Buscador::desde_argumentos(argumentos)?,
+            OpcionesBusqueda::desde_argumentos(argumentos),
+        ))
+    }
+
+    /// Búsqueda en un archivo, transcodificando su contenido a utf8 según las opciones de
+    /// lectura. Devuelve las estadísticas de la búsqueda.
+    ///
+    /// # Errores
+    ///
+    /// Si el archivo no existe o alguna de sus líneas no se puede leer, o si el receptor no pudo
+    /// procesar alguna línea, se devuelve el error correspondiente.
+    pub fn search_path(
+        &self,
+        archivo: &str,
+        sink: &mut impl Sink,
+    ) -> Result<EstadisticasBusqueda, ErrorBusqueda> {
+        // Si no se debe seleccionar ninguna línea, no se abre el archivo.
+        if self.opciones.maximo_seleccionadas == Some(0) {
+            return self.buscar_lineas(iter::empty(), sink);
+        }
+        let lineas = abrir_lineas_archivo(archivo, &self.opciones.lectura)?;
+        self.buscar_lineas(lineas, sink)
+    }
+
+    /// Búsqueda en el contenido de un lector (ej.: la entrada estándar), transcodificándolo a
+    /// utf8 según las opciones de lectura. El nombre solo se usa en los errores de lectura.
+    /// Devuelve las estadísticas de la búsqueda.
+    ///
+    /// # Errores
+    ///
+    /// Si alguna línea no se puede leer, o si el receptor no pudo procesar alguna línea, se
+    /// devuelve el error correspondiente.
+    pub fn search_reader(
+        &self,
+        lector: impl Read,
+        nombre: &str,
+        sink: &mut impl Sink,
+    ) -> Result<EstadisticasBusqueda, ErrorBusqueda> {
+        let lectura = &self.opciones.lectura;
+        let lector = BufReader::new(LectorDecodificado::new(lector, lectura.codificacion));
+        self.buscar_lineas(LectorLineas::new(lector, nombre, lectura), sink)
+    }
+
+    fn buscar_lineas(
+        &self,
+        lineas: impl Iterator<Item = Result<Linea, ErrorArchivo>>,
+        sink: &mut impl Sink,
+    ) -> Result<EstadisticasBusqueda, ErrorBusqueda> {
+        let mut estadisticas = EstadisticasBusqueda {
+            coincidencias: self.opciones.calcula_coincidencias().then_some(0),
+            ..EstadisticasBusqueda::default()
+        };
+        egrep_lineas(&self.buscador, lineas, &self.opciones, |linea_resultado| {
+            entregar_linea(&linea_resultado, &mut estadisticas, sink)
+                .map_err(ErrorBusqueda::Receptor)
+        })?;
+        sink.finish(&estadisticas)
+            .map_err(ErrorBusqueda::Receptor)?;
+        Ok(estadisticas)
+    }
+}
+
+/// Entrega una línea resultado a un receptor (como línea seleccionada o de contexto) y la suma a
+/// las estadísticas de la búsqueda. Devuelve si se debe seguir buscando.
+fn entregar_linea(
+    linea_resultado: &LineaResultado,
+    estadisticas: &mut EstadisticasBusqueda,
+    sink: &mut impl Sink,
+) -> io::Result<bool> {
+    let LineaResultado {
+        linea,
+        numero,
+        desplazamiento,
+        seleccionada,
+        coincidencias,
+    } = linea_resultado;
+    if *seleccionada {
+        estadisticas.lineas_seleccionadas += 1;
+        if let Some(cantidad) = &mut estadisticas.coincidencias {
+            *cantidad += coincidencias.len();
+        }
+        sink.matched(*numero, *desplazamiento, linea, coincidencias)
+    } else {
+        estadisticas.lineas_de_contexto += 1;
+        sink.context(*numero, *desplazamiento, linea)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::busqueda_cadenas::BuscadorCadenas;
+    use crate::regex_set::RegexSet;
+
+    /// Línea recibida: si es seleccionada, su número, su desplazamiento, su contenido y sus
+    /// coincidencias.
+    type LineaRecibida = (bool, usize, usize, Vec<u8>, Vec<(usize, usize)>);
+
+    /// Receptor que guarda cada línea que recibe.
+    #[derive(Default)]
+    struct Receptor {
+        lineas: Vec<LineaRecibida>,
+        limite: Option<usize>,
+        estadisticas: Option<EstadisticasBusqueda>,
+    }
+
+    impl Sink for Receptor {
+        fn matched(
+            &mut self,
+            numero_linea: usize,
+            desplazamiento: usize,
+            linea: &Linea,
+            coincidencias: &[(usize, usize)],
+        ) -> io::Result<bool> {
+            self.lineas.push((
+                true,
+                numero_linea,
+                desplazamiento,
+                linea.contenido.clone(),
+                coincidencias.to_vec(),
+            ));
+            Ok(self.limite.is_none_or(|limite| self.lineas.len() < limite))
+        }
+
+        fn context(
+            &mut self,
+            numero_linea: usize,
+            desplazamiento: usize,
+            linea: &Linea,
+        ) -> io::Result<bool> {
+            self.lineas.push((
+                false,
+                numero_linea,
+                desplazamiento,
+                linea.contenido.clone(),
+                Vec::new(),
+            ));
+            Ok(true)
+        }
+
+        fn finish(&mut self, estadisticas: &EstadisticasBusqueda) -> io::Result<()> {
+            self.estadisticas = Some(*estadisticas);
+            Ok(())
+        }
+    }
+
+    fn buscar(
+        opciones: &[&str],
+        contenido: &[u8],
+        receptor: &mut Receptor,
+    ) -> EstadisticasBusqueda {
+        let args: Vec<String> = iter::once(&"egrep")
+            .chain(opciones)
+            .chain(&["texto"])
+            .map(|a| a.to_string())
+            .collect();
+        let argumentos = Argumentos::new(&args).unwrap();
+        let opciones = OpcionesBusqueda {
+            con_coincidencias: true,
+            ..OpcionesBusqueda::desde_argumentos(&argumentos)
+        };
+        Searcher::new(Buscador::desde_argumentos(&argumentos).unwrap(), opciones)
+            .search_reader(contenido, "texto", receptor)
+            .unwrap()
+    }
+
+    #[test]
+    fn lineas_seleccionadas_y_de_contexto_con_posiciones() {
+        let mut receptor = Receptor::default();
+        let estadisticas = buscar(&["-A1", "b"], b"ab b\nxy\nz\r\nb", &mut receptor);
+
+        assert_eq!(
+            receptor.lineas,
+            vec![
+                (true, 1, 0, b"ab b".to_vec(), vec![(1, 2), (3, 4)]),
+                (false, 2, 5, b"xy".to_vec(), vec![]),
+                (true, 4, 11, b"b".to_vec(), vec![(0, 1)]),
+            ]
+        );
+        let esperadas = EstadisticasBusqueda {
+            lineas_seleccionadas: 2,
+            lineas_de_contexto: 1,
+            coincidencias: Some(3),
+        };
+        assert_eq!(estadisticas, esperadas);
+        assert_eq!(receptor.estadisticas, Some(esperadas));
+    }
+
+    #[test]
+    fn el_receptor_puede_interrumpir_la_busqueda() {
+        let mut receptor = Receptor {
+            limite: Some(1),
+            ..Receptor::default()
+        };
+        let estadisticas = buscar(&["a"], b"a\nb\na\n", &mut receptor);
+
+        assert_eq!(receptor.lineas.len(), 1);
+        assert_eq!(estadisticas.lineas_seleccionadas, 1);
+        assert_eq!(receptor.estadisticas, Some(estadisticas));
+    }
+
+    #[test]
+    fn coincidencias_multilinea_recortadas_a_cada_linea() {
+        let mut receptor = Receptor::default();
+        buscar(&["-U", "c\\nd"], b"abc\nde\n", &mut receptor);
+
+        assert_eq!(
+            receptor.lineas,
+            vec![
+                (true, 1, 0, b"abc".to_vec(), vec![(2, 3)]),
+                (true, 2, 4, b"de".to_vec(), vec![(0, 1)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn opciones_segun_los_argumentos() {
+        let opciones = |opciones: &[&str]| {
+            let args: Vec<String> = iter::once(&"egrep")
+                .chain(opciones)
+                .chain(&["a", "texto"])
+                .map(|a| a.to_string())
+                .collect();
+            OpcionesBusqueda::desde_argumentos(&Argumentos::new(&args).unwrap())
+        };
+
+        assert_eq!(opciones(&[]), OpcionesBusqueda::default());
+        assert_eq!(
+            opciones(&["-C2", "-x"]),
+            OpcionesBusqueda {
+                delimitacion: Delimitacion::Linea,
+                contexto_anterior: 2,
+                contexto_posterior: 2,
+                ..OpcionesBusqueda::default()
+            }
+        );
+        let solo_coincidencias = opciones(&["-o", "-A1"]);
+        assert!(solo_coincidencias.con_coincidencias);
+        assert_eq!(solo_coincidencias.contexto_posterior, 0);
+        let listar = opciones(&["-l", "-o", "-m5"]);
+        assert_eq!(listar.maximo_seleccionadas, Some(1));
+        assert!(!listar.solo_coincidencias);
+    }
+
+    #[test]
+    fn sin_coincidencias_si_no_se_piden() {
+        let mut receptor = Receptor::default();
+        let searcher = Searcher::new(
+            BuscadorCadenas::new(["b"], false),
+            OpcionesBusqueda::default(),
+        );
+        let estadisticas = searcher
+            .search_reader(&b"ab b\nxy\n"[..], "texto", &mut receptor)
+            .unwrap();
+
+        assert_eq!(
+            receptor.lineas,
+            vec![(true, 1, 0, b"ab b".to_vec(), vec![])]
+        );
+        assert_eq!(estadisticas.coincidencias, None);
+    }
+
+    #[test]
+    fn busca_los_patrones_de_un_conjunto() {
+        let mut receptor = Receptor::default();
+        let opciones = OpcionesBusqueda {
+            reemplazo: Some("<$0>".to_string()),
+            ..OpcionesBusqueda::default()
+        };
+        let conjunto = RegexSet::new(["b+", r"(x)\1"]).unwrap();
+        let estadisticas = Searcher::new(conjunto, opciones)
+            .search_reader(
+                &b"abb b
+xy
+xx
+"[..],
+                "texto",
+                &mut receptor,
+            )
+            .unwrap();
+
+        assert_eq!(
+            receptor.lineas,
+            vec![
+                (true, 1, 0, b"a<bb> <b>".to_vec(), vec![(1, 5), (6, 9)]),
+                (true, 3, 9, b"<xx>".to_vec(), vec![(0, 4)]),
+            ]
+        );
+        assert_eq!(estadisticas.coincidencias, Some(3));
+    }
+}
@@ -0,0 +1,26 @@
+/// Sintaxis con la que se interpreta el patrón de una expresión regular.
+///
+/// * Bre: expresiones regulares básicas (como grep). Los metacaracteres `(`, `)`, `{`, `}`, `|`,
+///   `+` y `?` son literales, salvo precedidos por una barra invertida (ej.: `\(`, `\{n,m\}`).
+///   `*` es literal al inicio de una expresión (o luego del `^` inicial), `^` solo es un anclaje
+///   al inicio de una expresión y `$` solo al final.
+///
+/// * Ere: expresiones regulares extendidas (como egrep). Los metacaracteres no necesitan barra
+///   invertida, y precedidos por una son literales.
+///
+/// * Perl: expresiones regulares compatibles con Perl. Como las extendidas, y además admite
+///   repeticiones perezosas (`*?`, `+?`, `??`, `{n,m}?`), que prefieren matchear lo menos posible,
+///   anticipaciones (`(?=...)`, `(?!...)`) y retrospecciones de largo acotado (`(?<=...)`,
+///   `(?<!...)`), que no consumen caracteres. Sus grupos pueden tener nombre (`(?<nombre>...)`
+///   o `(?P<nombre>...)`), que se usa al reemplazar coincidencias, o no capturar (`(?:...)`).
+///   Cualquier otro grupo que comience con `(?` (ej.: `(?i)`) es un error.
+///
+/// Todas admiten referencias a grupos (`\1` a `\9`), que matchean con lo mismo que matcheó el
+/// grupo (en las extendidas, como extensión de GNU).
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Syntax {
+    Bre,
+    #[default]
+    Ere,
+    Perl,
+}
@@ -1,18 +1,30 @@
-use crate::clase_caracter::ClaseCaracter;
+use crate::{
+    asercion::Asercion, clase_caracter::ClaseCaracter,
+    decodificacion_utf8::decodificar_primer_caracter, expresion::Expresion,
+};
 
 /// Representación de un valor de un token de una expresión regular.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum ValorRegexToken {
     Literal(char),
+    Byte(u8),
     Comodin,
     AnclajeInicio,
     AnclajeFinal,
+    Asercion(Asercion),
     ExpresionBracket(Vec<ClaseCaracter>),
     ExpresionBracketNegada(Vec<ClaseCaracter>),
+    Grupo(Option<usize>, Vec<Expresion>),
+    GrupoAtomico(Vec<Expresion>),
+    Referencia(usize, bool),
+    Anticipacion(Vec<Expresion>),
+    AnticipacionNegada(Vec<Expresion>),
+    Retrospeccion(Vec<Expresion>),
+    RetrospeccionNegada(Vec<Expresion>),
 }
 
 impl ValorRegexToken {
-    /// Matcheo de un valor de un token con otro valor, recibido como string slice.
+    /// Matcheo de un valor de un token con otro valor, recibido como slice de bytes.
     ///
     /// En todos los casos, se matchea con el primer caracter del valor evaluado.
     /// Si el valor evaluado está vacío, se devuelve 0.
@@ -20,56 +32,249 @@ impl ValorRegexToken {
     /// Si se matchea con el caracter evaluado, se devuelve su largo en utf8.
     /// Caso contrario, se devuelve 0.
     ///
+    /// Si el valor evaluado no comienza con un caracter utf8 válido, su primer byte
+    /// se considera un caracter de largo 1.
+    ///
     /// # Matcheos:
     ///
-    /// * Un literal matchea con un caracter si son iguales.
+    /// * Un literal matchea con un caracter si son iguales (se compara su codificación utf8).
+    ///
+    /// * Un byte (que no forma parte de un caracter utf8 válido en el patrón) matchea con ese
+    ///   mismo byte.
     ///
-    /// * Un comodín matchea con cualquier caracter.
+    /// * Un comodín matchea con cualquier caracter, incluso con un byte inválido.
     ///
-    /// * Un token de anclaje no matchea con ningún caracter.
+    /// * Un token de anclaje o de aserción no matchea con ningún caracter.
     ///
     /// * Una expresión bracket matchea con un caracter si este matchea con alguna de las
     ///   clases de caracter de la expresión.
     ///
     /// * Una expresión bracket negada matchea con un caracter si este no matchea con ninguna de las
-    ///   clases de caracter de la expresión.
-    pub fn matchea(&self, valor: &str) -> usize {
+    ///   clases de caracter de la expresión. Un byte inválido no pertenece a ninguna clase, por lo
+    ///   que siempre matchea.
+    ///
+    /// * Un grupo (con su número, si captura lo que matchea, y sus expresiones alternativas), un
+    ///   grupo atómico (que no vuelve atrás en lo que matcheó) y una
+    ///   referencia a un grupo (con su número, y si ignora mayúsculas al comparar con lo que
+    ///   matcheó el grupo) no matchean con un único caracter, por lo que devuelven 0: se evalúan al matchear la
+    ///   expresión completa.
+    ///
+    /// * Una anticipación o una retrospección (con sus expresiones alternativas), al igual que un
+    ///   anclaje, no consume caracteres: se cumple si sus expresiones matchean (o no, si es
+    ///   negada) a partir de la posición evaluada o hasta ella. Devuelve 0, y se evalúa al
+    ///   matchear la expresión completa.
+    pub fn matchea(&self, valor: &[u8]) -> usize {
         match self {
             Self::Literal(l) => matchear_con_literal(*l, valor),
+            Self::Byte(b) => usize::from(valor.first() == Some(b)),
             Self::Comodin => matchear_con_comodin(valor),
             Self::AnclajeInicio => matchear_con_anclaje(),
             Self::AnclajeFinal => matchear_con_anclaje(),
+            Self::Asercion(_) => matchear_con_anclaje(),
             Self::ExpresionBracket(clases_caracter) => {
                 matchear_con_expresion_bracket(clases_caracter, valor)
             }
             Self::ExpresionBracketNegada(clases_caracter) => {
                 matchear_con_expresion_bracket_negada(clases_caracter, valor)
             }
+            Self::Grupo(_, _)
+            | Self::GrupoAtomico(_)
+            | Self::Referencia(..)
+            | Self::Anticipacion(_)
+            | Self::AnticipacionNegada(_)
+            | Self::Retrospeccion(_)
+            | Self::RetrospeccionNegada(_) => 0,
+        }
+    }
+
+    /// Devuelve las expresiones alternativas que contiene un grupo, una anticipación o una
+    /// retrospección. Los demás valores no contienen expresiones.
+    pub fn expresiones(&self) -> &[Expresion] {
+        match self {
+            Self::Grupo(_, expresiones)
+            | Self::GrupoAtomico(expresiones)
+            | Self::Anticipacion(expresiones)
+            | Self::AnticipacionNegada(expresiones)
+            | Self::Retrospeccion(expresiones)
+            | Self::RetrospeccionNegada(expresiones) => expresiones,
+            _ => &[],
+        }
+    }
+
+    /// Devuelve el largo máximo en bytes de una única repetición del valor, o None si no está
+    /// acotado (como el de una referencia a un grupo).
+    pub fn largo_maximo(&self) -> Option<usize> {
+        match self {
+            Self::Literal(l) => Some(l.len_utf8()),
+            Self::Byte(_) => Some(1),
+            Self::Comodin | Self::ExpresionBracket(_) | Self::ExpresionBracketNegada(_) => {
+                Some(char::MAX.len_utf8())
+            }
+            Self::Grupo(_, expresiones) | Self::GrupoAtomico(expresiones) => expresiones
+                .iter()
+                .map(Expresion::largo_maximo)
+                .try_fold(0, |maximo, largo| Some(maximo.max(largo?))),
+            Self::Referencia(..) => None,
+            _ => Some(0),
+        }
+    }
+
+    /// Devuelve un valor equivalente en modo multilínea: los anclajes de inicio y final se
+    /// convierten en las [aserciones][Asercion] de inicio y final de las líneas separadas por un
+    /// terminador.
+    pub fn multilinea(self, terminador: u8) -> Self {
+        match self {
+            Self::AnclajeInicio => Self::Asercion(Asercion::InicioLinea(terminador)),
+            Self::AnclajeFinal => Self::Asercion(Asercion::FinLinea(terminador)),
+            valor => valor.transformar_expresiones(&|e: Expresion| e.multilinea(terminador)),
+        }
+    }
+
+    /// Devuelve un valor equivalente en el que un comodín no matchea con un terminador de línea.
+    pub fn comodin_sin_terminador(self, terminador: u8) -> Self {
+        match self {
+            Self::Comodin => {
+                Self::ExpresionBracketNegada(vec![ClaseCaracter::Literal(char::from(terminador))])
+            }
+            valor => {
+                valor.transformar_expresiones(&|e: Expresion| e.comodin_sin_terminador(terminador))
+            }
+        }
+    }
+
+    /// Devuelve un valor equivalente con cada una de sus expresiones (las de un grupo, una
+    /// anticipación o una retrospección) transformada por una función.
+    fn transformar_expresiones(self, f: &dyn Fn(Expresion) -> Expresion) -> Self {
+        let transformar = |expresiones: Vec<Expresion>| expresiones.into_iter().map(f).collect();
+        match self {
+            Self::Grupo(numero, expresiones) => Self::Grupo(numero, transformar(expresiones)),
+            Self::GrupoAtomico(expresiones) => Self::GrupoAtomico(transformar(expresiones)),
+            Self::Anticipacion(expresiones) => Self::Anticipacion(transformar(expresiones)),
+            Self::AnticipacionNegada(expresiones) => {
+                Self::AnticipacionNegada(transformar(expresiones))
+            }
+            Self::Retrospeccion(expresiones) => Self::Retrospeccion(transformar(expresiones)),
+            Self::RetrospeccionNegada(expresiones) => {
+                Self::RetrospeccionNegada(transformar(expresiones))
+            }
+            valor => valor,
+        }
+    }
+
+    /// Devuelve un valor equivalente en modo Unicode: las clases de caracter de las expresiones
+    /// bracket se convierten en [sus equivalentes][ClaseCaracter::unicode].
+    pub fn unicode(self) -> Self {
+        let unicode = |clases_caracter: Vec<ClaseCaracter>| {
+            clases_caracter
+                .into_iter()
+                .flat_map(ClaseCaracter::unicode)
+                .collect()
+        };
+        match self {
+            Self::ExpresionBracket(clases_caracter) => {
+                Self::ExpresionBracket(unicode(clases_caracter))
+            }
+            Self::ExpresionBracketNegada(clases_caracter) => {
+                Self::ExpresionBracketNegada(unicode(clases_caracter))
+            }
+            valor => valor.transformar_expresiones(&Expresion::unicode),
+        }
+    }
+
+    /// Devuelve un valor equivalente que no distingue mayúsculas de minúsculas.
+    ///
+    /// Un literal con variantes de mayúscula o minúscula se convierte en una expresión bracket
+    /// con todas ellas. En las expresiones bracket, se agregan las variantes de cada literal y
+    /// las clases de minúsculas y mayúsculas se convierten en la clase de caracteres alfabéticos.
+    /// Una referencia a un grupo pasa a ignorar mayúsculas al comparar con lo que matcheó este.
+    pub fn ignorar_mayusculas(self) -> Self {
+        match self {
+            Self::Literal(l) => {
+                let variantes = variantes_mayusculas(l);
+                if variantes.len() > 1 {
+                    Self::ExpresionBracket(
+                        variantes.into_iter().map(ClaseCaracter::Literal).collect(),
+                    )
+                } else {
+                    Self::Literal(l)
+                }
+            }
+            Self::ExpresionBracket(clases_caracter) => {
+                Self::ExpresionBracket(clases_ignorando_mayusculas(clases_caracter))
+            }
+            Self::ExpresionBracketNegada(clases_caracter) => {
+                Self::ExpresionBracketNegada(clases_ignorando_mayusculas(clases_caracter))
+            }
+            Self::Referencia(numero, _) => Self::Referencia(numero, true),
+            valor => valor.transformar_expresiones(&Expresion::ignorar_mayusculas),
         }
     }
 }
 
-fn matchear_con_literal(literal: char, valor: &str) -> usize {
-    if valor.starts_with(literal) {
-        literal.len_utf8()
-    } else {
-        0
+/// Devuelve un caracter junto con sus variantes de minúscula y mayúscula (solo las que son un
+/// único caracter).
+fn variantes_mayusculas(c: char) -> Vec<char> {
+    let mut variantes = vec![c];
+    let conversiones: [Vec<char>; 2] = [c.to_lowercase().collect(), c.to_uppercase().collect()];
+    for conversion in conversiones {
+        if let [variante] = conversion[..] {
+            if !variantes.contains(&variante) {
+                variantes.push(variante);
+            }
+        }
+    }
+    variantes
+}
+
+fn clases_ignorando_mayusculas(clases_caracter: Vec<ClaseCaracter>) -> Vec<ClaseCaracter> {
+    let mut resultado = Vec::new();
+    for clase_caracter in clases_caracter {
+        match clase_caracter {
+            ClaseCaracter::Literal(l) => resultado.extend(
+                variantes_mayusculas(l)
+                    .into_iter()
+                    .map(ClaseCaracter::Literal),
+            ),
+            ClaseCaracter::Minuscula | ClaseCaracter::Mayuscula => {
+                resultado.push(ClaseCaracter::Alfabetico)
+            }
+            ClaseCaracter::Propiedad(propiedad) => {
+                resultado.push(ClaseCaracter::Propiedad(propiedad.ignorar_mayusculas()))
+            }
+            ClaseCaracter::Equivalencia(base) => resultado.extend(
+                variantes_mayusculas(base)
+                    .into_iter()
+                    .map(ClaseCaracter::Equivalencia),
+            ),
+            clase => resultado.push(clase),
+        }
     }
+    resultado
 }
 
-fn matchear_con_comodin(valor: &str) -> usize {
-    if let Some(c) = valor.chars().next() {
-        c.len_utf8()
+fn matchear_con_literal(literal: char, valor: &[u8]) -> usize {
+    let mut buffer = [0; 4];
+    let literal_utf8 = literal.encode_utf8(&mut buffer).as_bytes();
+    if valor.starts_with(literal_utf8) {
+        literal_utf8.len()
     } else {
         0
     }
 }
 
+fn matchear_con_comodin(valor: &[u8]) -> usize {
+    match decodificar_primer_caracter(valor) {
+        Some(Ok(c)) => c.len_utf8(),
+        Some(Err(_)) => 1,
+        None => 0,
+    }
+}
+
 fn matchear_con_anclaje() -> usize {
     0
 }
 
-fn matchear_con_expresion_bracket(clases_caracter: &Vec<ClaseCaracter>, valor: &str) -> usize {
+fn matchear_con_expresion_bracket(clases_caracter: &Vec<ClaseCaracter>, valor: &[u8]) -> usize {
     let mut matcheo = 0;
     for clase_caracter in clases_caracter {
         if clase_caracter.matchea(valor) > 0 {
@@ -82,19 +287,21 @@ fn matchear_con_expresion_bracket(clases_caracter: &Vec<ClaseCaracter>, valor: &
 
 fn matchear_con_expresion_bracket_negada(
     clases_caracter: &Vec<ClaseCaracter>,
-    valor: &str,
+    valor: &[u8],
 ) -> usize {
-    if let Some(c) = valor.chars().next() {
-        let mut matcheo = c.len_utf8();
-        for clase_caracter in clases_caracter {
-            if clase_caracter.matchea(valor) > 0 {
-                matcheo = 0;
-                break;
+    match decodificar_primer_caracter(valor) {
+        Some(Ok(c)) => {
+            let mut matcheo = c.len_utf8();
+            for clase_caracter in clases_caracter {
+                if clase_caracter.matchea(valor) > 0 {
+                    matcheo = 0;
+                    break;
+                }
             }
+            matcheo
         }
-        matcheo
-    } else {
-        0
+        Some(Err(_)) => 1,
+        None => 0,
     }
 }
 
@@ -104,8 +311,8 @@ mod tests {
 
     #[test]
     fn literal_matchea_con_valor() {
-        let valor_que_matchea = "lddf";
-        let valor_que_no_matchea = "ddf";
+        let valor_que_matchea = b"lddf";
+        let valor_que_no_matchea = b"ddf";
         let literal = 'l';
 
         let result1 = matchear_con_literal(literal, valor_que_matchea);
@@ -117,7 +324,7 @@ mod tests {
 
     #[test]
     fn comodin_matchea_con_todo() {
-        let valor = "ddf";
+        let valor = b"ddf";
         let primera_letra_valor = 'd';
 
         let result = matchear_con_comodin(valor);
@@ -134,7 +341,7 @@ mod tests {
 
     #[test]
     fn bracket_expresion_matchea_con_valor() {
-        let valor = "#ddf";
+        let valor = b"#ddf";
         let primer_caracter_valor = '#';
         let clases_caracteres_que_matchean = vec![ClaseCaracter::Literal('#')];
         let clases_caracteres_que_no_matchean =
@@ -149,7 +356,7 @@ mod tests {
 
     #[test]
     fn bracket_expresion_negada_matchea_con_valor() {
-        let valor = "#ddf";
+        let valor = b"#ddf";
         let primer_caracter_valor = '#';
         let clases_caracteres_que_no_matchean = vec![ClaseCaracter::Literal('#')];
         let clases_caracteres_que_matchean =
@@ -161,4 +368,46 @@ mod tests {
         assert_eq!(result1, 0);
         assert_eq!(result2, primer_caracter_valor.len_utf8());
     }
+
+    #[test]
+    fn matcheos_con_bytes_invalidos() {
+        // "señal" en latin1
+        let valor = [b's', b'e', 0xF1, b'a', b'l'];
+        let clases_caracter = vec![ClaseCaracter::Alfabetico];
+
+        assert_eq!(1, matchear_con_comodin(&valor[2..]));
+        assert_eq!(0, matchear_con_literal('ñ', &valor[2..]));
+        assert_eq!(
+            0,
+            matchear_con_expresion_bracket(&clases_caracter, &valor[2..])
+        );
+        assert_eq!(
+            1,
+            matchear_con_expresion_bracket_negada(&clases_caracter, &valor[2..])
+        );
+        assert_eq!(
+            0,
+            matchear_con_expresion_bracket_negada(&clases_caracter, &valor[3..])
+        );
+    }
+
+    #[test]
+    fn valores_ignorando_mayusculas() {
+        let literal = ValorRegexToken::Literal('Ñ').ignorar_mayusculas();
+        let bracket = ValorRegexToken::ExpresionBracketNegada(vec![
+            ClaseCaracter::Literal('a'),
+            ClaseCaracter::Mayuscula,
+        ])
+        .ignorar_mayusculas();
+
+        assert_eq!(literal.matchea("ñ".as_bytes()), 2);
+        assert_eq!(literal.matchea("Ñ".as_bytes()), 2);
+        assert_eq!(
+            ValorRegexToken::Literal('1').ignorar_mayusculas(),
+            ValorRegexToken::Literal('1')
+        );
+        assert_eq!(bracket.matchea(b"A"), 0);
+        assert_eq!(bracket.matchea(b"b"), 0);
+        assert_eq!(bracket.matchea(b"1"), 1);
+    }
 }
@@ -1,4 +1,19 @@
-use egrep::egrep::egrep;
+use egrep::argumentos::Argumentos;
+use egrep::egrep::{egrep, egrep_bytes};
+use egrep::lectura_archivos::Linea;
+use egrep::reescritura::{aplicar_reescrituras, reescribir_bytes};
+use egrep::regex::Regex;
+use egrep::searcher::{EstadisticasBusqueda, OpcionesBusqueda, Searcher, Sink};
+use std::{fs, io, iter};
+
+/// Devuelve los argumentos de egrep que corresponden a las opciones y argumentos recibidos.
+fn nuevos_argumentos(args: &[&str]) -> Argumentos {
+    let args: Vec<String> = iter::once("egrep")
+        .chain(args.iter().copied())
+        .map(str::to_string)
+        .collect();
+    Argumentos::new(&args).unwrap()
+}
 
 #[test]
 fn test_1_grep() {
@@ -194,3 +209,446 @@ fn test_13_grep() {
 
     assert_eq!(lineas_que_matchean, lineas_que_deben_matchear);
 }
+
+#[test]
+fn test_archivos_con_y_sin_seleccion() {
+    let argumentos = nuevos_argumentos(&["-l", "es el fin$", "tests/data/tests_integracion.txt"]);
+
+    let resultados = egrep_bytes(&argumentos).unwrap();
+    let lineas = resultados[0].lineas.as_ref().unwrap();
+
+    // Se deja de leer el archivo en la primera línea seleccionada.
+    assert_eq!(lineas.len(), 1);
+    assert_eq!(lineas[0].linea.contenido, b"este es el fin".to_vec());
+}
+
+#[test]
+fn test_lineas_invertidas() {
+    let argumentos = nuevos_argumentos(&["-v", ".", "tests/data/tests_integracion.txt"]);
+
+    let resultados = egrep_bytes(&argumentos).unwrap();
+
+    assert!(resultados[0].lineas.as_ref().unwrap().is_empty());
+}
+
+#[test]
+fn test_maximo_de_lineas_con_contexto_posterior() {
+    let argumentos = nuevos_argumentos(&["-m2", "-A1", "^abc", "tests/data/tests_integracion.txt"]);
+
+    let resultados = egrep_bytes(&argumentos).unwrap();
+    let lineas: Vec<(usize, bool)> = resultados[0]
+        .lineas
+        .as_ref()
+        .unwrap()
+        .iter()
+        .map(|l| (l.numero, l.seleccionada))
+        .collect();
+
+    // Luego de la segunda línea seleccionada solo se lee su contexto posterior,
+    // aunque este también matchee.
+    assert_eq!(lineas, vec![(2, true), (3, false), (4, true), (5, false)]);
+}
+
+#[test]
+fn test_cadenas_fijas_con_coincidencias() {
+    let argumentos = nuevos_argumentos(&[
+        "-Fiow",
+        "-e",
+        "LA A",
+        "-e",
+        "vocal",
+        "tests/data/tests_integracion.txt",
+    ]);
+
+    let resultados = egrep_bytes(&argumentos).unwrap();
+    let coincidencias: Vec<Vec<(usize, usize)>> = resultados[0]
+        .lineas
+        .as_ref()
+        .unwrap()
+        .iter()
+        .map(|l| l.coincidencias.clone())
+        .collect();
+
+    assert_eq!(
+        coincidencias,
+        vec![
+            vec![(0, 4), (12, 17)],
+            vec![(12, 17)],
+            vec![(12, 17)],
+            vec![(15, 20)],
+            vec![(0, 4), (15, 20)],
+        ]
+    );
+}
+
+#[test]
+fn test_miles_de_patrones_de_archivo() {
+    let directorio = std::env::temp_dir().join(format!("egrep-patrones-{}", std::process::id()));
+    fs::create_dir_all(&directorio).unwrap();
+    // Palabras pseudoaleatorias de 8 letras, reproducibles.
+    let mut semilla: u64 = 12345;
+    let mut palabra = || -> String {
+        (0..8)
+            .map(|_| {
+                semilla = semilla.wrapping_mul(6364136223846793005).wrapping_add(1);
+                char::from(b'a' + ((semilla >> 33) % 26) as u8)
+            })
+            .collect()
+    };
+    let patrones: Vec<String> = (0..3000).map(|_| palabra()).collect();
+    let mut lineas: Vec<String> = (0..5000)
+        .map(|_| (0..8).map(|_| palabra()).collect::<Vec<_>>().join(" "))
+        .collect();
+    lineas[1234] = format!("x{}x", patrones[2999]);
+    lineas[4321] = format!("{}^{}", patrones[0].to_uppercase(), patrones[1500]);
+    fs::write(directorio.join("patrones.txt"), patrones.join("\n")).unwrap();
+    fs::write(directorio.join("texto.txt"), lineas.join("\n")).unwrap();
+    let opcion_patrones = format!("-f{}", directorio.join("patrones.txt").display());
+    let texto = directorio.join("texto.txt");
+    let argumentos = nuevos_argumentos(&["-i", &opcion_patrones, texto.to_str().unwrap()]);
+
+    let resultados = egrep_bytes(&argumentos).unwrap();
+    fs::remove_dir_all(&directorio).unwrap();
+
+    let numeros: Vec<usize> = resultados[0]
+        .lineas
+        .as_ref()
+        .unwrap()
+        .iter()
+        .map(|l| l.numero)
+        .collect();
+    assert_eq!(numeros, vec![1235, 4322]);
+}
+
+#[test]
+fn test_sintaxis_basica_con_referencias() {
+    let argumentos = nuevos_argumentos(&["-xG", r"\(a\)\1*", "tests/data/tests_integracion.txt"]);
+
+    let resultados = egrep_bytes(&argumentos).unwrap();
+    let lineas: Vec<Vec<u8>> = resultados[0]
+        .lineas
+        .as_ref()
+        .unwrap()
+        .iter()
+        .map(|l| l.linea.contenido.clone())
+        .collect();
+
+    assert_eq!(lineas, vec![b"aa".to_vec(), b"aaaaaaaaaa".to_vec()]);
+}
+
+#[test]
+fn test_sintaxis_basica_con_anclaje_inicio_repetido() {
+    let archivo = std::env::temp_dir().join(format!("egrep-anclajes-{}", std::process::id()));
+    fs::write(&archivo, "^x\nx\nq\n").unwrap();
+    let ruta = archivo.to_str().unwrap();
+
+    for patron in ["^^x", r"q\|^^x"] {
+        let argumentos = nuevos_argumentos(&["-G", patron, ruta]);
+
+        let resultados = egrep_bytes(&argumentos).unwrap();
+        let lineas: Vec<Vec<u8>> = resultados[0]
+            .lineas
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|l| l.linea.contenido.clone())
+            .collect();
+
+        let esperadas = if patron == "^^x" {
+            vec![b"^x".to_vec()]
+        } else {
+            vec![b"^x".to_vec(), b"q".to_vec()]
+        };
+        assert_eq!(lineas, esperadas, "{patron}");
+    }
+    fs::remove_file(&archivo).unwrap();
+}
+
+#[test]
+fn test_sintaxis_perl_con_repeticiones_perezosas() {
+    let argumentos = nuevos_argumentos(&["-oP", "hola.*?a", "tests/data/tests_integracion.txt"]);
+
+    let resultados = egrep_bytes(&argumentos).unwrap();
+    let coincidencias: Vec<Vec<(usize, usize)>> = resultados[0]
+        .lineas
+        .as_ref()
+        .unwrap()
+        .iter()
+        .map(|l| l.coincidencias.clone())
+        .collect();
+
+    assert_eq!(coincidencias, vec![vec![(0, 7)], vec![(0, 7)]]);
+}
+
+#[test]
+fn test_reemplazo_con_grupos_con_nombre() {
+    let argumentos = nuevos_argumentos(&[
+        "-P",
+        "x(?<par>ab)(c*)",
+        "--replace",
+        "${2}-${par}",
+        "tests/data/tests_integracion.txt",
+    ]);
+
+    let resultados = egrep_bytes(&argumentos).unwrap();
+    let lineas: Vec<String> = resultados[0]
+        .lineas
+        .as_ref()
+        .unwrap()
+        .iter()
+        .map(|l| String::from_utf8_lossy(&l.linea.contenido).to_string())
+        .collect();
+
+    assert_eq!(lineas, vec!["-abxcdx", "-abdx"]);
+}
+
+#[test]
+fn test_reemplazo_ignora_las_coincidencias_vacias_a_diferencia_de_replace_all() {
+    let argumentos = nuevos_argumentos(&[
+        "-m2",
+        "x*",
+        "--replace",
+        "-",
+        "tests/data/tests_integracion.txt",
+    ]);
+
+    let resultados = egrep_bytes(&argumentos).unwrap();
+    let lineas: Vec<String> = resultados[0]
+        .lineas
+        .as_ref()
+        .unwrap()
+        .iter()
+        .map(|l| String::from_utf8_lossy(&l.linea.contenido).to_string())
+        .collect();
+
+    assert_eq!(lineas, vec!["ab-cd", "abcd"]);
+    let regex = Regex::new("x*").unwrap();
+    assert_eq!(regex.replace_all("abxcd", "-"), "-a-b-c-d-");
+    assert_eq!(regex.replace_all("abcd", "-"), "-a-b-c-d-");
+}
+
+#[test]
+fn test_reescritura_en_el_lugar_con_respaldo() {
+    let directorio = std::env::temp_dir().join(format!("egrep-reescritura-{}", std::process::id()));
+    fs::create_dir_all(&directorio).unwrap();
+    let archivo = directorio.join("datos.txt");
+    fs::write(&archivo, "clave=1\notra linea\nclave=22").unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&archivo, fs::Permissions::from_mode(0o640)).unwrap();
+    }
+    let ruta = archivo.to_str().unwrap();
+    let argumentos = nuevos_argumentos(&[
+        "clave=([[:digit:]]+)",
+        "--replace",
+        "valor: $1",
+        "--in-place",
+        "--backup=.orig",
+        ruta,
+    ]);
+
+    let codigo = aplicar_reescrituras(reescribir_bytes(&argumentos), &argumentos);
+
+    assert_eq!(codigo, 0);
+    assert_eq!(
+        fs::read_to_string(&archivo).unwrap(),
+        "valor: 1\notra linea\nvalor: 22"
+    );
+    assert_eq!(
+        fs::read_to_string(directorio.join("datos.txt.orig")).unwrap(),
+        "clave=1\notra linea\nclave=22"
+    );
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let permisos = fs::metadata(&archivo).unwrap().permissions();
+        assert_eq!(permisos.mode() & 0o777, 0o640);
+    }
+    assert_eq!(
+        aplicar_reescrituras(reescribir_bytes(&argumentos), &argumentos),
+        1
+    );
+    assert_eq!(fs::read_dir(&directorio).unwrap().count(), 2);
+    fs::remove_dir_all(&directorio).unwrap();
+}
+
+#[test]
+fn test_reescritura_en_el_lugar_conserva_la_codificacion() {
+    let directorio =
+        std::env::temp_dir().join(format!("egrep-reescritura-cod-{}", std::process::id()));
+    fs::create_dir_all(&directorio).unwrap();
+    let utf16 = directorio.join("utf16.txt");
+    let con_bom = directorio.join("bom.txt");
+    let codificar_utf16 = |texto: &str| -> Vec<u8> {
+        [0xFF, 0xFE]
+            .into_iter()
+            .chain(texto.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect()
+    };
+    fs::write(&utf16, codificar_utf16("año=1\nclave=año\n")).unwrap();
+    fs::write(&con_bom, b"\xEF\xBB\xBFa\xF1o=1\n").unwrap();
+    let argumentos = nuevos_argumentos(&[
+        "^a.o",
+        "--replace",
+        "ciclo",
+        "--in-place",
+        utf16.to_str().unwrap(),
+        con_bom.to_str().unwrap(),
+    ]);
+
+    let codigo = aplicar_reescrituras(reescribir_bytes(&argumentos), &argumentos);
+
+    assert_eq!(codigo, 0);
+    assert_eq!(
+        fs::read(&utf16).unwrap(),
+        codificar_utf16("ciclo=1\nclave=año\n")
+    );
+    assert_eq!(fs::read(&con_bom).unwrap(), b"\xEF\xBB\xBFciclo=1\n");
+
+    fs::write(&con_bom, b"a\xF1o\n").unwrap();
+    let argumentos = nuevos_argumentos(&[
+        "--encoding=latin1",
+        "a.o",
+        "--replace",
+        "€",
+        "--in-place",
+        con_bom.to_str().unwrap(),
+    ]);
+
+    let codigo = aplicar_reescrituras(reescribir_bytes(&argumentos), &argumentos);
+
+    assert_eq!(codigo, 2);
+    assert_eq!(fs::read(&con_bom).unwrap(), b"a\xF1o\n");
+    fs::remove_dir_all(&directorio).unwrap();
+}
+
+#[test]
+fn test_multilinea_selecciona_las_lineas_tocadas() {
+    let argumentos_con = |opciones: &[&str]| {
+        let args: Vec<&str> =
+            [opciones, &["abc\\ndef", "tests/data/tests_integracion.txt"]].concat();
+        nuevos_argumentos(&args)
+    };
+
+    let resultados = egrep_bytes(&argumentos_con(&["-U"])).unwrap();
+    let numeros: Vec<usize> = resultados[0]
+        .lineas
+        .as_ref()
+        .unwrap()
+        .iter()
+        .map(|l| l.numero)
+        .collect();
+
+    assert_eq!(numeros, vec![16, 17]);
+
+    let resultados = egrep_bytes(&argumentos_con(&["-Uo"])).unwrap();
+    let lineas = resultados[0].lineas.as_ref().unwrap();
+
+    assert_eq!(lineas.len(), 1);
+    assert_eq!(lineas[0].numero, 16);
+    assert_eq!(lineas[0].linea.contenido, b"abc\ndef\n");
+    assert_eq!(lineas[0].coincidencias, vec![(0, 7)]);
+
+    let resultados = egrep_bytes(&argumentos_con(&[])).unwrap();
+
+    assert!(resultados[0].lineas.as_ref().unwrap().is_empty());
+}
+
+#[test]
+fn test_multilinea_con_delimitacion_inversion_y_registros() {
+    let directorio = std::env::temp_dir().join(format!("egrep-multilinea-{}", std::process::id()));
+    fs::create_dir_all(&directorio).unwrap();
+    let lineas = directorio.join("lineas.txt");
+    let registros = directorio.join("registros.txt");
+    fs::write(&lineas, "foo\nbar\nfoo\nxfoo\nbar baz\n").unwrap();
+    fs::write(&registros, "a\0b\0a\nb\0").unwrap();
+    let numeros = |opciones: &[&str], patron: &str, archivo: &std::path::Path| {
+        let args: Vec<&str> = [opciones, &[patron, archivo.to_str().unwrap()]].concat();
+        let resultados = egrep_bytes(&nuevos_argumentos(&args)).unwrap();
+        resultados[0]
+            .lineas
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|l| l.numero)
+            .collect::<Vec<usize>>()
+    };
+
+    assert_eq!(numeros(&["-Ux"], "foo", &lineas), vec![1, 3]);
+    assert_eq!(numeros(&["-Ux"], "foo\\nbar", &lineas), vec![1, 2]);
+    assert_eq!(numeros(&["-Ux"], "bar", &lineas), vec![2]);
+    assert_eq!(numeros(&["-Uw"], "foo\\nbar", &lineas), vec![1, 2]);
+    assert_eq!(numeros(&["-Uw"], "bar", &lineas), vec![2, 5]);
+    assert_eq!(numeros(&["-Uv"], "foo\\nbar", &lineas), vec![3]);
+    assert_eq!(numeros(&["-Uxv"], "foo", &lineas), vec![2, 4, 5]);
+    assert_eq!(numeros(&["-Uz"], "a.b", &registros), vec![3]);
+    assert_eq!(numeros(&["-Uz"], "^b$", &registros), vec![2]);
+    assert_eq!(numeros(&["-Uzx"], "a", &registros), vec![1]);
+    assert_eq!(numeros(&["-Uz"], "a\\nb$", &registros), vec![3]);
+    fs::remove_dir_all(&directorio).unwrap();
+}
+
+/// Receptor que guarda el número y el contenido de cada línea seleccionada, y sus estadísticas.
+#[derive(Default)]
+struct LineasSeleccionadas {
+    lineas: Vec<(usize, String)>,
+    estadisticas: Option<EstadisticasBusqueda>,
+}
+
+impl Sink for LineasSeleccionadas {
+    fn matched(
+        &mut self,
+        numero_linea: usize,
+        _desplazamiento: usize,
+        linea: &Linea,
+        _coincidencias: &[(usize, usize)],
+    ) -> io::Result<bool> {
+        let contenido = String::from_utf8_lossy(&linea.contenido).to_string();
+        self.lineas.push((numero_linea, contenido));
+        Ok(true)
+    }
+
+    fn finish(&mut self, estadisticas: &EstadisticasBusqueda) -> io::Result<()> {
+        self.estadisticas = Some(*estadisticas);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_busqueda_con_receptor_propio() {
+    let opciones = OpcionesBusqueda {
+        contexto_posterior: 1,
+        con_coincidencias: true,
+        ..OpcionesBusqueda::default()
+    };
+    let searcher = Searcher::new(Regex::new("es el fin$").unwrap(), opciones);
+    let mut receptor = LineasSeleccionadas::default();
+
+    let estadisticas = searcher
+        .search_path("tests/data/tests_integracion.txt", &mut receptor)
+        .unwrap();
+
+    // Las líneas de contexto se ignoran, pero se cuentan en las estadísticas.
+    assert_eq!(receptor.lineas.len(), 1);
+    assert_eq!(receptor.lineas[0].1, "este es el fin");
+    assert_eq!(estadisticas.lineas_seleccionadas, 1);
+    assert_eq!(estadisticas.coincidencias, Some(1));
+    assert_eq!(receptor.estadisticas, Some(estadisticas));
+    assert!(searcher
+        .search_path("tests/data/no_existe.txt", &mut receptor)
+        .is_err());
+}
+
+#[test]
+fn test_utf16_con_bom_big_endian() {
+    let argumentos =
+        nuevos_argumentos(&["--encoding", "utf-16", "^hi$", "tests/data/utf16be_bom.txt"]);
+
+    let resultados = egrep_bytes(&argumentos).unwrap();
+    let lineas = resultados[0].lineas.as_ref().unwrap();
+
+    assert_eq!(lineas.len(), 1);
+    assert_eq!(lineas[0].numero, 2);
+    assert_eq!(lineas[0].linea.contenido, b"hi");
+}
@@ -1,4 +1,7 @@
 use egrep::regex::Regex;
+use egrep::regex_builder::RegexBuilder;
+use egrep::regex_set::RegexSet;
+use egrep::syntax::Syntax;
 
 #[test]
 fn matcheo_con_expresion_vacia() {
@@ -203,3 +206,66 @@ fn matcheo_con_comodin_y_repeticiones() {
 
     assert!(!regex.matchea("eeeeeeeee")); // 9
 }
+
+#[test]
+fn matcheo_con_bytes_no_utf8() {
+    let regex = Regex::new("se.al").unwrap();
+    let regex_negada = Regex::new("se[^a]al").unwrap();
+    let regex_literal = Regex::new("señal").unwrap();
+
+    // "señal" en latin1
+    let latin1 = [b's', b'e', 0xF1, b'a', b'l'];
+
+    assert!(regex.is_match_bytes(&latin1));
+    assert!(regex_negada.is_match_bytes(&latin1));
+    assert!(!regex_literal.is_match_bytes(&latin1));
+    assert!(regex_literal.is_match_bytes("señal".as_bytes()));
+}
+
+#[test]
+fn conjunto_de_regex_indica_patrones_que_matchean() {
+    let conjunto = RegexSet::new(["error", "^WARN", "timeout$", "[[:digit:]]{3}"]).unwrap();
+
+    assert_eq!(
+        conjunto.patrones_que_matchean("WARN: error 500 timeout"),
+        vec![0, 1, 2, 3]
+    );
+    assert_eq!(
+        conjunto.patrones_que_matchean("info: ok"),
+        Vec::<usize>::new()
+    );
+    assert_eq!(
+        conjunto.patrones_que_matchean("conexión con timeout"),
+        vec![2]
+    );
+    assert_eq!(conjunto.patrones_que_matchean_bytes(b"\xff error"), vec![0]);
+}
+
+#[test]
+fn clases_posix_equivalencias_y_simbolos_intercalados() {
+    let regex = Regex::new("^[[:xdigit:]]+[[:blank:]][[=e=]]l[[.hyphen.]]").unwrap();
+
+    assert!(regex.matchea("c0fe\tél-"));
+    assert!(regex.matchea("BEEF el-"));
+    assert!(!regex.matchea("c0fg él-"));
+    assert!(!regex.matchea("c0fe\nel-"));
+    assert!(Regex::new("[[:graph:]][[:print:]][[:cntrl:]][[:word:]]")
+        .unwrap()
+        .matchea("! \u{7}_"));
+}
+
+#[test]
+fn grupo_opcional_que_matchea_vacio_guarda_su_captura() {
+    for patron in [r"(a*)?\1x", r"(a?)?\1x", r"(a*)*\1x"] {
+        let regex = Regex::new(patron).unwrap();
+
+        assert!(regex.matchea("xx"), "{}", patron);
+        assert!(regex.matchea("aaax"), "{}", patron);
+        assert!(!regex.matchea("yy"), "{}", patron);
+    }
+    let regex = RegexBuilder::new(r"\(a*\)\{0,1\}\1x")
+        .syntax(Syntax::Bre)
+        .build()
+        .unwrap();
+    assert!(regex.matchea("xx"));
+}